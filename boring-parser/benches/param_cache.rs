@@ -0,0 +1,43 @@
+//! パラメータキャッシュの読み込み時間の計測
+//!
+//! `cargo bench -p boring-parser --bench param_cache` で、TKY2JGD形式のテキストのパースと
+//! キャッシュのバイト列の読み込みにかかる時間を表示する。
+
+use std::hint::black_box;
+use std::time::{Duration, Instant};
+
+use boring_parser::param_cache::ParamCache;
+use boring_parser::transform::EarthquakeCatalogue;
+
+/// パラメータの行数（TKY2JGD.parは約39万行）
+const LINES: u32 = 400_000;
+/// 計測の繰り返し回数
+const ROUNDS: u32 = 5;
+
+/// 繰り返した中で最短の時間
+fn fastest<T>(mut f: impl FnMut() -> T) -> Duration {
+    (0..ROUNDS)
+        .map(|_| {
+            let start = Instant::now();
+            black_box(f());
+            start.elapsed()
+        })
+        .min()
+        .unwrap_or_default()
+}
+
+fn main() {
+    let mut tky = "header\n".repeat(2);
+    for i in 0..LINES {
+        tky.push_str(&format!("{:08} {:>9} {:>9}\n", 30000000 + i, "11.64926", "-11.65470"));
+    }
+    let catalogue = EarthquakeCatalogue::builtin();
+
+    let parse = fastest(|| ParamCache::from_sources("TKY2JGD.par", &tky, &[], &catalogue).unwrap());
+    let bytes = ParamCache::from_sources("TKY2JGD.par", &tky, &[], &catalogue).unwrap().to_bytes();
+    let load = fastest(|| ParamCache::from_bytes(bytes.clone()).unwrap());
+
+    println!("{} 行", LINES);
+    println!("テキストのパース:     {:?}", parse);
+    println!("キャッシュの読み込み: {:?}（バイト列の複製を含む）", load);
+}
@@ -0,0 +1,225 @@
+//! 調査位置の水平精度モジュール
+//!
+//! 経度緯度情報の `取得方法説明` から、調査位置の水平方向の不確かさ（半径、m）を推定する。
+//!
+//! # コードを解釈しない理由
+//! DTDでは `取得方法コード`・`読取精度コード` はどちらも値の定めのない `#PCDATA` で、
+//! 出典のあるコード表がない。コードの意味を推測すると、地形図から読み取った位置を
+//! 測量した位置として小さな不確かさで報告するおそれがあるため、コードは記載どおりの
+//! 値を保持するだけとし、取得方法と縮尺は `取得方法説明`（"1,000分の1地形図を0.1mm単位で
+//! 読み取り" など）からのみ判定する。
+//!
+//! # 不確かさの推定
+//! - 測量（説明に "測量"・"GNSS"・"GPS"・"トータルステーション" を含む）: 0.1m
+//! - 地形図の読み取り: 縮尺分母 × 0.7mm（公共測量の地図情報レベルの水平位置の標準偏差、
+//!   1/2,500で1.75m、1/25,000で17.5m）。縮尺の記載がない場合は推定しない
+//! - 判定できない説明・説明がない場合: 推定しない
+
+use serde::Serialize;
+
+/// 測量による位置の不確かさ (m)
+const SURVEY_UNCERTAINTY: f64 = 0.1;
+/// 地形図上の位置の標準偏差 (m、図上0.7mm)
+const MAP_UNCERTAINTY_PER_SCALE: f64 = 0.0007;
+/// 地形図からの読み取りを表す語
+const MAP_KEYWORDS: [&str; 3] = ["地形図", "地図", "計画図"];
+/// 測量を表す語
+const SURVEY_KEYWORDS: [&str; 4] = ["測量", "GNSS", "GPS", "トータルステーション"];
+
+/// 位置の取得方法
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub enum AcquisitionMethod {
+    /// 測量（GNSS・トータルステーション等）
+    Survey,
+    /// 地形図からの読み取り（縮尺分母、記載がない場合はNone）
+    MapReading { scale: Option<u32> },
+    /// 判定できない取得方法（取得方法説明、説明がない場合は取得方法コード）
+    Other(String),
+}
+
+impl AcquisitionMethod {
+    /// 取得方法説明から判定
+    ///
+    /// 縮尺の記載か地形図を表す語があれば地形図からの読み取り、測量を表す語があれば測量とする。
+    pub fn from_description(description: &str) -> Self {
+        let scale = map_scale(description);
+        if scale.is_some() || MAP_KEYWORDS.iter().any(|k| description.contains(k)) {
+            AcquisitionMethod::MapReading { scale }
+        } else if SURVEY_KEYWORDS.iter().any(|k| description.contains(k)) {
+            AcquisitionMethod::Survey
+        } else {
+            AcquisitionMethod::Other(description.trim().to_string())
+        }
+    }
+
+    /// 取得方法による不確かさ (m)
+    pub fn uncertainty(&self) -> Option<f64> {
+        match self {
+            AcquisitionMethod::Survey => Some(SURVEY_UNCERTAINTY),
+            AcquisitionMethod::MapReading { scale } => {
+                scale.map(|s| s as f64 * MAP_UNCERTAINTY_PER_SCALE)
+            }
+            AcquisitionMethod::Other(_) => None,
+        }
+    }
+}
+
+/// 調査位置の水平精度
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct HorizontalAccuracy {
+    /// 取得方法
+    pub method: Option<AcquisitionMethod>,
+    /// 取得方法コード（記載どおり）
+    pub method_code: Option<String>,
+    /// 読取精度コード（記載どおり）
+    pub precision_code: Option<String>,
+    /// 推定した水平方向の不確かさ（半径、m）。取得方法と縮尺がわからない場合はNone
+    pub uncertainty_m: Option<f64>,
+}
+
+impl HorizontalAccuracy {
+    /// 取得方法コード・取得方法説明・読取精度コードから作成
+    ///
+    /// 取得方法は `取得方法説明` からのみ判定し、説明がなくコードだけある場合は
+    /// [`AcquisitionMethod::Other`] とする。
+    ///
+    /// # Example
+    /// ```ignore
+    /// let accuracy = HorizontalAccuracy::new(Some("02"), Some("1,000分の1地形図"), Some("2"));
+    /// assert!(accuracy.uncertainty_m.unwrap() < 1.0);
+    /// ```
+    pub fn new(
+        method_code: Option<&str>,
+        description: Option<&str>,
+        precision_code: Option<&str>,
+    ) -> Self {
+        let method = match (present(description), present(method_code)) {
+            (Some(description), _) => Some(AcquisitionMethod::from_description(description)),
+            (None, Some(code)) => Some(AcquisitionMethod::Other(code.to_string())),
+            (None, None) => None,
+        };
+        let uncertainty_m = method.as_ref().and_then(|m| m.uncertainty());
+
+        Self {
+            method,
+            method_code: present(method_code).map(str::to_string),
+            precision_code: present(precision_code).map(str::to_string),
+            uncertainty_m,
+        }
+    }
+}
+
+/// 空白を除いた値（空の場合はNone）
+fn present(value: Option<&str>) -> Option<&str> {
+    value.map(str::trim).filter(|v| !v.is_empty())
+}
+
+/// 取得方法説明から地形図の縮尺分母を読む
+///
+/// "1,000分の1"、"1/2,500"、"1:25000" の形式に対応する（全角数字も可）。
+fn map_scale(description: &str) -> Option<u32> {
+    let text: String = description
+        .chars()
+        .map(|c| match c {
+            '０'..='９' => char::from_u32(c as u32 - '０' as u32 + '0' as u32).unwrap_or(c),
+            '，' | '、' => ',',
+            '／' => '/',
+            '：' => ':',
+            _ => c,
+        })
+        .collect();
+
+    // "N分の1"
+    if let Some(pos) = text.find("分の1") {
+        return trailing_number(&text[..pos]);
+    }
+    // "1/N" または "1:N"
+    for sep in ["1/", "1:"] {
+        if let Some(pos) = text.find(sep) {
+            return leading_number(&text[pos + sep.len()..]);
+        }
+    }
+    None
+}
+
+/// 文字列末尾の数値（桁区切りのカンマを含む）
+fn trailing_number(text: &str) -> Option<u32> {
+    let digits: String = text
+        .chars()
+        .rev()
+        .take_while(|c| c.is_ascii_digit() || *c == ',')
+        .filter(|c| c.is_ascii_digit())
+        .collect::<Vec<_>>()
+        .into_iter()
+        .rev()
+        .collect();
+    digits.parse().ok().filter(|&v| v > 1)
+}
+
+/// 文字列先頭の数値（桁区切りのカンマを含む）
+fn leading_number(text: &str) -> Option<u32> {
+    let digits: String = text
+        .chars()
+        .take_while(|c| c.is_ascii_digit() || *c == ',')
+        .filter(|c| c.is_ascii_digit())
+        .collect();
+    digits.parse().ok().filter(|&v| v > 1)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parser::sample;
+
+    #[test]
+    fn test_map_scale() {
+        assert_eq!(map_scale("1,000分の1地形図を0.1mm単位で読み取り"), Some(1000));
+        assert_eq!(map_scale("２５，０００分の１地形図"), Some(25000));
+        assert_eq!(map_scale("都市計画図 1/2,500"), Some(2500));
+        assert_eq!(map_scale("1:25000"), Some(25000));
+        assert_eq!(map_scale("地形図から読み取り"), None);
+    }
+
+    #[test]
+    fn test_from_description() {
+        // 1/1,000の地形図: 1000 × 0.7mm
+        let a = HorizontalAccuracy::new(Some("02"), Some("1,000分の1地形図を0.1mm単位で読み取り"), Some("2"));
+        assert_eq!(a.method, Some(AcquisitionMethod::MapReading { scale: Some(1000) }));
+        assert_eq!((a.method_code.as_deref(), a.precision_code.as_deref()), (Some("02"), Some("2")));
+        assert!((a.uncertainty_m.unwrap() - 0.7).abs() < 1e-9);
+
+        // 縮尺不明の地形図は推定しない
+        let a = HorizontalAccuracy::new(Some("02"), Some("地形図から読み取り"), None);
+        assert_eq!(a.method, Some(AcquisitionMethod::MapReading { scale: None }));
+        assert_eq!(a.uncertainty_m, None);
+
+        let a = HorizontalAccuracy::new(None, Some("GNSS測量"), None);
+        assert_eq!(a.method, Some(AcquisitionMethod::Survey));
+        assert_eq!(a.uncertainty_m, Some(0.1));
+
+        // コードだけでは取得方法を判定しない
+        let a = HorizontalAccuracy::new(Some("01"), None, Some("3"));
+        assert_eq!(a.method, Some(AcquisitionMethod::Other("01".to_string())));
+        assert_eq!(a.uncertainty_m, None);
+        let a = HorizontalAccuracy::new(Some("01"), Some("現地で確認"), None);
+        assert_eq!(a.method, Some(AcquisitionMethod::Other("現地で確認".to_string())));
+        assert_eq!(a.uncertainty_m, None);
+        assert_eq!(HorizontalAccuracy::new(None, None, None).method, None);
+    }
+
+    #[test]
+    fn test_sample_accuracy() {
+        use crate::coordinate::GeoCoordinate;
+
+        let boring = sample::<crate::boring_structs_210::Boring210>("BED0210.XML");
+        let a = boring.geo_location().horizontal_accuracy();
+        assert_eq!(a.method, Some(AcquisitionMethod::MapReading { scale: Some(1000) }));
+        assert!((a.uncertainty_m.unwrap() - 0.7).abs() < 1e-9);
+
+        // BED0110は取得方法説明がない
+        let boring = sample::<crate::boring_structs_110::Boring110>("BED0110.XML");
+        let a = boring.geo_location().horizontal_accuracy();
+        assert_eq!(a.method, Some(AcquisitionMethod::Other("02".to_string())));
+        assert_eq!(a.uncertainty_m, None);
+    }
+}
@@ -0,0 +1,231 @@
+//! 一括座標変換モジュール
+//!
+//! 多数の地点・ボーリングファイルをまとめて変換する。変換に失敗した地点は
+//! `None` にせず、地点ごとの `Result` としてエラーの内容を残す。
+//!
+//! `rayon` フィーチャーを有効にすると、`par_` で始まるメソッドで並列に変換できる。
+//! [`JgdTransformer`] は構築後は読み取り専用のため、全スレッドで1つを共有する。
+
+#[cfg(feature = "rayon")]
+use rayon::prelude::*;
+
+use crate::coordinate::GeoCoordinate;
+use crate::transform::{JgdTransformer, SurveyDate, SurveyStartDate, TransformError, TransformResult};
+
+/// 一括変換の1地点
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct BatchPoint<'a> {
+    /// 緯度 (度, 10進数)
+    pub latitude: f64,
+    /// 経度 (度, 10進数)
+    pub longitude: f64,
+    /// 標高 (m)
+    pub altitude: f64,
+    /// 測地系コード ("00"=Tokyo, "01"=JGD2000, "02"=JGD2011)
+    pub system_code: &'a str,
+    /// 調査日（地震補正の適用判定に使用）
+    pub survey_date: Option<SurveyStartDate>,
+}
+
+impl<'a> BatchPoint<'a> {
+    /// 標高0m、調査日なしの地点
+    pub fn new(latitude: f64, longitude: f64, system_code: &'a str) -> Self {
+        Self {
+            latitude,
+            longitude,
+            altitude: 0.0,
+            system_code,
+            survey_date: None,
+        }
+    }
+
+    /// 標高を指定
+    pub fn with_altitude(mut self, altitude: f64) -> Self {
+        self.altitude = altitude;
+        self
+    }
+
+    /// 調査日を指定
+    pub fn with_survey_date(mut self, survey_date: SurveyStartDate) -> Self {
+        self.survey_date = Some(survey_date);
+        self
+    }
+}
+
+/// 地点ごとの変換結果
+pub type BatchResult = Result<TransformResult, TransformError>;
+
+impl JgdTransformer {
+    /// 複数地点をJGD2011に変換
+    ///
+    /// 結果は入力と同じ順序で、地点ごとに成功・失敗を返す。
+    /// 調査日のない地点には変換器に設定した調査日を使う。
+    ///
+    /// # Example
+    /// ```ignore
+    /// let points = [
+    ///     BatchPoint::new(35.6895, 139.6917, "00"),
+    ///     BatchPoint::new(38.2682, 140.8694, "01").with_survey_date(SurveyStartDate::new(2005, 6, 1)),
+    /// ];
+    /// for (point, result) in points.iter().zip(transformer.transform_batch(&points)) {
+    ///     match result {
+    ///         Ok(r) => println!("({}, {})", r.latitude, r.longitude),
+    ///         Err(e) => eprintln!("{:?}: {}", point, e),
+    ///     }
+    /// }
+    /// ```
+    pub fn transform_batch(&self, points: &[BatchPoint<'_>]) -> Vec<BatchResult> {
+        points.iter().map(|p| self.transform_batch_point(p)).collect()
+    }
+
+    /// 複数地点をWGS84に変換
+    pub fn to_wgs84_batch(&self, points: &[BatchPoint<'_>]) -> Vec<BatchResult> {
+        points.iter().map(|p| self.to_wgs84_batch_point(p)).collect()
+    }
+
+    /// 地点を順に読みながらJGD2011に変換
+    ///
+    /// 全地点をメモリに載せずに、CSVなどから読み込んだ地点を逐次変換する。
+    pub fn transform_iter<'a, I>(&self, points: I) -> impl Iterator<Item = BatchResult>
+    where
+        I: IntoIterator<Item = BatchPoint<'a>>,
+    {
+        points.into_iter().map(|p| self.transform_batch_point(&p))
+    }
+
+    /// 地点を順に読みながらWGS84に変換
+    pub fn to_wgs84_iter<'a, I>(&self, points: I) -> impl Iterator<Item = BatchResult>
+    where
+        I: IntoIterator<Item = BatchPoint<'a>>,
+    {
+        points.into_iter().map(|p| self.to_wgs84_batch_point(&p))
+    }
+
+    /// 複数のボーリングファイルの調査位置をWGS84に変換
+    ///
+    /// 各ファイルの調査開始日で地震補正を選ぶ（[`JgdTransformer::document_to_wgs84`]）。
+    pub fn documents_to_wgs84<T>(&self, documents: &[T]) -> Vec<BatchResult>
+    where
+        T: GeoCoordinate + SurveyDate,
+    {
+        documents.iter().map(|d| self.document_to_wgs84(d)).collect()
+    }
+
+    /// 複数地点をJGD2011に並列変換
+    #[cfg(feature = "rayon")]
+    pub fn par_transform_batch(&self, points: &[BatchPoint<'_>]) -> Vec<BatchResult> {
+        points.par_iter().map(|p| self.transform_batch_point(p)).collect()
+    }
+
+    /// 複数地点をWGS84に並列変換
+    ///
+    /// projの変換器はスレッドごとに作成される。
+    #[cfg(feature = "rayon")]
+    pub fn par_to_wgs84_batch(&self, points: &[BatchPoint<'_>]) -> Vec<BatchResult> {
+        points.par_iter().map(|p| self.to_wgs84_batch_point(p)).collect()
+    }
+
+    /// 複数のボーリングファイルの調査位置をWGS84に並列変換
+    #[cfg(feature = "rayon")]
+    pub fn par_documents_to_wgs84<T>(&self, documents: &[T]) -> Vec<BatchResult>
+    where
+        T: GeoCoordinate + SurveyDate + Sync,
+    {
+        documents.par_iter().map(|d| self.document_to_wgs84(d)).collect()
+    }
+
+    /// 1地点をJGD2011に変換
+    fn transform_batch_point(&self, p: &BatchPoint<'_>) -> BatchResult {
+        let survey_date = p.survey_date.or(self.survey_date());
+        self.transform_on(p.latitude, p.longitude, p.altitude, p.system_code, survey_date)
+    }
+
+    /// 1地点をWGS84に変換
+    fn to_wgs84_batch_point(&self, p: &BatchPoint<'_>) -> BatchResult {
+        let result = self.transform_batch_point(p)?;
+        self.jgd2011_result_to_wgs84(result)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::param_cache::PatchSource;
+    use crate::transform::{EarthquakeCatalogue, StepKind};
+
+    /// 東京駅付近 (3次メッシュ 533946xx の南西4×4) に一様な補正量を持つTKY2JGD
+    fn transformer() -> JgdTransformer {
+        let mut tky = "header\nheader\n".to_string();
+        for row in 0..4 {
+            for col in 0..4 {
+                let code = 53394600 + row * 10 + col;
+                tky.push_str(&format!("{} {:>9} {:>9}\n", code, "11.60000", "-11.60000"));
+            }
+        }
+        let patches: [PatchSource<'_>; 0] = [];
+        JgdTransformer::from_sources("TKY2JGD.par", &tky, &patches, &EarthquakeCatalogue::builtin())
+            .unwrap()
+    }
+
+    #[test]
+    fn test_batch_preserves_errors_in_order() {
+        let transformer = transformer();
+        let points = [
+            BatchPoint::new(35.675, 139.77, "00"),
+            BatchPoint::new(43.06, 141.35, "00"), // TKY2JGDの範囲外
+            BatchPoint::new(35.675, 139.77, "02").with_altitude(12.5),
+        ];
+
+        let results = transformer.transform_batch(&points);
+        assert_eq!(results.len(), 3);
+
+        let tokyo = results[0].as_ref().unwrap();
+        assert!((tokyo.latitude - (35.675 + 11.6 / 3600.0)).abs() < 1e-9);
+        assert!((tokyo.longitude - (139.77 - 11.6 / 3600.0)).abs() < 1e-9);
+        assert_eq!(tokyo.applied_steps().next().unwrap().kind, StepKind::Tky2Jgd);
+
+        assert!(matches!(results[1], Err(TransformError::OutOfCoverage(_))));
+        assert_eq!(results[2].as_ref().unwrap().altitude, 12.5);
+
+        // イテレータ版も同じ結果
+        let iter: Vec<_> = transformer.transform_iter(points).collect();
+        assert_eq!(iter[0].as_ref().unwrap().latitude, tokyo.latitude);
+        assert!(iter[1].is_err());
+
+        let wgs84 = transformer.to_wgs84_batch(&points);
+        assert_eq!(wgs84[0].as_ref().unwrap().steps.last().unwrap().kind, StepKind::Wgs84);
+        assert!(wgs84[1].is_err());
+    }
+
+    #[test]
+    fn test_transformer_is_shareable_between_threads() {
+        fn assert_send_sync<T: Send + Sync>() {}
+        assert_send_sync::<JgdTransformer>();
+
+        let transformer = transformer();
+        let points = [BatchPoint::new(35.675, 139.77, "00")];
+        let expected = transformer.to_wgs84_batch(&points)[0].as_ref().unwrap().latitude;
+        std::thread::scope(|s| {
+            for _ in 0..2 {
+                s.spawn(|| {
+                    let result = transformer.to_wgs84_batch(&points);
+                    assert_eq!(result[0].as_ref().unwrap().latitude, expected);
+                });
+            }
+        });
+    }
+
+    #[cfg(feature = "rayon")]
+    #[test]
+    fn test_parallel_matches_sequential() {
+        let transformer = transformer();
+        let points: Vec<_> = (0..100)
+            .map(|i| BatchPoint::new(35.67 + i as f64 * 1e-4, 139.77, if i % 10 == 0 { "99" } else { "00" }))
+            .collect();
+        let sequential = transformer.to_wgs84_batch(&points);
+        let parallel = transformer.par_to_wgs84_batch(&points);
+        for (s, p) in sequential.iter().zip(&parallel) {
+            assert_eq!(s.as_ref().ok().map(|r| r.latitude), p.as_ref().ok().map(|r| r.latitude));
+        }
+    }
+}
@@ -753,27 +753,28 @@ pub struct AlterationClassification210 {
 //      孔内水平載荷試験_試験方法?, 孔内水平載荷試験_載荷パターン?, 孔内水平載荷試験_初期圧,
 //      孔内水平載荷試験_降伏圧, 孔内水平載荷試験_変形係数, 孔内水平載荷試験_割線弾性係数?,
 //      孔内水平載荷試験_接線弾性係数?)>
+// v4.00では要素名が「孔内載荷試験_*」に変更されているため alias で受け付ける
 // ============================================================================
 
 #[derive(Debug, Serialize, Deserialize)]
 pub struct BoreholeHorizontalLoadTest210 {
-    #[serde(rename = "孔内水平載荷試験_試験深度")] // 必須
+    #[serde(rename = "孔内水平載荷試験_試験深度", alias = "孔内載荷試験_試験深度")] // 必須
     pub test_depth: Option<String>,
-    #[serde(rename = "孔内水平載荷試験_試験方法コード")] // 必須
+    #[serde(rename = "孔内水平載荷試験_試験方法コード", alias = "孔内載荷試験_試験方法コード")] // 必須
     pub test_method_code: Option<String>,
-    #[serde(default, rename = "孔内水平載荷試験_試験方法")] // オプション
+    #[serde(default, rename = "孔内水平載荷試験_試験方法", alias = "孔内載荷試験_試験方法")] // オプション
     pub test_method: Option<String>,
-    #[serde(default, rename = "孔内水平載荷試験_載荷パターン")] // オプション
+    #[serde(default, rename = "孔内水平載荷試験_載荷パターン", alias = "孔内載荷試験_載荷パターン")] // オプション
     pub load_pattern: Option<String>,
-    #[serde(rename = "孔内水平載荷試験_初期圧")] // 必須
+    #[serde(rename = "孔内水平載荷試験_初期圧", alias = "孔内載荷試験_初期圧")] // 必須
     pub initial_pressure: Option<String>,
-    #[serde(rename = "孔内水平載荷試験_降伏圧")] // 必須
+    #[serde(rename = "孔内水平載荷試験_降伏圧", alias = "孔内載荷試験_降伏圧")] // 必須
     pub yield_pressure: Option<String>,
-    #[serde(rename = "孔内水平載荷試験_変形係数")] // 必須
+    #[serde(rename = "孔内水平載荷試験_変形係数", alias = "孔内載荷試験_変形係数")] // 必須
     pub deformation_modulus: Option<String>,
-    #[serde(default, rename = "孔内水平載荷試験_割線弾性係数")] // オプション
+    #[serde(default, rename = "孔内水平載荷試験_割線弾性係数", alias = "孔内載荷試験_割線弾性係数")] // オプション
     pub secant_modulus: Option<String>,
-    #[serde(default, rename = "孔内水平載荷試験_接線弾性係数")] // オプション
+    #[serde(default, rename = "孔内水平載荷試験_接線弾性係数", alias = "孔内載荷試験_接線弾性係数")] // オプション
     pub tangent_modulus: Option<String>,
 }
 
@@ -393,6 +393,8 @@ pub struct BoreholeWaterLevel400 {
 
 #[derive(Debug, Serialize, Deserialize)]
 pub struct DrillingProcess400 {
+    #[serde(default, rename = "削孔工程_測定年月日")] // DTD v4.00: 削孔工程_測定年月日
+    pub measurement_date: Option<String>,
     #[serde(rename = "削孔工程_開始年月日")] // v3.00: 掘削工程_開始年月日
     pub start_date: Option<String>,
     #[serde(rename = "削孔工程_終了年月日")] // v3.00: 掘削工程_終了年月日
@@ -401,6 +403,8 @@ pub struct DrillingProcess400 {
     pub drilling_depth: Option<String>,
     #[serde(default, rename = "削孔工程_作業内容")] // v3.00: 掘削工程_作業内容
     pub work_content: Option<String>,
+    #[serde(default, rename = "削孔工程_ケーシング下端深度")] // オプション
+    pub casing_bottom_depth: Option<String>,
 }
 
 // ============================================================================
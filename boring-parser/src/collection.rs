@@ -0,0 +1,266 @@
+//! ボーリング集合の空間検索モジュール
+//!
+//! 多数のボーリングファイルの調査位置をJGD2011に変換してR木に登録し、
+//! 半径・緯度経度の範囲・近い順のk件で検索する。検索結果は登録したボーリングへの
+//! 参照と距離を返す。
+//!
+//! 距離は球面上の大円距離（半径6,371,008.8m）とする。半径検索と近傍検索は
+//! 地心直交座標（単位球）のR木で行うため、経度方向の縮みや系の境界を意識せずに使える。
+
+use rstar::primitives::GeomWithData;
+use rstar::{RTree, AABB};
+use serde::Serialize;
+
+use crate::coordinate::GeoCoordinate;
+use crate::transform::{JgdTransformer, SurveyDate, TransformError};
+
+/// 地球の平均半径 (m)
+const EARTH_RADIUS: f64 = 6_371_008.8;
+
+/// 地心直交座標（単位球）の点とボーリングの番号
+type SpherePoint = GeomWithData<[f64; 3], usize>;
+/// 経度緯度の点とボーリングの番号
+type LngLatPoint = GeomWithData<[f64; 2], usize>;
+
+/// 位置のわかったボーリング
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+pub struct LocatedBoring<'a, T> {
+    /// 登録順の番号
+    pub index: usize,
+    /// ボーリング
+    pub document: &'a T,
+    /// 緯度 (度, JGD2011)
+    pub latitude: f64,
+    /// 経度 (度, JGD2011)
+    pub longitude: f64,
+}
+
+/// 距離付きの検索結果
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+pub struct Neighbor<'a, T> {
+    /// ボーリング
+    pub boring: LocatedBoring<'a, T>,
+    /// 検索地点からの距離 (m)
+    pub distance: f64,
+}
+
+/// 空間検索できるボーリングの集合
+pub struct BoringCollection<T> {
+    documents: Vec<T>,
+    /// (緯度, 経度)（JGD2011、変換できなかった場合はNone）
+    locations: Vec<Option<(f64, f64)>>,
+    /// 変換できなかったボーリングの番号とエラー
+    errors: Vec<(usize, TransformError)>,
+    sphere: RTree<SpherePoint>,
+    lng_lat: RTree<LngLatPoint>,
+}
+
+impl<T: GeoCoordinate + SurveyDate> BoringCollection<T> {
+    /// ボーリングの調査位置をJGD2011に変換して登録
+    ///
+    /// 各ファイルの調査開始日で地震補正を選ぶ。変換できなかったボーリングも集合には含め、
+    /// 空間検索の対象から外す（[`BoringCollection::errors`]）。
+    ///
+    /// # Example
+    /// ```ignore
+    /// let collection = BoringCollection::new(borings, &transformer);
+    /// for n in collection.within_radius(35.6812, 139.7671, 200.0) {
+    ///     println!("{:.1}m: {:?}", n.distance, n.boring.document.title.boring_name);
+    /// }
+    /// ```
+    pub fn new(documents: Vec<T>, transformer: &JgdTransformer) -> Self {
+        let mut errors = Vec::new();
+        let locations = documents
+            .iter()
+            .enumerate()
+            .map(|(i, d)| match transformer.transform_document(d) {
+                Ok(r) => Some((r.latitude, r.longitude)),
+                Err(e) => {
+                    errors.push((i, e));
+                    None
+                }
+            })
+            .collect();
+        let mut collection = Self::from_locations(documents, locations);
+        collection.errors = errors;
+        collection
+    }
+}
+
+impl<T> BoringCollection<T> {
+    /// 変換済みの (緯度, 経度)（JGD2011）と組にして登録
+    ///
+    /// `locations` は `documents` と同じ順序・同じ長さとする（足りない分は位置なし）。
+    pub fn from_locations(documents: Vec<T>, mut locations: Vec<Option<(f64, f64)>>) -> Self {
+        locations.resize(documents.len(), None);
+        let located = || {
+            locations
+                .iter()
+                .enumerate()
+                .filter_map(|(i, l)| l.map(|(lat, lng)| (i, lat, lng)))
+        };
+        let sphere = RTree::bulk_load(
+            located()
+                .map(|(i, lat, lng)| SpherePoint::new(unit_vector(lat, lng), i))
+                .collect(),
+        );
+        let lng_lat = RTree::bulk_load(
+            located()
+                .map(|(i, lat, lng)| LngLatPoint::new([lng, lat], i))
+                .collect(),
+        );
+        Self {
+            documents,
+            locations,
+            errors: Vec::new(),
+            sphere,
+            lng_lat,
+        }
+    }
+
+    /// 登録したボーリングの数
+    pub fn len(&self) -> usize {
+        self.documents.len()
+    }
+
+    /// 空か
+    pub fn is_empty(&self) -> bool {
+        self.documents.is_empty()
+    }
+
+    /// 登録順のボーリング
+    pub fn documents(&self) -> &[T] {
+        &self.documents
+    }
+
+    /// 番号のボーリングと位置
+    pub fn get(&self, index: usize) -> Option<LocatedBoring<'_, T>> {
+        let (latitude, longitude) = (*self.locations.get(index)?)?;
+        Some(LocatedBoring {
+            index,
+            document: &self.documents[index],
+            latitude,
+            longitude,
+        })
+    }
+
+    /// 位置が変換できなかったボーリングの番号とエラー
+    pub fn errors(&self) -> &[(usize, TransformError)] {
+        &self.errors
+    }
+
+    /// 位置のないボーリング
+    pub fn unlocated(&self) -> impl Iterator<Item = (usize, &T)> {
+        self.locations
+            .iter()
+            .enumerate()
+            .filter(|(_, l)| l.is_none())
+            .map(|(i, _)| (i, &self.documents[i]))
+    }
+
+    /// 地点から半径 (m) 以内のボーリング（近い順）
+    pub fn within_radius(&self, latitude: f64, longitude: f64, radius: f64) -> Vec<Neighbor<'_, T>> {
+        // 大円距離の半径を単位球上の弦の長さに換算
+        let chord = 2.0 * (radius.min(std::f64::consts::PI * EARTH_RADIUS) / EARTH_RADIUS / 2.0).sin();
+        let mut neighbors: Vec<Neighbor<'_, T>> = self
+            .sphere
+            .locate_within_distance(unit_vector(latitude, longitude), chord * chord)
+            .filter_map(|p| self.neighbor(p.data, latitude, longitude))
+            .filter(|n| n.distance <= radius)
+            .collect();
+        neighbors.sort_by(|a, b| a.distance.total_cmp(&b.distance));
+        neighbors
+    }
+
+    /// 地点に近い順にk件のボーリング
+    pub fn nearest(&self, latitude: f64, longitude: f64, k: usize) -> Vec<Neighbor<'_, T>> {
+        self.sphere
+            .nearest_neighbor_iter(&unit_vector(latitude, longitude))
+            .take(k)
+            .filter_map(|p| self.neighbor(p.data, latitude, longitude))
+            .collect()
+    }
+
+    /// 緯度経度の範囲内のボーリング（登録順）
+    pub fn within_bounds(&self, south: f64, west: f64, north: f64, east: f64) -> Vec<LocatedBoring<'_, T>> {
+        let envelope = AABB::from_corners([west, south], [east, north]);
+        let mut found: Vec<usize> = self
+            .lng_lat
+            .locate_in_envelope(&envelope)
+            .map(|p| p.data)
+            .collect();
+        found.sort_unstable();
+        found.into_iter().filter_map(|i| self.get(i)).collect()
+    }
+
+    /// 番号のボーリングの地点からの距離付き結果
+    fn neighbor(&self, index: usize, latitude: f64, longitude: f64) -> Option<Neighbor<'_, T>> {
+        let boring = self.get(index)?;
+        let distance = great_circle_distance(latitude, longitude, boring.latitude, boring.longitude);
+        Some(Neighbor { boring, distance })
+    }
+}
+
+/// 緯度経度（度）の単位球上の地心直交座標
+fn unit_vector(latitude: f64, longitude: f64) -> [f64; 3] {
+    let (sin_lat, cos_lat) = latitude.to_radians().sin_cos();
+    let (sin_lng, cos_lng) = longitude.to_radians().sin_cos();
+    [cos_lat * cos_lng, cos_lat * sin_lng, sin_lat]
+}
+
+/// 2点間の大円距離 (m)
+pub fn great_circle_distance(lat1: f64, lng1: f64, lat2: f64, lng2: f64) -> f64 {
+    let d_lat = (lat2 - lat1).to_radians();
+    let d_lng = (lng2 - lng1).to_radians();
+    let a = (d_lat / 2.0).sin().powi(2)
+        + lat1.to_radians().cos() * lat2.to_radians().cos() * (d_lng / 2.0).sin().powi(2);
+    2.0 * EARTH_RADIUS * a.sqrt().min(1.0).asin()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// 東京駅から北へ約100m間隔の5地点と、位置のない1件
+    fn collection() -> BoringCollection<&'static str> {
+        let names = vec!["B-0", "B-1", "B-2", "B-3", "B-4", "B-X"];
+        let step = 100.0 / EARTH_RADIUS * 180.0 / std::f64::consts::PI;
+        let mut locations: Vec<_> = (0..5).map(|i| Some((35.6812 + step * i as f64, 139.7671))).collect();
+        locations.push(None);
+        BoringCollection::from_locations(names, locations)
+    }
+
+    #[test]
+    fn test_radius_and_nearest() {
+        let c = collection();
+        assert_eq!(c.len(), 6);
+        assert_eq!(c.unlocated().map(|(_, d)| *d).collect::<Vec<_>>(), vec!["B-X"]);
+
+        let found = c.within_radius(35.6812, 139.7671, 250.0);
+        let names: Vec<_> = found.iter().map(|n| *n.boring.document).collect();
+        assert_eq!(names, vec!["B-0", "B-1", "B-2"]);
+        assert!((found[2].distance - 200.0).abs() < 1e-6);
+
+        // B-3付近から近い順に3件
+        let nearest = c.nearest(35.6812 + 0.0026, 139.7671, 3);
+        let names: Vec<_> = nearest.iter().map(|n| *n.boring.document).collect();
+        assert_eq!(names, vec!["B-3", "B-2", "B-4"]);
+        assert!(nearest.windows(2).all(|w| w[0].distance <= w[1].distance));
+    }
+
+    #[test]
+    fn test_within_bounds() {
+        let c = collection();
+        let found = c.within_bounds(35.6820, 139.76, 35.6845, 139.77);
+        let names: Vec<_> = found.iter().map(|b| *b.document).collect();
+        assert_eq!(names, vec!["B-1", "B-2", "B-3"]);
+        assert!(c.within_bounds(35.0, 135.0, 35.1, 135.1).is_empty());
+    }
+
+    #[test]
+    fn test_great_circle_distance() {
+        // 東京駅〜大阪駅 約403km
+        let d = great_circle_distance(35.6812, 139.7671, 34.7025, 135.4959);
+        assert!((d - 403_000.0).abs() < 2_000.0);
+    }
+}
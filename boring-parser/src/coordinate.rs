@@ -3,7 +3,16 @@
 //! 各バージョンのボーリングデータ構造体に実装され、
 //! 統一的なインターフェースで緯度経度情報を取得できます。
 
-use crate::transform::JgdTransformer;
+use serde::Serialize;
+
+use crate::accuracy::HorizontalAccuracy;
+use crate::dms::{Axis, DmsError, ParsedDms};
+use crate::elevation::CollarElevation;
+use crate::geoid::GeoidModel;
+use crate::local_coordinate::HeightDatum;
+use crate::mesh_code::MeshCode;
+use crate::plane_rectangular::{PlaneRectangular, PlaneRectangularZone};
+use crate::transform::{JgdTransformer, SurveyDate, SurveyStartDate};
 
 /// 度分秒形式の座標
 #[derive(Debug, Clone, PartialEq)]
@@ -19,7 +28,8 @@ pub struct DmsCoordinate {
 impl DmsCoordinate {
     /// 度分秒を10進数（度）に変換
     ///
-    /// 度、分、秒のいずれかがNoneまたはパースできない場合はNoneを返す
+    /// 度、分、秒のいずれかがNoneまたはパースできない場合はNoneを返す。
+    /// 表記の揺れを正規化して読む場合は [`DmsCoordinate::parse_lenient`] を使う。
     pub fn to_decimal(&self) -> Option<f64> {
         let deg = self.degree.as_ref()?.parse::<f64>().ok()?;
         let min = self.minute.as_ref()?.parse::<f64>().ok()?;
@@ -80,6 +90,12 @@ pub struct GeoLocation {
     pub latitude: DmsCoordinate,
     /// 測地系コード
     pub geodetic_system: Option<String>,
+    /// 取得方法コード
+    pub acquisition_method: Option<String>,
+    /// 取得方法説明（v2.10以降）
+    pub acquisition_method_description: Option<String>,
+    /// 読取精度コード
+    pub reading_precision: Option<String>,
 }
 
 impl GeoLocation {
@@ -109,6 +125,68 @@ impl GeoLocation {
         transformer.to_wgs84(lat, lng, system_code)
     }
 
+    /// 平面直角座標系に変換した座標を取得
+    ///
+    /// Tokyo Datum/JGD2000 → JGD2011 の変換後、指定した系に投影する。
+    ///
+    /// # Arguments
+    /// * `transformer` - JgdTransformer インスタンス
+    /// * `zone` - 平面直角座標系の系
+    ///
+    /// # Example
+    /// ```ignore
+    /// let zone = PlaneRectangularZone::new(9).unwrap();
+    /// let p = location.to_plane_rectangular(&transformer, zone)?;
+    /// println!("{}系 X={:.3} Y={:.3}", p.zone.roman(), p.x, p.y);
+    /// ```
+    pub fn to_plane_rectangular(
+        &self,
+        transformer: &JgdTransformer,
+        zone: PlaneRectangularZone,
+    ) -> Option<PlaneRectangular> {
+        let (lat, lng) = self.jgd2011_lat_lng(transformer)?;
+        Some(PlaneRectangular::from_geographic(lat, lng, zone))
+    }
+
+    /// 系を自動選択して平面直角座標系に変換した座標を取得
+    ///
+    /// 都道府県（`SurveyPrefecture::prefecture()` で調査位置住所から取得できる）を
+    /// 指定するとその都道府県の適用系から選ぶ。
+    ///
+    /// # Example
+    /// ```ignore
+    /// let p = boring.geo_location().to_plane_rectangular_auto(&transformer, boring.prefecture())?;
+    /// ```
+    pub fn to_plane_rectangular_auto(
+        &self,
+        transformer: &JgdTransformer,
+        prefecture: Option<&str>,
+    ) -> Option<PlaneRectangular> {
+        let (lat, lng) = self.jgd2011_lat_lng(transformer)?;
+        let zone = PlaneRectangularZone::select(lat, lng, prefecture);
+        Some(PlaneRectangular::from_geographic(lat, lng, zone))
+    }
+
+    /// JGD2011に変換した座標の地域メッシュコードを取得
+    ///
+    /// # Example
+    /// ```ignore
+    /// let mesh = boring.geo_location().mesh_code(&transformer)?;
+    /// println!("{}", mesh.code(MeshLevel::Quarter));
+    /// ```
+    pub fn mesh_code(&self, transformer: &JgdTransformer) -> Option<MeshCode> {
+        let (lat, lng) = self.jgd2011_lat_lng(transformer)?;
+        MeshCode::from_lat_lng(lat, lng)
+    }
+
+    /// JGD2011の (緯度, 経度) を取得
+    fn jgd2011_lat_lng(&self, transformer: &JgdTransformer) -> Option<(f64, f64)> {
+        let lng = self.longitude.to_decimal()?;
+        let lat = self.latitude.to_decimal()?;
+        let system_code = self.geodetic_system.as_deref().unwrap_or("02");
+        transformer.transform_horizontal(lat, lng, system_code)
+    }
+
     /// 10進数座標を取得 (変換なし)
     ///
     /// # Returns
@@ -120,6 +198,35 @@ impl GeoLocation {
         Some((lng, lat))
     }
 
+    /// 度分秒を正規化して10進数座標を取得し、日本の範囲にあるか検証する
+    ///
+    /// [`GeoLocation::to_decimal`] や座標変換は記載どおりの度分秒だけを読むため、
+    /// 修正が必要な座標はこの結果を確認してから使う。
+    ///
+    /// # Returns
+    /// `Ok((経度, 緯度))` - それぞれの値と、読み取り時に行った修正
+    pub fn parse_lenient(&self) -> Result<(ParsedDms, ParsedDms), DmsError> {
+        let lng = self.longitude.parse_lenient(Axis::Longitude)?;
+        let lat = self.latitude.parse_lenient(Axis::Latitude)?;
+        Ok((lng, lat))
+    }
+
+    /// 取得方法説明から水平精度を推定
+    ///
+    /// # Example
+    /// ```ignore
+    /// if let Some(radius) = boring.geo_location().horizontal_accuracy().uncertainty_m {
+    ///     println!("±{:.1}m", radius);
+    /// }
+    /// ```
+    pub fn horizontal_accuracy(&self) -> HorizontalAccuracy {
+        HorizontalAccuracy::new(
+            self.acquisition_method.as_deref(),
+            self.acquisition_method_description.as_deref(),
+            self.reading_precision.as_deref(),
+        )
+    }
+
     /// 測地系を取得
     pub fn datum(&self) -> GeodeticDatum {
         self.geodetic_system
@@ -129,6 +236,104 @@ impl GeoLocation {
     }
 }
 
+/// 3次元の位置
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+pub struct Position3D {
+    /// 緯度（度）
+    pub latitude: f64,
+    /// 経度（度）
+    pub longitude: f64,
+    /// 標高（m）
+    pub orthometric_height: f64,
+    /// ジオイド高（m）
+    pub geoid_height: f64,
+    /// 楕円体高（m）
+    pub ellipsoidal_height: f64,
+}
+
+/// 孔口の3次元位置
+///
+/// WGS84の楕円体高はJGD2011（GRS80楕円体）の楕円体高と同じ値とする。
+/// GRS80とWGS84の楕円体の差は0.1mm程度で、孔口標高の精度に比べて無視できる。
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+pub struct CollarPosition {
+    /// JGD2011での位置
+    pub jgd2011: Position3D,
+    /// WGS84での位置
+    pub wgs84: Position3D,
+}
+
+/// 孔口標高を含む緯度経度情報
+#[derive(Debug, Clone, PartialEq)]
+pub struct GeoLocation3D {
+    /// 緯度経度
+    pub location: GeoLocation,
+    /// 孔口標高（m）
+    pub elevation: Option<String>,
+    /// 調査開始日（地震補正の適用判定に使用）
+    pub survey_date: Option<SurveyStartDate>,
+}
+
+impl GeoLocation3D {
+    /// 孔口標高（m）
+    ///
+    /// "T.P.+12.34" のような前置は除く。T.P.以外の基準面（D.L.など）の値はNone。
+    pub fn elevation_value(&self) -> Option<f64> {
+        CollarElevation::parse(self.elevation.as_deref())
+            .ok()
+            .filter(|c| c.datum == HeightDatum::TokyoPeil)
+            .map(|c| c.value)
+    }
+
+    /// JGD2011とWGS84での孔口の3次元位置を取得
+    ///
+    /// Tokyo Datum/JGD2000 → JGD2011 の変換で、地震・ジオイド改定による標高補正
+    /// （PatchJGD_H/HyokoRev）も孔口標高に適用する。その後、ジオイドモデルから
+    /// ジオイド高を求めて楕円体高を計算する。
+    ///
+    /// 調査開始日が取得できない場合は変換器に設定した調査日を使う。
+    ///
+    /// # Returns
+    /// `None` - 座標・孔口標高が取得できない場合、変換範囲外、ジオイドモデルの範囲外
+    ///
+    /// # Example
+    /// ```ignore
+    /// let geoid = GeoidModel::load("params/gsigeo2011_ver2_2.asc")?;
+    /// let collar = boring.geo_location_3d().to_collar_position(&transformer, &geoid)?;
+    /// println!("h = {:.3}m", collar.wgs84.ellipsoidal_height);
+    /// ```
+    pub fn to_collar_position(
+        &self,
+        transformer: &JgdTransformer,
+        geoid: &GeoidModel,
+    ) -> Option<CollarPosition> {
+        let (lng, lat) = self.location.to_decimal()?;
+        let elevation = self.elevation_value()?;
+        let system_code = self.location.geodetic_system.as_deref().unwrap_or("02");
+        let survey_date = self.survey_date.or(transformer.survey_date());
+
+        let result = transformer
+            .transform_on(lat, lng, elevation, system_code, survey_date)
+            .ok()?;
+        let geoid_height = geoid.undulation(result.latitude, result.longitude)?;
+        let jgd2011 = Position3D {
+            latitude: result.latitude,
+            longitude: result.longitude,
+            orthometric_height: result.altitude,
+            geoid_height,
+            ellipsoidal_height: result.altitude + geoid_height,
+        };
+
+        let (wgs_lng, wgs_lat) = transformer.to_wgs84(result.latitude, result.longitude, "02")?;
+        let wgs84 = Position3D {
+            latitude: wgs_lat,
+            longitude: wgs_lng,
+            ..jgd2011
+        };
+        Some(CollarPosition { jgd2011, wgs84 })
+    }
+}
+
 /// 緯度経度情報を取得するためのトレイト
 pub trait GeoCoordinate {
     /// GeoLocationを取得
@@ -157,6 +362,9 @@ impl GeoCoordinate for crate::boring_structs_400::Boring400 {
                 second: lng_lat.latitude_second.clone(),
             },
             geodetic_system: lng_lat.geodetic_system.clone(),
+            acquisition_method: lng_lat.acquisition_method_code.clone(),
+            acquisition_method_description: lng_lat.acquisition_method_description.clone(),
+            reading_precision: lng_lat.reading_precision_code.clone(),
         }
     }
 }
@@ -176,6 +384,9 @@ impl GeoCoordinate for crate::boring_structs_300::Boring300 {
                 second: lng_lat.latitude_second.clone(),
             },
             geodetic_system: lng_lat.geodetic_system.clone(),
+            acquisition_method: lng_lat.acquisition_method_code.clone(),
+            acquisition_method_description: lng_lat.acquisition_method_description.clone(),
+            reading_precision: lng_lat.reading_precision_code.clone(),
         }
     }
 }
@@ -195,6 +406,9 @@ impl GeoCoordinate for crate::boring_structs_200::Boring200 {
                 second: lng_lat.latitude_second.clone(),
             },
             geodetic_system: lng_lat.geodetic_system.clone(),
+            acquisition_method: lng_lat.acquisition_method_code.clone(),
+            acquisition_method_description: lng_lat.acquisition_method_description.clone(),
+            reading_precision: lng_lat.reading_precision_code.clone(),
         }
     }
 }
@@ -214,6 +428,9 @@ impl GeoCoordinate for crate::boring_structs_210::Boring210 {
                 second: lng_lat.latitude_second.clone(),
             },
             geodetic_system: lng_lat.geodetic_system.clone(),
+            acquisition_method: lng_lat.acquisition_method_code.clone(),
+            acquisition_method_description: lng_lat.acquisition_method_description.clone(),
+            reading_precision: lng_lat.reading_precision_code.clone(),
         }
     }
 }
@@ -233,10 +450,58 @@ impl GeoCoordinate for crate::boring_structs_110::Boring110 {
                 second: lng_lat.latitude_second.clone(),
             },
             geodetic_system: lng_lat.geodetic_system.clone(),
+            acquisition_method: lng_lat.acquisition_method_code.clone(),
+            acquisition_method_description: None,
+            reading_precision: lng_lat.reading_precision_code.clone(),
         }
     }
 }
 
+/// 孔口標高を含む3次元の位置情報を取得するためのトレイト
+pub trait GeoCoordinate3D: GeoCoordinate + SurveyDate {
+    /// 孔口標高（m）の文字列
+    fn collar_elevation(&self) -> Option<String>;
+
+    /// GeoLocation3Dを取得
+    fn geo_location_3d(&self) -> GeoLocation3D {
+        GeoLocation3D {
+            location: self.geo_location(),
+            elevation: self.collar_elevation(),
+            survey_date: self.survey_start_date(),
+        }
+    }
+}
+
+impl GeoCoordinate3D for crate::boring_structs_400::Boring400 {
+    fn collar_elevation(&self) -> Option<String> {
+        self.title.boring_basic_info.surface_elevation.clone()
+    }
+}
+
+impl GeoCoordinate3D for crate::boring_structs_300::Boring300 {
+    fn collar_elevation(&self) -> Option<String> {
+        self.title.boring_basic_info.elevation.clone()
+    }
+}
+
+impl GeoCoordinate3D for crate::boring_structs_200::Boring200 {
+    fn collar_elevation(&self) -> Option<String> {
+        self.title.boring_basic_info.elevation.clone()
+    }
+}
+
+impl GeoCoordinate3D for crate::boring_structs_210::Boring210 {
+    fn collar_elevation(&self) -> Option<String> {
+        self.title.boring_basic_info.elevation.clone()
+    }
+}
+
+impl GeoCoordinate3D for crate::boring_structs_110::Boring110 {
+    fn collar_elevation(&self) -> Option<String> {
+        self.title.boring_basic_info.elevation.clone()
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -273,6 +538,16 @@ mod tests {
             second: Some("0".to_string()),
         };
         assert!(dms.to_decimal().is_none());
+
+        // 表記の揺れは修正せず、修正内容を返す parse_lenient に任せる
+        let dms = DmsCoordinate {
+            degree: Some("１３５".to_string()),
+            minute: Some("45".to_string()),
+            second: Some("0".to_string()),
+        };
+        assert!(dms.to_decimal().is_none());
+        let parsed = dms.parse_lenient(Axis::Longitude).unwrap();
+        assert_eq!(parsed.repairs, vec![crate::dms::DmsRepair::FullWidth(crate::dms::DmsPart::Degree)]);
     }
 
     #[test]
@@ -312,6 +587,9 @@ mod tests {
                 second: Some("0".to_string()),
             },
             geodetic_system: Some("00".to_string()),
+            acquisition_method: None,
+            acquisition_method_description: None,
+            reading_precision: None,
         };
 
         let (lng, lat) = loc.to_wgs84(&transformer).unwrap();
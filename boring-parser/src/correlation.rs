@@ -0,0 +1,828 @@
+//! 地層の自動対比モジュール
+//!
+//! 地層岩体区分の地層岩体名（例: "Ac1"、"As2"、"Dg"、"沖積粘性土層"）を、別名表と
+//! 表記の規則で時代・土質・番号（[`StratumUnit`]）に正規化し、近くのボーリング同士で
+//! 地層名・土質・標高・N値の近さから同じ地層を対比する。対比した地層ごとに各孔の
+//! 上面・下面の標高（[`Horizon`]）を出力し、地層面のモデル化に使えるようにする。
+//!
+//! # 表記の規則
+//! - 記号: 1文字目が時代（A: 沖積、D: 洪積、T: 第三紀、B・F: 埋土・盛土）、
+//!   2文字目が土質（c・m: 粘性土、s: 砂質土、g: 礫質土、p・o: 有機質土、r: 岩）、続く数字が番号
+//! - 名称: 「沖積」「完新」「洪積」「更新」「第三紀」「埋土」「盛土」で時代を、
+//!   末尾に近い土質名で土質を、最初の数字で番号を読む
+//!
+//! 会社ごとの固有名（例: "○○層"）は [`StratumNormalizer::with_alias`] で記号に読み替える。
+//!
+//! # 対比の方法
+//! 孔口間の距離が [`StratigraphicCorrelator::search_radius`] 以内の2孔について、上から順に
+//! 並べた地層の組の得点を求め、地層の上下関係が入れ替わらない組み合わせのうち
+//! 得点の合計が最大となるものを選ぶ。選んだ組を連結したものを1つの地層面とする。
+//! 孔口の位置は同じ平面直角座標系で与える。
+//!
+//! 孔口標高の基準面（T.P.、孔口標高がない場合のG.L.など）が異なる孔同士は標高を
+//! 比べられないため対比しない。このため1つの地層面の点はすべて同じ基準面になる。
+
+use std::collections::HashMap;
+
+use serde::Serialize;
+use thiserror::Error;
+
+use crate::elevation::{datum_label, DepthElevations};
+use crate::layer::{SoilClass, SoilLayer, StratumLayers};
+use crate::local_coordinate::{normalize, HeightDatum};
+use crate::plane_rectangular::{PlaneRectangular, PlaneRectangularZone};
+use crate::section::BoreholeLog;
+use crate::transform::JgdTransformer;
+use crate::types::{csv_field, BoringHeader, DisjointSets};
+
+/// 地層の対比のエラー
+#[derive(Error, Debug, Clone, PartialEq)]
+pub enum CorrelationError {
+    #[error("対比条件 {0} が不正です（{1}）")]
+    InvalidOption(&'static str, f64),
+}
+
+/// 地質時代
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize)]
+pub enum GeologicAge {
+    /// 埋土・盛土
+    Fill,
+    /// 沖積層（完新統）
+    Alluvial,
+    /// 洪積層（更新統）
+    Diluvial,
+    /// 第三紀層
+    Tertiary,
+}
+
+impl GeologicAge {
+    /// 記号の1文字目
+    fn letter(&self) -> char {
+        match self {
+            Self::Fill => 'B',
+            Self::Alluvial => 'A',
+            Self::Diluvial => 'D',
+            Self::Tertiary => 'T',
+        }
+    }
+}
+
+/// 時代・土質・番号で表した地層
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize)]
+pub struct StratumUnit {
+    /// 時代
+    pub age: GeologicAge,
+    /// 土質
+    pub soil: Option<SoilClass>,
+    /// 番号（Ac1の1）
+    pub number: Option<u32>,
+}
+
+impl StratumUnit {
+    /// 地層岩体名を表記の規則で読む（読めない場合はNone）
+    ///
+    /// # Example
+    /// ```ignore
+    /// let unit = StratumUnit::parse("第2洪積砂質土層").unwrap();
+    /// assert_eq!(unit.key(), "Ds2");
+    /// ```
+    pub fn parse(name: &str) -> Option<Self> {
+        let text = normalize(name);
+        parse_code(&text).or_else(|| parse_description(&text))
+    }
+
+    /// 記号（例: "Ac1"）
+    pub fn key(&self) -> String {
+        let mut key = self.age.letter().to_string();
+        if let Some(soil) = self.soil {
+            key.push(soil.letter());
+        }
+        if let Some(number) = self.number {
+            key.push_str(&number.to_string());
+        }
+        key
+    }
+}
+
+/// 記号（"AC1"、"DG-2"、"BK" など、正規化済み）を読む
+fn parse_code(text: &str) -> Option<StratumUnit> {
+    let text = text.trim_end_matches("層群").trim_end_matches('層');
+    let mut chars = text.chars().filter(|c| !matches!(c, '-' | '_')).peekable();
+    let age = match chars.next()? {
+        'A' => GeologicAge::Alluvial,
+        'D' => GeologicAge::Diluvial,
+        'T' => GeologicAge::Tertiary,
+        'B' | 'F' => GeologicAge::Fill,
+        _ => return None,
+    };
+    let soil = chars.peek().copied().and_then(SoilClass::from_letter);
+    if soil.is_some() || (age == GeologicAge::Fill && chars.peek() == Some(&'K')) {
+        chars.next();
+    }
+    let rest: String = chars.collect();
+    let number = if rest.is_empty() { None } else { Some(rest.parse().ok()?) };
+
+    // "A" や "D" だけでは記号か判断できない
+    if soil.is_none() && age != GeologicAge::Fill {
+        return None;
+    }
+    Some(StratumUnit { age, soil, number })
+}
+
+/// 日本語の名称（"沖積粘性土層"、"第2洪積砂質土層" など、正規化済み）を読む
+fn parse_description(text: &str) -> Option<StratumUnit> {
+    let has = |keys: &[&str]| keys.iter().any(|k| text.contains(k));
+    let age = if has(&["埋土", "盛土", "埋戻"]) {
+        GeologicAge::Fill
+    } else if has(&["沖積", "完新"]) {
+        GeologicAge::Alluvial
+    } else if has(&["洪積", "更新"]) {
+        GeologicAge::Diluvial
+    } else if has(&["第三紀", "第三系"]) {
+        GeologicAge::Tertiary
+    } else {
+        return None;
+    };
+    let digits: String = text
+        .chars()
+        .skip_while(|c| !c.is_ascii_digit())
+        .take_while(|c| c.is_ascii_digit())
+        .collect();
+    Some(StratumUnit {
+        age,
+        soil: SoilClass::from_name(text),
+        number: digits.parse().ok(),
+    })
+}
+
+/// 正規化した地層岩体名
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct StratumName {
+    /// 記載どおりの名称
+    pub raw: String,
+    /// 時代・土質・番号（読めない場合はNone）
+    pub unit: Option<StratumUnit>,
+    /// 対比に使うキー（読めた場合は記号、読めない場合は正規化した名称）
+    pub key: String,
+}
+
+/// 地層岩体名の正規化（別名表と表記の規則）
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct StratumNormalizer {
+    /// 正規化した別名から読み替え先
+    aliases: HashMap<String, String>,
+}
+
+impl StratumNormalizer {
+    /// 別名を追加
+    ///
+    /// # Example
+    /// ```ignore
+    /// let normalizer = StratumNormalizer::default()
+    ///     .with_alias("有楽町層下部", "Ac2")
+    ///     .with_alias("東京礫層", "Dg1");
+    /// ```
+    pub fn with_alias(mut self, alias: &str, canonical: &str) -> Self {
+        self.insert(alias, canonical);
+        self
+    }
+
+    /// 別名を追加
+    pub fn insert(&mut self, alias: &str, canonical: &str) {
+        self.aliases.insert(normalize(alias), canonical.to_string());
+    }
+
+    /// 地層岩体名を正規化
+    pub fn normalize(&self, name: &str) -> StratumName {
+        let name = name.trim();
+        let canonical = self.aliases.get(&normalize(name)).map_or(name, String::as_str);
+        let unit = StratumUnit::parse(canonical);
+        StratumName {
+            raw: name.to_string(),
+            unit,
+            key: unit.map_or_else(|| normalize(canonical), |u| u.key()),
+        }
+    }
+}
+
+/// 対比の対象とする1孔の地層
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct UnitInterval {
+    /// 入力順のボーリング番号
+    pub borehole: usize,
+    /// 地層岩体区分の層
+    pub layer: SoilLayer,
+    /// 正規化した地層岩体名
+    pub name: StratumName,
+    /// 上面標高 (m)
+    pub top_elevation: f64,
+    /// 下面標高 (m)
+    pub bottom_elevation: f64,
+    /// 区間内で最も厚い土質
+    pub soil: Option<SoilClass>,
+    /// 区間内の標準貫入試験の平均N値
+    pub mean_n_value: Option<f64>,
+    /// 上面の平面直角座標
+    pub position: PlaneRectangular,
+}
+
+impl UnitInterval {
+    /// 中央の標高 (m)
+    pub fn mid_elevation(&self) -> f64 {
+        (self.top_elevation + self.bottom_elevation) / 2.0
+    }
+}
+
+/// 対比の重み
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+pub struct CorrelationWeights {
+    /// 地層名
+    pub name: f64,
+    /// 土質
+    pub soil: f64,
+    /// 標高
+    pub elevation: f64,
+    /// N値
+    pub n_value: f64,
+}
+
+impl Default for CorrelationWeights {
+    fn default() -> Self {
+        Self {
+            name: 0.4,
+            soil: 0.2,
+            elevation: 0.25,
+            n_value: 0.15,
+        }
+    }
+}
+
+/// 2孔の地層の組の得点（各項目0〜1）
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+pub struct CorrelationScore {
+    /// 地層名
+    pub name: f64,
+    /// 土質（どちらかが不明なら0.5）
+    pub soil: f64,
+    /// 中央標高の近さ
+    pub elevation: f64,
+    /// 平均N値の近さ（どちらかが不明なら0.5）
+    pub n_value: f64,
+    /// 重み付き平均
+    pub total: f64,
+}
+
+/// 対比した地層の組
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+pub struct CorrelationLink {
+    /// [`Correlation::intervals`] の番号
+    pub first: usize,
+    /// [`Correlation::intervals`] の番号
+    pub second: usize,
+    /// 得点
+    pub score: CorrelationScore,
+}
+
+/// 地層面上の1孔の点
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct HorizonPoint {
+    /// 入力順のボーリング番号
+    pub borehole: usize,
+    /// ボーリング名
+    pub name: Option<String>,
+    /// 標高の基準面
+    pub datum: HeightDatum,
+    /// X座標（北向き正, m）
+    pub x: f64,
+    /// Y座標（東向き正, m）
+    pub y: f64,
+    /// 上面標高 (m)
+    pub top_elevation: f64,
+    /// 下面標高 (m)
+    pub bottom_elevation: f64,
+}
+
+/// 対比した地層（地層面）
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct Horizon {
+    /// 最も多く使われたキー
+    pub key: String,
+    /// 時代・土質・番号
+    pub unit: Option<StratumUnit>,
+    /// [`Correlation::intervals`] の番号
+    pub intervals: Vec<usize>,
+    /// ボーリング番号順の点（同じ孔に複数の区間がある場合は上面・下面の外側）
+    pub points: Vec<HorizonPoint>,
+}
+
+/// 対比結果
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct Correlation {
+    /// 対象とした地層（孔ごとに上から順）
+    pub intervals: Vec<UnitInterval>,
+    /// 対比した組
+    pub links: Vec<CorrelationLink>,
+    /// 上面の平均標高が高い順の地層面
+    pub horizons: Vec<Horizon>,
+}
+
+/// 地層の対比条件
+#[derive(Debug, Clone, PartialEq)]
+pub struct StratigraphicCorrelator {
+    /// 地層岩体名の正規化
+    pub normalizer: StratumNormalizer,
+    /// 対比する孔口間の距離の上限 (m)
+    pub search_radius: f64,
+    /// 組にする得点の下限
+    pub threshold: f64,
+    /// 中央標高の差がこの値 (m) で標高の得点を0とする
+    pub elevation_tolerance: f64,
+    /// 重み
+    pub weights: CorrelationWeights,
+}
+
+impl Default for StratigraphicCorrelator {
+    fn default() -> Self {
+        Self {
+            normalizer: StratumNormalizer::default(),
+            search_radius: 500.0,
+            threshold: 0.65,
+            elevation_tolerance: 5.0,
+            weights: CorrelationWeights::default(),
+        }
+    }
+}
+
+impl StratigraphicCorrelator {
+    /// ボーリングファイルの地層を対比
+    ///
+    /// # Example
+    /// ```ignore
+    /// let zone = PlaneRectangularZone::new(9).unwrap();
+    /// let correlation = StratigraphicCorrelator::default().correlate_documents(&borings, &transformer, zone)?;
+    /// std::fs::write("horizons.csv", horizons_to_csv(&correlation.horizons))?;
+    /// ```
+    pub fn correlate_documents<T>(
+        &self,
+        documents: &[T],
+        transformer: &JgdTransformer,
+        zone: PlaneRectangularZone,
+    ) -> Result<Correlation, CorrelationError>
+    where
+        T: DepthElevations + StratumLayers + BoringHeader,
+    {
+        let logs: Vec<BoreholeLog> = documents
+            .iter()
+            .map(|d| BoreholeLog::from_document(d, transformer, zone))
+            .collect();
+        self.correlate(&logs)
+    }
+
+    /// ボーリングの記録の地層を対比
+    ///
+    /// 孔口の平面直角座標がないボーリングは対象外とし、基準面の異なる孔同士は対比しない。
+    /// 対比条件が不正な場合（[`StratigraphicCorrelator::validate`]）はエラーを返す。
+    pub fn correlate(&self, logs: &[BoreholeLog]) -> Result<Correlation, CorrelationError> {
+        self.validate()?;
+        let mut intervals = Vec::new();
+        let mut by_borehole: Vec<Vec<usize>> = vec![Vec::new(); logs.len()];
+        for (b, log) in logs.iter().enumerate() {
+            let mut local: Vec<UnitInterval> = log
+                .stratum_layers
+                .iter()
+                .filter_map(|layer| self.interval(b, log, layer))
+                .collect();
+            local.sort_by(|x, y| y.top_elevation.total_cmp(&x.top_elevation));
+            for interval in local {
+                by_borehole[b].push(intervals.len());
+                intervals.push(interval);
+            }
+        }
+
+        let mut links = Vec::new();
+        for a in 0..logs.len() {
+            for b in a + 1..logs.len() {
+                let (Some(ca), Some(cb)) = (logs[a].trajectory.collar, logs[b].trajectory.collar) else {
+                    continue;
+                };
+                if logs[a].collar.datum != logs[b].collar.datum {
+                    continue;
+                }
+                if (ca.x - cb.x).hypot(ca.y - cb.y) <= self.search_radius {
+                    links.extend(self.align(&intervals, &by_borehole[a], &by_borehole[b]));
+                }
+            }
+        }
+
+        let horizons = horizons(logs, &intervals, &links);
+        Ok(Correlation { intervals, links, horizons })
+    }
+
+    /// 探索半径が0以上、閾値が0〜1、標高の許容差が正の有限値、重みが0以上の有限値で
+    /// 合計が正であることを確認
+    pub fn validate(&self) -> Result<(), CorrelationError> {
+        if !(self.search_radius.is_finite() && self.search_radius >= 0.0) {
+            return Err(CorrelationError::InvalidOption("search_radius", self.search_radius));
+        }
+        if !(0.0..=1.0).contains(&self.threshold) {
+            return Err(CorrelationError::InvalidOption("threshold", self.threshold));
+        }
+        if !(self.elevation_tolerance.is_finite() && self.elevation_tolerance > 0.0) {
+            return Err(CorrelationError::InvalidOption("elevation_tolerance", self.elevation_tolerance));
+        }
+        let w = &self.weights;
+        for (name, value) in [
+            ("weights.name", w.name),
+            ("weights.soil", w.soil),
+            ("weights.elevation", w.elevation),
+            ("weights.n_value", w.n_value),
+        ] {
+            if !(value.is_finite() && value >= 0.0) {
+                return Err(CorrelationError::InvalidOption(name, value));
+            }
+        }
+        let sum = w.name + w.soil + w.elevation + w.n_value;
+        if sum <= 0.0 {
+            return Err(CorrelationError::InvalidOption("weights", sum));
+        }
+        Ok(())
+    }
+
+    /// 2つの地層の得点
+    ///
+    /// 対比条件は検証しないため、先に [`StratigraphicCorrelator::validate`] で確認しておく。
+    pub fn score(&self, a: &UnitInterval, b: &UnitInterval) -> CorrelationScore {
+        let name = name_similarity(&a.name, &b.name);
+        let soil = match (a.soil, b.soil) {
+            (Some(x), Some(y)) if x == y => 1.0,
+            (Some(_), Some(_)) => 0.0,
+            _ => 0.5,
+        };
+        let elevation = (1.0 - (a.mid_elevation() - b.mid_elevation()).abs() / self.elevation_tolerance).clamp(0.0, 1.0);
+        let n_value = match (a.mean_n_value, b.mean_n_value) {
+            // N値の比が4倍で0
+            (Some(x), Some(y)) => (1.0 - ((x + 1.0) / (y + 1.0)).ln().abs() / 4f64.ln()).clamp(0.0, 1.0),
+            _ => 0.5,
+        };
+
+        let w = &self.weights;
+        let sum = w.name + w.soil + w.elevation + w.n_value;
+        let total = if sum > 0.0 {
+            (w.name * name + w.soil * soil + w.elevation * elevation + w.n_value * n_value) / sum
+        } else {
+            0.0
+        };
+        CorrelationScore {
+            name,
+            soil,
+            elevation,
+            n_value,
+            total,
+        }
+    }
+
+    /// 1孔の地層岩体区分の層を対比の対象にする（孔口位置がなければNone）
+    fn interval(&self, borehole: usize, log: &BoreholeLog, layer: &SoilLayer) -> Option<UnitInterval> {
+        let top = log.trajectory.point_at(layer.top_depth);
+        let bottom = log.trajectory.point_at(layer.bottom_depth);
+
+        // 区間と重なる土質・岩種区分の厚さを土質ごとに合計
+        let mut thickness: Vec<(SoilClass, f64)> = Vec::new();
+        for soil in &log.soil_layers {
+            let overlap = soil.bottom_depth.min(layer.bottom_depth) - soil.top_depth.max(layer.top_depth);
+            let Some(class) = SoilClass::from_layer(soil) else {
+                continue;
+            };
+            if overlap <= 0.0 {
+                continue;
+            }
+            match thickness.iter_mut().find(|(c, _)| *c == class) {
+                Some((_, t)) => *t += overlap,
+                None => thickness.push((class, overlap)),
+            }
+        }
+        let soil = thickness
+            .iter()
+            .max_by(|a, b| a.1.total_cmp(&b.1))
+            .map(|(c, _)| *c);
+
+        let n_values: Vec<f64> = log
+            .spt
+            .iter()
+            .filter(|r| layer.contains(r.mid_depth()))
+            .map(|r| r.n_value())
+            .collect();
+        let mean_n_value = (!n_values.is_empty()).then(|| n_values.iter().sum::<f64>() / n_values.len() as f64);
+
+        Some(UnitInterval {
+            borehole,
+            layer: layer.clone(),
+            name: self.normalizer.normalize(layer.name.as_deref().unwrap_or_default()),
+            top_elevation: log.collar.value - top.vertical_depth,
+            bottom_elevation: log.collar.value - bottom.vertical_depth,
+            soil,
+            mean_n_value,
+            position: top.plane?,
+        })
+    }
+
+    /// 上下関係を保ったまま得点の合計が最大になる組を選ぶ
+    fn align(&self, intervals: &[UnitInterval], a: &[usize], b: &[usize]) -> Vec<CorrelationLink> {
+        let scores: Vec<Vec<Option<CorrelationScore>>> = a
+            .iter()
+            .map(|&i| {
+                b.iter()
+                    .map(|&j| Some(self.score(&intervals[i], &intervals[j])).filter(|s| s.total >= self.threshold))
+                    .collect()
+            })
+            .collect();
+
+        let mut best = vec![vec![0.0; b.len() + 1]; a.len() + 1];
+        for i in 1..=a.len() {
+            for j in 1..=b.len() {
+                let matched = scores[i - 1][j - 1].map_or(f64::NEG_INFINITY, |s| best[i - 1][j - 1] + s.total);
+                best[i][j] = best[i - 1][j].max(best[i][j - 1]).max(matched);
+            }
+        }
+
+        let mut links = Vec::new();
+        let (mut i, mut j) = (a.len(), b.len());
+        while i > 0 && j > 0 {
+            match scores[i - 1][j - 1] {
+                Some(score) if best[i][j] == best[i - 1][j - 1] + score.total => {
+                    links.push(CorrelationLink {
+                        first: a[i - 1],
+                        second: b[j - 1],
+                        score,
+                    });
+                    i -= 1;
+                    j -= 1;
+                }
+                _ if best[i][j] == best[i - 1][j] => i -= 1,
+                _ => j -= 1,
+            }
+        }
+        links.reverse();
+        links
+    }
+}
+
+/// 地層名の類似度
+///
+/// 時代・土質が同じなら番号が同じで1、一方に番号がなければ0.8、番号が異なれば0.5。
+/// 時代だけ同じなら0.2。読めない名称同士はキーが同じなら1とする。
+fn name_similarity(a: &StratumName, b: &StratumName) -> f64 {
+    match (a.unit, b.unit) {
+        (Some(x), Some(y)) if x.age == y.age && x.soil == y.soil => match (x.number, y.number) {
+            (Some(m), Some(n)) if m != n => 0.5,
+            (Some(_), None) | (None, Some(_)) => 0.8,
+            _ => 1.0,
+        },
+        (Some(x), Some(y)) if x.age == y.age => 0.2,
+        (Some(_), Some(_)) => 0.0,
+        _ if !a.key.is_empty() && a.key == b.key => 1.0,
+        _ => 0.0,
+    }
+}
+
+/// 組を連結して地層面にする
+fn horizons(logs: &[BoreholeLog], intervals: &[UnitInterval], links: &[CorrelationLink]) -> Vec<Horizon> {
+    let mut sets = DisjointSets::new(intervals.len());
+    for link in links {
+        sets.union(link.first, link.second);
+    }
+
+    let mut members: Vec<Vec<usize>> = Vec::new();
+    let mut group_of = HashMap::new();
+    for i in 0..intervals.len() {
+        let r = sets.find(i);
+        let g = *group_of.entry(r).or_insert_with(|| {
+            members.push(Vec::new());
+            members.len() - 1
+        });
+        members[g].push(i);
+    }
+
+    let mut horizons: Vec<Horizon> = members
+        .into_iter()
+        .map(|ids| {
+            // 最も多いキー（同数なら先に現れたもの）
+            let mut counts: Vec<(&str, usize, Option<StratumUnit>)> = Vec::new();
+            for &i in &ids {
+                let name = &intervals[i].name;
+                match counts.iter_mut().find(|(k, _, _)| *k == name.key) {
+                    Some((_, n, _)) => *n += 1,
+                    None => counts.push((&name.key, 1, name.unit)),
+                }
+            }
+            let (key, _, unit) = counts
+                .iter()
+                .rev()
+                .max_by_key(|(k, n, _)| (!k.is_empty(), *n))
+                .copied()
+                .unwrap_or_default();
+
+            let mut points: Vec<HorizonPoint> = Vec::new();
+            for &i in &ids {
+                let interval = &intervals[i];
+                match points.iter_mut().find(|p| p.borehole == interval.borehole) {
+                    Some(p) => {
+                        if interval.top_elevation > p.top_elevation {
+                            p.top_elevation = interval.top_elevation;
+                            p.x = interval.position.x;
+                            p.y = interval.position.y;
+                        }
+                        p.bottom_elevation = p.bottom_elevation.min(interval.bottom_elevation);
+                    }
+                    None => points.push(HorizonPoint {
+                        borehole: interval.borehole,
+                        name: logs[interval.borehole].name.clone(),
+                        datum: logs[interval.borehole].collar.datum.clone(),
+                        x: interval.position.x,
+                        y: interval.position.y,
+                        top_elevation: interval.top_elevation,
+                        bottom_elevation: interval.bottom_elevation,
+                    }),
+                }
+            }
+            points.sort_by_key(|p| p.borehole);
+
+            Horizon {
+                key: key.to_string(),
+                unit,
+                intervals: ids,
+                points,
+            }
+        })
+        .collect();
+
+    let mean_top = |h: &Horizon| h.points.iter().map(|p| p.top_elevation).sum::<f64>() / h.points.len() as f64;
+    horizons.sort_by(|a, b| mean_top(b).total_cmp(&mean_top(a)));
+    horizons
+}
+
+/// 地層面をCSV文字列に変換
+///
+/// 1行が1孔の点で、地層面の上面・下面標高とその基準面を出力する。
+pub fn horizons_to_csv(horizons: &[Horizon]) -> String {
+    let mut csv = String::from("datum,horizon,borehole,name,x,y,top_elevation_m,bottom_elevation_m\n");
+    for h in horizons {
+        for p in &h.points {
+            csv.push_str(&format!(
+                "{},{},{},{},{:.3},{:.3},{:.3},{:.3}\n",
+                csv_field(&datum_label(&p.datum)),
+                csv_field(&h.key),
+                p.borehole,
+                csv_field(p.name.as_deref().unwrap_or_default()),
+                p.x,
+                p.y,
+                p.top_elevation,
+                p.bottom_elevation
+            ));
+        }
+    }
+    csv
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::elevation::CollarElevation;
+    use crate::groundwater::WaterLevelSeries;
+    use crate::spt::SptRecord;
+    use crate::trajectory::Trajectory;
+
+    fn layer(top: f64, bottom: f64, name: &str) -> SoilLayer {
+        SoilLayer {
+            top_depth: top,
+            bottom_depth: bottom,
+            name: Some(name.to_string()),
+            symbol: None,
+        }
+    }
+
+    fn log(name: &str, y: f64, elevation: f64, strata: Vec<SoilLayer>, n_values: &[f64]) -> BoreholeLog {
+        let zone = PlaneRectangularZone::new(9).unwrap();
+        BoreholeLog {
+            name: Some(name.to_string()),
+            collar: CollarElevation { datum: HeightDatum::TokyoPeil, value: elevation },
+            trajectory: Trajectory::vertical(Some(elevation)).with_collar(PlaneRectangular { zone, x: 0.0, y }),
+            soil_layers: vec![layer(0.0, 5.0, "シルト質粘土"), layer(5.0, 10.0, "細砂"), layer(10.0, 15.0, "砂礫")],
+            stratum_layers: strata,
+            spt: n_values
+                .iter()
+                .enumerate()
+                .map(|(i, &n)| SptRecord { start_depth: 1.0 + 5.0 * i as f64, blows: n, penetration: Some(30.0) })
+                .collect(),
+            water_levels: WaterLevelSeries::new(Some(elevation), Vec::new()),
+        }
+    }
+
+    #[test]
+    fn test_normalize_names() {
+        let key = |name: &str| StratumNormalizer::default().normalize(name).key;
+        assert_eq!(key("Ac1"), "Ac1");
+        assert_eq!(key("ＡＳ２"), "As2");
+        assert_eq!(key("Dg-2層"), "Dg2");
+        assert_eq!(key("Am"), "Ac");
+        assert_eq!(key("Bk"), "B");
+        assert_eq!(key("沖積粘性土層"), "Ac");
+        assert_eq!(key("第2洪積砂質土層"), "Ds2");
+        assert_eq!(key("盛土"), "B");
+        assert_eq!(key("Tr"), "Tr");
+        assert_eq!(key("○○層"), "○○層");
+        assert_eq!(StratumUnit::parse("D"), None);
+
+        let normalizer = StratumNormalizer::default().with_alias("○○ 層", "Dc1");
+        let name = normalizer.normalize("○○層");
+        assert_eq!((name.raw.as_str(), name.key.as_str()), ("○○層", "Dc1"));
+        assert_eq!(name.unit.unwrap().age, GeologicAge::Diluvial);
+    }
+
+    #[test]
+    fn test_correlate_neighbours() {
+        let logs = vec![
+            log("B-1", 0.0, 10.0, vec![layer(0.0, 5.0, "Ac1"), layer(5.0, 10.0, "As1"), layer(10.0, 15.0, "Dg")], &[2.0, 15.0, 50.0]),
+            log(
+                "B-2",
+                50.0,
+                10.5,
+                vec![layer(0.0, 4.0, "沖積粘性土層"), layer(4.0, 11.0, "As1"), layer(11.0, 15.0, "Dg1")],
+                &[3.0, 12.0, 45.0],
+            ),
+            // 離れているため対比しない
+            log("B-3", 2_000.0, 10.0, vec![layer(0.0, 5.0, "Ac1")], &[]),
+        ];
+        let correlation = StratigraphicCorrelator::default().correlate(&logs).unwrap();
+        assert_eq!(correlation.intervals.len(), 7);
+
+        let pairs: Vec<(&str, &str)> = correlation
+            .links
+            .iter()
+            .map(|l| (correlation.intervals[l.first].name.raw.as_str(), correlation.intervals[l.second].name.raw.as_str()))
+            .collect();
+        assert_eq!(pairs, vec![("Ac1", "沖積粘性土層"), ("As1", "As1"), ("Dg", "Dg1")]);
+        assert!((correlation.links[1].score.name - 1.0).abs() < 1e-9);
+        assert!((correlation.links[0].score.soil - 1.0).abs() < 1e-9);
+
+        let keys: Vec<(&str, usize)> = correlation.horizons.iter().map(|h| (h.key.as_str(), h.points.len())).collect();
+        assert_eq!(keys, vec![("Ac1", 2), ("Ac1", 1), ("As1", 2), ("Dg", 2)]);
+        let top = &correlation.horizons[0].points[1];
+        assert_eq!((top.borehole, top.top_elevation, top.bottom_elevation), (1, 10.5, 6.5));
+
+        let csv = horizons_to_csv(&correlation.horizons);
+        assert_eq!(csv.lines().count(), 1 + 7);
+        assert!(csv.contains("T.P.,Ac1,1,B-2,0.000,50.000,10.500,6.500\n"));
+
+        let mut horizon = correlation.horizons[0].clone();
+        horizon.key = "○○層,上部".to_string();
+        horizon.points[0].name = Some("No.1,\"旧\"".to_string());
+        let csv = horizons_to_csv(&[horizon]);
+        assert_eq!(csv.lines().nth(1), Some("T.P.,\"○○層,上部\",0,\"No.1,\"\"旧\"\"\",0.000,0.000,10.000,5.000"));
+    }
+
+    #[test]
+    fn test_different_datums_are_not_correlated() {
+        let strata = || vec![layer(0.0, 5.0, "Ac1"), layer(5.0, 10.0, "As1")];
+        let mut logs = vec![log("B-1", 0.0, 10.0, strata(), &[2.0, 15.0]), log("B-2", 50.0, 0.0, strata(), &[2.0, 15.0])];
+
+        // 標高の得点が0でも地層名・土質・N値だけで閾値を超える
+        let correlator = StratigraphicCorrelator::default();
+        let same_datum = correlator.correlate(&logs).unwrap();
+        assert_eq!(same_datum.links.len(), 2);
+        assert!(same_datum.links.iter().all(|l| l.score.elevation == 0.0));
+
+        // 孔口標高がなくG.L.になった孔とは対比しない
+        logs[1].collar.datum = HeightDatum::GroundLevel;
+        let correlation = correlator.correlate(&logs).unwrap();
+        assert!(correlation.links.is_empty());
+        assert!(correlation.horizons.iter().all(|h| h.points.len() == 1));
+        let csv = horizons_to_csv(&correlation.horizons);
+        assert!(csv.contains("T.P.,Ac1,0,B-1,"));
+        assert!(csv.contains("G.L.,Ac1,1,B-2,"));
+    }
+
+    #[test]
+    fn test_invalid_options() {
+        let logs = vec![log("B-1", 0.0, 10.0, vec![layer(0.0, 5.0, "Ac1")], &[])];
+        let correlate = |correlator: StratigraphicCorrelator| correlator.correlate(&logs).map(|c| c.intervals.len());
+        assert_eq!(correlate(StratigraphicCorrelator::default()), Ok(1));
+
+        // 許容差が0・NaNだと標高の得点がNaNになり、すべての組が黙って閾値を下回る
+        for tolerance in [0.0, -1.0, f64::NAN] {
+            let correlator = StratigraphicCorrelator { elevation_tolerance: tolerance, ..Default::default() };
+            assert!(matches!(
+                correlate(correlator),
+                Err(CorrelationError::InvalidOption("elevation_tolerance", _))
+            ));
+        }
+        let correlator = StratigraphicCorrelator { threshold: 1.5, ..Default::default() };
+        assert_eq!(correlate(correlator), Err(CorrelationError::InvalidOption("threshold", 1.5)));
+        let weights = CorrelationWeights { soil: f64::INFINITY, ..Default::default() };
+        let correlator = StratigraphicCorrelator { weights, ..Default::default() };
+        assert!(matches!(correlate(correlator), Err(CorrelationError::InvalidOption("weights.soil", _))));
+        let weights = CorrelationWeights { name: 0.0, soil: 0.0, elevation: 0.0, n_value: 0.0 };
+        let correlator = StratigraphicCorrelator { weights, ..Default::default() };
+        assert_eq!(correlate(correlator), Err(CorrelationError::InvalidOption("weights", 0.0)));
+    }
+}
@@ -0,0 +1,334 @@
+//! 度分秒の読み取り・書き出しモジュール
+//!
+//! 納品データの緯度経度には、全角数字、カンマの小数点、60以上の分・秒、
+//! `-0` のような符号付きゼロ、`経度_度` に10進数の度がそのまま入った値などが
+//! 混じっている。[`DmsCoordinate::parse_lenient`] はこれらを正規化して10進数の度に変換し、
+//! 行った修正を [`DmsRepair`] として返す。
+//!
+//! [`DmsCoordinate::from_decimal`] は10進数の度をXMLに書き出す度分秒に変換する。
+
+use serde::Serialize;
+use thiserror::Error;
+
+use crate::coordinate::DmsCoordinate;
+
+/// 日本の範囲（緯度、度）
+const JAPAN_LATITUDE: (f64, f64) = (20.0, 46.0);
+/// 日本の範囲（経度、度）
+const JAPAN_LONGITUDE: (f64, f64) = (122.0, 154.0);
+
+/// 座標の軸
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+pub enum Axis {
+    /// 緯度
+    Latitude,
+    /// 経度
+    Longitude,
+}
+
+impl Axis {
+    /// 日本の範囲（最小, 最大）
+    pub fn japan_range(&self) -> (f64, f64) {
+        match self {
+            Axis::Latitude => JAPAN_LATITUDE,
+            Axis::Longitude => JAPAN_LONGITUDE,
+        }
+    }
+}
+
+/// 度分秒の要素
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+pub enum DmsPart {
+    /// 度
+    Degree,
+    /// 分
+    Minute,
+    /// 秒
+    Second,
+}
+
+/// 読み取り時に行った修正
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+pub enum DmsRepair {
+    /// 全角の数字・記号を半角に変換した
+    FullWidth(DmsPart),
+    /// 小数点のカンマをピリオドに変換した
+    CommaDecimal(DmsPart),
+    /// 符号付きゼロ (`-0`) を0とした
+    NegativeZero(DmsPart),
+    /// 60以上の分を度に繰り上げた
+    MinuteOverflow,
+    /// 60以上の秒を分に繰り上げた
+    SecondOverflow,
+    /// 度に10進数の度が入っていたため、そのまま使った
+    DecimalDegree,
+}
+
+/// 度分秒の読み取りエラー
+#[derive(Error, Debug, Clone, PartialEq)]
+pub enum DmsError {
+    #[error("{0:?}がありません")]
+    Missing(DmsPart),
+
+    #[error("{part:?}を数値として読めません: {value}")]
+    Invalid { part: DmsPart, value: String },
+
+    #[error("{0:?}が負の値です")]
+    Negative(DmsPart),
+
+    #[error("度に小数があり、分・秒も記載されています")]
+    AmbiguousDecimalDegree,
+
+    #[error("{axis:?}が日本の範囲外です: {value}")]
+    OutOfRange { axis: Axis, value: f64 },
+}
+
+/// 度分秒の読み取り結果
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct ParsedDms {
+    /// 10進数の度
+    pub value: f64,
+    /// 行った修正（修正なしの場合は空）
+    pub repairs: Vec<DmsRepair>,
+}
+
+impl DmsCoordinate {
+    /// 度分秒を正規化して10進数（度）に変換し、日本の範囲にあるか検証する
+    ///
+    /// # Example
+    /// ```ignore
+    /// let dms = DmsCoordinate {
+    ///     degree: Some("１３５".to_string()),
+    ///     minute: Some("49".to_string()),
+    ///     second: Some("58,2".to_string()),
+    /// };
+    /// let parsed = dms.parse_lenient(Axis::Longitude)?;
+    /// assert_eq!(parsed.repairs, vec![DmsRepair::FullWidth(DmsPart::Degree), DmsRepair::CommaDecimal(DmsPart::Second)]);
+    /// ```
+    pub fn parse_lenient(&self, axis: Axis) -> Result<ParsedDms, DmsError> {
+        let parsed = self.normalize()?;
+        let (min, max) = axis.japan_range();
+        if !(min..=max).contains(&parsed.value) {
+            return Err(DmsError::OutOfRange {
+                axis,
+                value: parsed.value,
+            });
+        }
+        Ok(parsed)
+    }
+
+    /// 度分秒を正規化して10進数（度）に変換する（範囲の検証なし）
+    pub fn normalize(&self) -> Result<ParsedDms, DmsError> {
+        let mut repairs = Vec::new();
+        let degree = parse_part(self.degree.as_deref(), DmsPart::Degree, &mut repairs)?
+            .ok_or(DmsError::Missing(DmsPart::Degree))?;
+        let minute = parse_part(self.minute.as_deref(), DmsPart::Minute, &mut repairs)?;
+        let second = parse_part(self.second.as_deref(), DmsPart::Second, &mut repairs)?;
+
+        // 度に10進数の度が入っている
+        if degree.fract() != 0.0 {
+            if minute.unwrap_or(0.0) != 0.0 || second.unwrap_or(0.0) != 0.0 {
+                return Err(DmsError::AmbiguousDecimalDegree);
+            }
+            repairs.push(DmsRepair::DecimalDegree);
+            return Ok(ParsedDms { value: degree, repairs });
+        }
+
+        let minute = minute.ok_or(DmsError::Missing(DmsPart::Minute))?;
+        let second = second.ok_or(DmsError::Missing(DmsPart::Second))?;
+        if second >= 60.0 {
+            repairs.push(DmsRepair::SecondOverflow);
+        }
+        if minute + (second / 60.0).floor() >= 60.0 {
+            repairs.push(DmsRepair::MinuteOverflow);
+        }
+
+        Ok(ParsedDms {
+            value: degree + minute / 60.0 + second / 3600.0,
+            repairs,
+        })
+    }
+
+    /// 10進数（度）を度分秒に変換する
+    ///
+    /// 秒は `second_digits` 桁の小数で書き出す。丸めで60秒になる場合は分・度に繰り上げる。
+    /// 度分秒には符号を書けない（[`DmsCoordinate::normalize`] で読めない）ため、
+    /// 負の値はエラーとする。
+    ///
+    /// # Example
+    /// ```ignore
+    /// let dms = DmsCoordinate::from_decimal(135.832833, 4)?;
+    /// assert_eq!(dms.second.as_deref(), Some("58.1988"));
+    /// ```
+    pub fn from_decimal(value: f64, second_digits: usize) -> Result<Self, DmsError> {
+        if !value.is_finite() {
+            return Err(DmsError::Invalid {
+                part: DmsPart::Degree,
+                value: value.to_string(),
+            });
+        }
+        if value < 0.0 {
+            return Err(DmsError::Negative(DmsPart::Degree));
+        }
+        let scale = 10f64.powi(second_digits as i32);
+        // 秒の最小単位で丸めてから分解する
+        let total = (value.abs() * 3600.0 * scale).round();
+        let units_per_minute = 60.0 * scale;
+        let units_per_degree = 3600.0 * scale;
+
+        let degree = (total / units_per_degree).floor();
+        let minute = ((total - degree * units_per_degree) / units_per_minute).floor();
+        let second = (total - degree * units_per_degree - minute * units_per_minute) / scale;
+
+        Ok(Self {
+            degree: Some(format!("{}", degree)),
+            minute: Some(format!("{}", minute)),
+            second: Some(format!("{:.*}", second_digits, second)),
+        })
+    }
+}
+
+/// 度分秒の1要素を読み取る（空欄はNone）
+fn parse_part(
+    value: Option<&str>,
+    part: DmsPart,
+    repairs: &mut Vec<DmsRepair>,
+) -> Result<Option<f64>, DmsError> {
+    let Some(raw) = value else {
+        return Ok(None);
+    };
+
+    let mut text = String::with_capacity(raw.len());
+    let mut full_width = false;
+    for c in raw.chars() {
+        let half = match c {
+            '０'..='９' => char::from_u32(c as u32 - '０' as u32 + '0' as u32),
+            '．' => Some('.'),
+            '，' => Some(','),
+            '－' | '−' | 'ー' => Some('-'),
+            '＋' => Some('+'),
+            '\u{3000}' => Some(' '),
+            _ => None,
+        };
+        match half {
+            Some(h) => {
+                full_width = true;
+                text.push(h);
+            }
+            None => text.push(c),
+        }
+    }
+    if full_width {
+        repairs.push(DmsRepair::FullWidth(part));
+    }
+
+    let mut text = text.trim().to_string();
+    if text.is_empty() {
+        return Ok(None);
+    }
+    if text.contains(',') && !text.contains('.') && text.matches(',').count() == 1 {
+        text = text.replace(',', ".");
+        repairs.push(DmsRepair::CommaDecimal(part));
+    }
+
+    let number = text
+        .parse::<f64>()
+        .ok()
+        .filter(|v| v.is_finite())
+        .ok_or_else(|| DmsError::Invalid {
+            part,
+            value: raw.to_string(),
+        })?;
+
+    if number == 0.0 && number.is_sign_negative() {
+        repairs.push(DmsRepair::NegativeZero(part));
+        return Ok(Some(0.0));
+    }
+    if number < 0.0 {
+        return Err(DmsError::Negative(part));
+    }
+    Ok(Some(number))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn dms(degree: &str, minute: &str, second: &str) -> DmsCoordinate {
+        let value = |s: &str| (!s.is_empty()).then(|| s.to_string());
+        DmsCoordinate {
+            degree: value(degree),
+            minute: value(minute),
+            second: value(second),
+        }
+    }
+
+    #[test]
+    fn test_parse_lenient_repairs() {
+        let parsed = dms("１３５", "49", "58,2").parse_lenient(Axis::Longitude).unwrap();
+        assert!((parsed.value - (135.0 + 49.0 / 60.0 + 58.2 / 3600.0)).abs() < 1e-12);
+        assert_eq!(
+            parsed.repairs,
+            vec![DmsRepair::FullWidth(DmsPart::Degree), DmsRepair::CommaDecimal(DmsPart::Second)]
+        );
+
+        // 分・秒の繰り上げ
+        let parsed = dms("34", "59", "65").parse_lenient(Axis::Latitude).unwrap();
+        assert!((parsed.value - (35.0 + 5.0 / 3600.0)).abs() < 1e-12);
+        assert_eq!(parsed.repairs, vec![DmsRepair::SecondOverflow, DmsRepair::MinuteOverflow]);
+
+        // 符号付きゼロ
+        let parsed = dms("35", "-0", "0").parse_lenient(Axis::Latitude).unwrap();
+        assert_eq!((parsed.value, parsed.repairs), (35.0, vec![DmsRepair::NegativeZero(DmsPart::Minute)]));
+
+        // 度に10進数の度
+        let parsed = dms("135.8328", "", "0").parse_lenient(Axis::Longitude).unwrap();
+        assert_eq!((parsed.value, parsed.repairs), (135.8328, vec![DmsRepair::DecimalDegree]));
+
+        // 修正なし
+        assert!(dms("35", "40", "0").parse_lenient(Axis::Latitude).unwrap().repairs.is_empty());
+    }
+
+    #[test]
+    fn test_parse_lenient_errors() {
+        assert_eq!(dms("", "40", "0").normalize(), Err(DmsError::Missing(DmsPart::Degree)));
+        assert_eq!(dms("35", "40", "").normalize(), Err(DmsError::Missing(DmsPart::Second)));
+        assert!(matches!(dms("35", "四十", "0").normalize(), Err(DmsError::Invalid { .. })));
+        assert_eq!(dms("35", "-5", "0").normalize(), Err(DmsError::Negative(DmsPart::Minute)));
+        assert_eq!(dms("35.5", "10", "0").normalize(), Err(DmsError::AmbiguousDecimalDegree));
+        // 緯度と経度の取り違え
+        assert!(matches!(
+            dms("135", "0", "0").parse_lenient(Axis::Latitude),
+            Err(DmsError::OutOfRange { axis: Axis::Latitude, .. })
+        ));
+    }
+
+    #[test]
+    fn test_from_decimal() {
+        let value = 135.0 + 49.0 / 60.0 + 58.2 / 3600.0;
+        assert_eq!(DmsCoordinate::from_decimal(value, 4), Ok(dms("135", "49", "58.2000")));
+
+        // 丸めで60秒になる場合は繰り上げる
+        let value = 35.0 + 59.0 / 60.0 + 59.99996 / 3600.0;
+        assert_eq!(DmsCoordinate::from_decimal(value, 4), Ok(dms("36", "0", "0.0000")));
+
+        let back = DmsCoordinate::from_decimal(34.998111, 4).unwrap().normalize().unwrap();
+        assert!((back.value - 34.998111).abs() < 1e-7);
+    }
+
+    #[test]
+    fn test_from_decimal_round_trip_sign() {
+        // 負の値は書き出せない（丸めると0秒になる小さな負の値も同じ）
+        for value in [-5.5, -0.0001, -1.0e-9] {
+            assert_eq!(DmsCoordinate::from_decimal(value, 4), Err(DmsError::Negative(DmsPart::Degree)));
+        }
+        assert!(matches!(DmsCoordinate::from_decimal(f64::NAN, 4), Err(DmsError::Invalid { .. })));
+
+        // 0と-0は正の0として書き出し、読み戻せる
+        for value in [0.0, -0.0, 0.0001, 5.5] {
+            let back = DmsCoordinate::from_decimal(value, 4).unwrap().normalize().unwrap();
+            assert!((back.value - value).abs() < 1e-7, "{value}");
+            assert!(back.repairs.is_empty(), "{value}");
+        }
+    }
+}
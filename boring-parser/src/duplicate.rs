@@ -0,0 +1,421 @@
+//! 重複ボーリングの検出モジュール
+//!
+//! 同じボーリングが複数の業務の納品データに、名前の表記や入力し直しの違いを伴って
+//! 含まれることがある。[`DuplicateFinder`] は [`BoringCollection`] の中で近い位置にある
+//! ボーリングの組を、位置・ボーリング名・調査開始日・総掘進長・層構成の類似度を
+//! 重み付けした得点で比べ、重複の可能性が高い組をまとめて返す。
+//!
+//! どちらかに記載がない項目は得点から除き、残りの項目の重みで平均する。
+
+use serde::Serialize;
+
+use crate::collection::BoringCollection;
+use crate::layer::{SoilLayer, SoilLayers, layer_at};
+use crate::transform::{SurveyDate, SurveyStartDate};
+use crate::types::{BoringHeader, DisjointSets};
+
+/// 層構成を比べる深度の間隔 (m)
+const LAYER_SAMPLE_INTERVAL: f64 = 0.5;
+
+/// ボーリングの識別に使う項目を取得するためのトレイト
+pub trait BoringIdentity: BoringHeader + SurveyDate + SoilLayers {}
+
+impl<T: BoringHeader + SurveyDate + SoilLayers> BoringIdentity for T {}
+
+/// 類似度の重み
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+pub struct DuplicateWeights {
+    /// 位置
+    pub location: f64,
+    /// ボーリング名
+    pub name: f64,
+    /// 調査開始日
+    pub date: f64,
+    /// 総掘進長
+    pub depth: f64,
+    /// 層構成
+    pub layers: f64,
+}
+
+impl Default for DuplicateWeights {
+    fn default() -> Self {
+        Self {
+            location: 0.3,
+            name: 0.2,
+            date: 0.15,
+            depth: 0.1,
+            layers: 0.25,
+        }
+    }
+}
+
+/// 2本のボーリングの類似度（各項目0〜1）
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+pub struct SimilarityScore {
+    /// 孔口間の距離 (m)
+    pub distance: f64,
+    /// 位置
+    pub location: f64,
+    /// ボーリング名（どちらかに記載がない場合はNone、以下同じ）
+    pub name: Option<f64>,
+    /// 調査開始日
+    pub date: Option<f64>,
+    /// 総掘進長
+    pub depth: Option<f64>,
+    /// 層構成
+    pub layers: Option<f64>,
+    /// 重み付き平均
+    pub total: f64,
+}
+
+/// 重複の可能性がある組
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+pub struct DuplicatePair {
+    /// 集合での番号（小さい方）
+    pub first: usize,
+    /// 集合での番号（大きい方）
+    pub second: usize,
+    /// 類似度
+    pub score: SimilarityScore,
+}
+
+/// 重複の可能性があるボーリングのまとまり
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct DuplicateGroup {
+    /// 集合での番号（昇順）
+    pub members: Vec<usize>,
+    /// まとまりを作った組（得点の高い順）
+    pub pairs: Vec<DuplicatePair>,
+}
+
+/// 重複ボーリングの検出条件
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+pub struct DuplicateFinder {
+    /// 比べる孔口間の距離の上限 (m)
+    pub search_radius: f64,
+    /// 重複とみなす得点の下限
+    pub threshold: f64,
+    /// 調査開始日の差の許容日数（これ以上離れると0点）
+    pub date_tolerance_days: f64,
+    /// 総掘進長の差の許容割合（これ以上離れると0点）
+    pub depth_tolerance: f64,
+    /// 類似度の重み
+    pub weights: DuplicateWeights,
+}
+
+impl Default for DuplicateFinder {
+    fn default() -> Self {
+        Self {
+            search_radius: 30.0,
+            threshold: 0.75,
+            date_tolerance_days: 365.0,
+            depth_tolerance: 0.2,
+            weights: DuplicateWeights::default(),
+        }
+    }
+}
+
+impl DuplicateFinder {
+    /// 集合から重複の可能性があるボーリングのまとまりを探す
+    ///
+    /// 得点が `threshold` 以上の組を連結して1つのまとまりとする（AとB、BとCが組ならA・B・C）。
+    ///
+    /// # Example
+    /// ```ignore
+    /// let collection = BoringCollection::new(borings, &transformer);
+    /// for group in DuplicateFinder::default().find(&collection) {
+    ///     for pair in &group.pairs {
+    ///         println!("{} - {}: {:.2}", pair.first, pair.second, pair.score.total);
+    ///     }
+    /// }
+    /// ```
+    pub fn find<T: BoringIdentity>(&self, collection: &BoringCollection<T>) -> Vec<DuplicateGroup> {
+        let mut pairs = Vec::new();
+        for index in 0..collection.len() {
+            let Some(boring) = collection.get(index) else {
+                continue;
+            };
+            for neighbor in collection.within_radius(boring.latitude, boring.longitude, self.search_radius) {
+                let other = neighbor.boring.index;
+                if other <= index {
+                    continue;
+                }
+                let score = self.compare(boring.document, neighbor.boring.document, neighbor.distance);
+                if score.total >= self.threshold {
+                    pairs.push(DuplicatePair {
+                        first: index,
+                        second: other,
+                        score,
+                    });
+                }
+            }
+        }
+        group_pairs(collection.len(), pairs)
+    }
+
+    /// 2本のボーリングの類似度
+    ///
+    /// # Arguments
+    /// * `distance` - 孔口間の距離 (m)
+    pub fn compare<T: BoringIdentity>(&self, a: &T, b: &T, distance: f64) -> SimilarityScore {
+        let location = (1.0 - distance / self.search_radius).clamp(0.0, 1.0);
+        let name = a.boring_name().zip(b.boring_name()).map(|(a, b)| name_similarity(a, b));
+        let date = a
+            .survey_start_date()
+            .zip(b.survey_start_date())
+            .map(|(a, b)| {
+                let days = (day_number(a) - day_number(b)).abs() as f64;
+                (1.0 - days / self.date_tolerance_days).clamp(0.0, 1.0)
+            });
+        let depth = a
+            .total_length()
+            .zip(b.total_length())
+            .filter(|(a, b)| a.max(*b) > 0.0)
+            .map(|(a, b)| {
+                let ratio = (a - b).abs() / a.max(b);
+                (1.0 - ratio / self.depth_tolerance).clamp(0.0, 1.0)
+            });
+        let layers = layer_similarity(&a.soil_layers(), &b.soil_layers());
+
+        let w = &self.weights;
+        let parts = [
+            (Some(location), w.location),
+            (name, w.name),
+            (date, w.date),
+            (depth, w.depth),
+            (layers, w.layers),
+        ];
+        let weight: f64 = parts.iter().filter(|(v, _)| v.is_some()).map(|(_, w)| w).sum();
+        let total = if weight > 0.0 {
+            parts.iter().filter_map(|(v, w)| v.map(|v| v * w)).sum::<f64>() / weight
+        } else {
+            0.0
+        };
+
+        SimilarityScore {
+            distance,
+            location,
+            name,
+            date,
+            depth,
+            layers,
+            total,
+        }
+    }
+}
+
+/// 組を連結してまとまりにする
+fn group_pairs(len: usize, pairs: Vec<DuplicatePair>) -> Vec<DuplicateGroup> {
+    let mut sets = DisjointSets::new(len);
+    for pair in &pairs {
+        sets.union(pair.first, pair.second);
+    }
+
+    let mut groups: Vec<DuplicateGroup> = Vec::new();
+    let mut group_of = std::collections::HashMap::new();
+    for pair in pairs {
+        let r = sets.find(pair.first);
+        let i = *group_of.entry(r).or_insert_with(|| {
+            groups.push(DuplicateGroup {
+                members: Vec::new(),
+                pairs: Vec::new(),
+            });
+            groups.len() - 1
+        });
+        let group = &mut groups[i];
+        for member in [pair.first, pair.second] {
+            if !group.members.contains(&member) {
+                group.members.push(member);
+            }
+        }
+        group.pairs.push(pair);
+    }
+    for group in &mut groups {
+        group.members.sort_unstable();
+        group.pairs.sort_by(|a, b| b.score.total.total_cmp(&a.score.total));
+    }
+    groups.sort_by_key(|g| g.members[0]);
+    groups
+}
+
+/// ボーリング名の比較用の正規化（全角→半角、大文字、空白・記号・"NO." を除く）
+fn normalize_name(name: &str) -> String {
+    name.chars()
+        .map(|c| match c {
+            '\u{FF01}'..='\u{FF5E}' => char::from_u32(c as u32 - 0xFEE0).unwrap_or(c),
+            _ => c,
+        })
+        .collect::<String>()
+        .to_ascii_uppercase()
+        .replace("NO.", "")
+        .chars()
+        .filter(|c| c.is_alphanumeric())
+        .collect()
+}
+
+/// ボーリング名の類似度
+///
+/// 正規化した名前の編集距離による。番号（数字の並び）が同じで、番号の前の英字が
+/// 一致するか一方が他方の略記（"B" と "BOR" など）の場合は0.8以上とする。
+/// "No.1" と "B-1" のように英字の有無が異なる場合は番号だけでは同じ孔とみなさない。
+fn name_similarity(a: &str, b: &str) -> f64 {
+    let (a, b) = (normalize_name(a), normalize_name(b));
+    if a.is_empty() || b.is_empty() {
+        return 0.0;
+    }
+    let a: Vec<char> = a.chars().collect();
+    let b: Vec<char> = b.chars().collect();
+    let similarity = 1.0 - levenshtein(&a, &b) as f64 / a.len().max(b.len()) as f64;
+
+    let digits = |s: &[char]| s.iter().filter(|c| c.is_ascii_digit()).collect::<String>();
+    let prefix = |s: &[char]| s.iter().take_while(|c| !c.is_ascii_digit()).collect::<String>();
+    let (da, db) = (digits(&a), digits(&b));
+    let (pa, pb) = (prefix(&a), prefix(&b));
+    let same_prefix = if pa.is_empty() || pb.is_empty() {
+        pa == pb
+    } else {
+        pa.starts_with(&pb) || pb.starts_with(&pa)
+    };
+    if !da.is_empty() && da == db && same_prefix {
+        similarity.max(0.8)
+    } else {
+        similarity
+    }
+}
+
+/// 編集距離
+fn levenshtein(a: &[char], b: &[char]) -> usize {
+    let mut row: Vec<usize> = (0..=b.len()).collect();
+    for (i, ca) in a.iter().enumerate() {
+        let mut previous = row[0];
+        row[0] = i + 1;
+        for (j, cb) in b.iter().enumerate() {
+            let current = row[j + 1];
+            row[j + 1] = if ca == cb {
+                previous
+            } else {
+                1 + previous.min(row[j]).min(current)
+            };
+            previous = current;
+        }
+    }
+    row[b.len()]
+}
+
+/// 層構成の類似度
+///
+/// 浅い方の下端深度まで一定間隔で、同じ深度の土質岩種記号（なければ土質岩種名）が
+/// 一致する割合とする。
+fn layer_similarity(a: &[SoilLayer], b: &[SoilLayer]) -> Option<f64> {
+    let bottom = a.last()?.bottom_depth.min(b.last()?.bottom_depth);
+    let samples = (bottom / LAYER_SAMPLE_INTERVAL).floor() as usize;
+    if samples == 0 {
+        return None;
+    }
+    let label = |layers: &[SoilLayer], depth: f64| {
+        layer_at(layers, depth)
+            .and_then(|l| l.symbol.as_deref().or(l.name.as_deref()))
+            .map(normalize_name)
+    };
+    let matched = (0..samples)
+        .map(|i| (i as f64 + 0.5) * LAYER_SAMPLE_INTERVAL)
+        .filter(|&depth| {
+            let (la, lb) = (label(a, depth), label(b, depth));
+            la.is_some() && la == lb
+        })
+        .count();
+    Some(matched as f64 / samples as f64)
+}
+
+/// 日付の通し日数（1970-01-01を0とする）
+fn day_number(date: SurveyStartDate) -> i64 {
+    let (y, m, d) = (date.year as i64, date.month as i64, date.day as i64);
+    let y = if m <= 2 { y - 1 } else { y };
+    let era = y.div_euclid(400);
+    let yoe = y - era * 400;
+    let doy = (153 * ((m + 9) % 12) + 2) / 5 + d - 1;
+    let doe = yoe * 365 + yoe / 4 - yoe / 100 + doy;
+    era * 146_097 + doe - 719_468
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    struct Doc {
+        name: &'static str,
+        date: SurveyStartDate,
+        length: f64,
+        layers: Vec<(f64, &'static str)>,
+    }
+
+    impl SurveyDate for Doc {
+        fn survey_start_date(&self) -> Option<SurveyStartDate> {
+            Some(self.date)
+        }
+    }
+
+    impl SoilLayers for Doc {
+        fn soil_layers(&self) -> Vec<SoilLayer> {
+            let mut top_depth = 0.0;
+            self.layers
+                .iter()
+                .map(|&(bottom_depth, symbol)| {
+                    let layer = SoilLayer { top_depth, bottom_depth, name: None, symbol: Some(symbol.to_string()) };
+                    top_depth = bottom_depth;
+                    layer
+                })
+                .collect()
+        }
+    }
+
+    impl BoringHeader for Doc {
+        fn boring_name(&self) -> Option<&str> {
+            Some(self.name)
+        }
+
+        fn total_length(&self) -> Option<f64> {
+            Some(self.length)
+        }
+    }
+
+    fn doc(name: &'static str, day: u32, length: f64, layers: Vec<(f64, &'static str)>) -> Doc {
+        Doc { name, date: SurveyStartDate::new(2015, 6, day), length, layers }
+    }
+
+    #[test]
+    fn test_find_groups_duplicates() {
+        let layers = vec![(2.0, "B"), (6.0, "As"), (10.0, "Ds")];
+        let docs = vec![
+            doc("No.1", 1, 10.0, layers.clone()),
+            doc("Bor.1", 3, 10.0, layers.clone()),
+            doc("B-2", 1, 15.0, vec![(1.0, "B"), (15.0, "Ac")]),
+            doc("Ｂｏｒ．１", 1, 10.0, layers),
+        ];
+        let step = 1.0 / 111_000.0;
+        let locations = vec![
+            Some((35.0, 135.0)),
+            Some((35.0 + 2.0 * step, 135.0)),
+            Some((35.0 + 5.0 * step, 135.0)),
+            Some((35.0 + 20.0 * step, 135.0)),
+        ];
+        let collection = BoringCollection::from_locations(docs, locations);
+        let groups = DuplicateFinder::default().find(&collection);
+        assert_eq!(groups.len(), 1);
+        assert_eq!(groups[0].members, vec![0, 1, 3]);
+        assert!(groups[0].pairs.iter().all(|p| p.score.total >= 0.75));
+    }
+
+    #[test]
+    fn test_name_similarity_and_days() {
+        assert_eq!(name_similarity("No.1", "１"), 1.0);
+        assert_eq!(name_similarity("Bor.1", "B-1"), 0.8);
+        assert!(name_similarity("BV-1", "BV-2") < 0.8);
+        // 番号が同じでも英字の有無が異なれば下限を適用しない
+        assert_eq!(name_similarity("No.1", "B-1"), 0.5);
+        assert!(name_similarity("No.1", "B-1") < DuplicateFinder::default().threshold);
+        assert!(name_similarity("1", "BV-1") < 0.8);
+        assert_eq!(day_number(SurveyStartDate::new(1970, 1, 1)), 0);
+        assert_eq!(day_number(SurveyStartDate::new(2016, 3, 1)) - day_number(SurveyStartDate::new(2016, 2, 28)), 2);
+    }
+}
@@ -0,0 +1,577 @@
+//! 動的弾性係数算定モジュール
+//!
+//! P波・S波速度検層の区間速度と密度から、区間ごとの動的ポアソン比・
+//! 動的せん断弾性係数G0・動的ヤング率E0を算定する。
+//!
+//! # 算定式
+//! - ポアソン比: ν = (Vp² - 2Vs²) / (2(Vp² - Vs²))
+//! - せん断弾性係数: G0 = ρ・Vs²
+//! - ヤング率: E0 = 2(1 + ν)・G0
+//!
+//! 密度ρ (t/m³) と速度 (m/s) から求めた係数の単位は kN/m² となる。
+//!
+//! # 密度の採用順
+//! 1. 土質試験結果の湿潤密度（v1.10）
+//! 2. コア質量から求めた密度（v4.00、コア径の指定が必要）
+//! 3. 土質岩種区分に応じた既定値（[`DensityTable`]）
+
+use serde::Serialize;
+use std::f64::consts::PI;
+
+use crate::layer::{SoilLayer, SoilLayers, last_keyword, layer_at};
+use crate::types::{csv_field, parse_f64};
+
+/// 深度区間ごとの値（速度、密度など）
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+pub struct DepthInterval {
+    /// 上端深度 (m)
+    pub top_depth: f64,
+    /// 下端深度 (m)
+    pub bottom_depth: f64,
+    /// 区間の値
+    pub value: f64,
+}
+
+impl DepthInterval {
+    fn contains(&self, depth: f64) -> bool {
+        self.top_depth <= depth && depth < self.bottom_depth
+    }
+
+    fn from_strs(top: Option<&str>, bottom: Option<&str>, value: Option<&str>) -> Option<Self> {
+        Some(Self {
+            top_depth: parse_f64(top)?,
+            bottom_depth: parse_f64(bottom)?,
+            value: parse_f64(value)?,
+        })
+    }
+}
+
+/// コア質量の測定値（v4.00）
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct CoreMassRecord {
+    /// 上端深度 (m)（直上の測定記録の下端深度）
+    pub top_depth: f64,
+    /// 下端深度 (m)
+    pub bottom_depth: f64,
+    /// コア質量 (kg)
+    pub mass: f64,
+}
+
+impl CoreMassRecord {
+    /// コア径 (m) を仮定した密度 (t/m³)
+    pub fn density(&self, core_diameter: f64) -> Option<f64> {
+        let length = self.bottom_depth - self.top_depth;
+        let volume = PI / 4.0 * core_diameter * core_diameter * length;
+        (volume > 0.0).then(|| self.mass / 1000.0 / volume)
+    }
+}
+
+/// 採用した密度の出典
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub enum DensitySource {
+    /// 土質試験結果_湿潤密度
+    SoilTest,
+    /// コア質量とコア径から算定
+    CoreMass,
+    /// 土質岩種区分に応じた既定値
+    SoilType(String),
+    /// 土質岩種区分が不明なための既定値
+    Default,
+}
+
+/// 土質岩種名ごとの既定密度表
+///
+/// 土質名は主たる構成要素が末尾に来る（例: "シルト質砂"は砂、"砂岩"は岩）ため、
+/// 土質名中で最も後ろに現れるキーワードの密度を採用する。
+#[derive(Debug, Clone, PartialEq)]
+pub struct DensityTable {
+    /// (キーワード, 湿潤密度 t/m³)
+    entries: Vec<(String, f64)>,
+    /// どのキーワードにも該当しない場合の密度 (t/m³)
+    default_density: f64,
+}
+
+impl Default for DensityTable {
+    fn default() -> Self {
+        let entries = [
+            ("腐植", 1.3),
+            ("泥炭", 1.2),
+            ("ローム", 1.4),
+            ("粘土", 1.6),
+            ("シルト", 1.7),
+            ("砂", 1.8),
+            ("礫", 2.0),
+            ("玉石", 2.0),
+            ("埋土", 1.8),
+            ("盛土", 1.8),
+            ("岩", 2.3),
+        ];
+        Self::new(
+            entries.iter().map(|(k, v)| (k.to_string(), *v)).collect(),
+            1.8,
+        )
+    }
+}
+
+impl DensityTable {
+    /// キーワードと密度 (t/m³) の組から密度表を作成
+    pub fn new(entries: Vec<(String, f64)>, default_density: f64) -> Self {
+        Self { entries, default_density }
+    }
+
+    /// 土質岩種名に対応する密度 (t/m³) を取得
+    ///
+    /// 複数のキーワードを含む場合は最も後ろに現れるものを採用する。該当するキーワードがない場合はNone
+    pub fn lookup(&self, soil_name: &str) -> Option<f64> {
+        last_keyword(soil_name, &self.entries)
+    }
+
+    /// 該当キーワードがない場合の既定密度 (t/m³)
+    pub fn default_density(&self) -> f64 {
+        self.default_density
+    }
+}
+
+/// 区間ごとの動的弾性係数
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct ElasticModulusInterval {
+    /// 上端深度 (m)
+    pub top_depth: f64,
+    /// 下端深度 (m)
+    pub bottom_depth: f64,
+    /// P波速度 (m/s)
+    pub vp: Option<f64>,
+    /// S波速度 (m/s)
+    pub vs: Option<f64>,
+    /// 密度 (t/m³)
+    pub density: f64,
+    /// 密度の出典
+    pub density_source: DensitySource,
+    /// 動的ポアソン比
+    pub poisson_ratio: Option<f64>,
+    /// 動的せん断弾性係数G0 (kN/m²)
+    pub shear_modulus: Option<f64>,
+    /// 動的ヤング率E0 (kN/m²)
+    pub youngs_modulus: Option<f64>,
+}
+
+/// 動的ポアソン比を算定
+///
+/// Vp ≦ Vs の場合は物理的に成立しないためNone
+pub fn poisson_ratio(vp: f64, vs: f64) -> Option<f64> {
+    if vp <= vs || vs <= 0.0 {
+        return None;
+    }
+    let vp2 = vp * vp;
+    let vs2 = vs * vs;
+    Some((vp2 - 2.0 * vs2) / (2.0 * (vp2 - vs2)))
+}
+
+/// 動的弾性係数の算定に必要なデータを取得するためのトレイト
+pub trait ElasticWaveSource: SoilLayers {
+    /// P波試験の区間速度 (m/s)
+    fn p_wave_velocities(&self) -> Vec<DepthInterval>;
+
+    /// S波試験の区間速度 (m/s)
+    fn s_wave_velocities(&self) -> Vec<DepthInterval>;
+
+    /// 土質試験結果の湿潤密度 (t/m³)
+    fn measured_densities(&self) -> Vec<DepthInterval> {
+        Vec::new()
+    }
+
+    /// コア質量の測定値
+    fn core_masses(&self) -> Vec<CoreMassRecord> {
+        Vec::new()
+    }
+}
+
+/// 動的弾性係数の算定器
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct ElasticModuliCalculator {
+    /// 既定密度表
+    density_table: DensityTable,
+    /// コア質量から密度を求める際のコア径 (m)
+    core_diameter: Option<f64>,
+}
+
+impl ElasticModuliCalculator {
+    /// 密度表とコア径 (m) を指定して算定器を作成
+    ///
+    /// コア質量はコア径がわからないと体積が求まらないため、`core_diameter` を
+    /// 指定した場合のみ密度の算定に使用する。既定の密度表でよい場合は `default()` を使う。
+    pub fn new(density_table: DensityTable, core_diameter: Option<f64>) -> Self {
+        Self { density_table, core_diameter }
+    }
+
+    /// ボーリングデータから区間ごとの動的弾性係数を算定
+    ///
+    /// # Example
+    /// ```ignore
+    /// let calculator = ElasticModuliCalculator::new(DensityTable::default(), Some(0.05));
+    /// let intervals = calculator.calculate(&boring);
+    /// let csv = to_csv(&intervals);
+    /// ```
+    pub fn calculate<T: ElasticWaveSource>(&self, boring: &T) -> Vec<ElasticModulusInterval> {
+        self.calculate_from(
+            &boring.p_wave_velocities(),
+            &boring.s_wave_velocities(),
+            &boring.measured_densities(),
+            &boring.core_masses(),
+            &boring.soil_layers(),
+        )
+    }
+
+    /// 速度・密度・層区分から区間ごとの動的弾性係数を算定
+    ///
+    /// P波・S波の区間境界をすべて合わせた細分区間ごとに算定する。
+    /// どちらの速度も得られない区間は出力しない。
+    pub fn calculate_from(
+        &self,
+        p_waves: &[DepthInterval],
+        s_waves: &[DepthInterval],
+        densities: &[DepthInterval],
+        core_masses: &[CoreMassRecord],
+        layers: &[SoilLayer],
+    ) -> Vec<ElasticModulusInterval> {
+        let mut boundaries: Vec<f64> = p_waves
+            .iter()
+            .chain(s_waves.iter())
+            .flat_map(|interval| [interval.top_depth, interval.bottom_depth])
+            .collect();
+        boundaries.sort_by(f64::total_cmp);
+        boundaries.dedup();
+
+        boundaries
+            .windows(2)
+            .filter_map(|pair| {
+                let (top_depth, bottom_depth) = (pair[0], pair[1]);
+                let mid = (top_depth + bottom_depth) / 2.0;
+                let vp = p_waves.iter().find(|i| i.contains(mid)).map(|i| i.value);
+                let vs = s_waves.iter().find(|i| i.contains(mid)).map(|i| i.value);
+                if vp.is_none() && vs.is_none() {
+                    return None;
+                }
+
+                let (density, density_source) =
+                    self.density_at(mid, densities, core_masses, layers);
+                let poisson_ratio = vp.zip(vs).and_then(|(vp, vs)| poisson_ratio(vp, vs));
+                let shear_modulus = vs.map(|vs| density * vs * vs);
+                let youngs_modulus = shear_modulus
+                    .zip(poisson_ratio)
+                    .map(|(g, nu)| 2.0 * (1.0 + nu) * g);
+
+                Some(ElasticModulusInterval {
+                    top_depth,
+                    bottom_depth,
+                    vp,
+                    vs,
+                    density,
+                    density_source,
+                    poisson_ratio,
+                    shear_modulus,
+                    youngs_modulus,
+                })
+            })
+            .collect()
+    }
+
+    /// 指定深度で採用する密度 (t/m³) とその出典
+    fn density_at(
+        &self,
+        depth: f64,
+        densities: &[DepthInterval],
+        core_masses: &[CoreMassRecord],
+        layers: &[SoilLayer],
+    ) -> (f64, DensitySource) {
+        if let Some(measured) = densities.iter().find(|d| d.contains(depth)) {
+            return (measured.value, DensitySource::SoilTest);
+        }
+
+        if let Some(core_diameter) = self.core_diameter {
+            let from_core = core_masses
+                .iter()
+                .find(|m| m.top_depth <= depth && depth < m.bottom_depth)
+                .and_then(|m| m.density(core_diameter));
+            if let Some(density) = from_core {
+                return (density, DensitySource::CoreMass);
+            }
+        }
+
+        if let Some(name) = layer_at(layers, depth).and_then(|layer| layer.name.as_deref())
+            && let Some(density) = self.density_table.lookup(name)
+        {
+            return (density, DensitySource::SoilType(name.to_string()));
+        }
+
+        (self.density_table.default_density(), DensitySource::Default)
+    }
+}
+
+/// 算定結果をCSV文字列に変換
+///
+/// FEMプリプロセッサ等への受け渡し用。値がない項目は空欄とする。
+pub fn to_csv(intervals: &[ElasticModulusInterval]) -> String {
+    let mut csv = String::from(
+        "top_depth_m,bottom_depth_m,vp_m_s,vs_m_s,density_t_m3,density_source,poisson_ratio,g0_kn_m2,e0_kn_m2\n",
+    );
+    let opt = |v: Option<f64>| v.map(|v| format!("{}", v)).unwrap_or_default();
+
+    for i in intervals {
+        let source = match &i.density_source {
+            DensitySource::SoilTest => "soil_test".to_string(),
+            DensitySource::CoreMass => "core_mass".to_string(),
+            DensitySource::SoilType(name) => format!("soil_type:{}", name),
+            DensitySource::Default => "default".to_string(),
+        };
+        csv.push_str(&format!(
+            "{},{},{},{},{},{},{},{},{}\n",
+            i.top_depth,
+            i.bottom_depth,
+            opt(i.vp),
+            opt(i.vs),
+            i.density,
+            csv_field(&source),
+            opt(i.poisson_ratio),
+            opt(i.shear_modulus),
+            opt(i.youngs_modulus),
+        ));
+    }
+
+    csv
+}
+
+/// コア質量の記録（下端深度、コア質量）を測定区間に変換
+///
+/// DTDには下端深度しかないため、上端深度は直上の記録の下端深度とする。
+/// 最初の記録は上端深度がわからず（地表から採取したとは限らない）体積が求まらないので、
+/// 次の記録の上端深度としてのみ使う。
+fn core_mass_records<'a, I>(rows: I) -> Vec<CoreMassRecord>
+where
+    I: IntoIterator<Item = (Option<&'a str>, Option<&'a str>)>,
+{
+    let mut records = Vec::new();
+    let mut top_depth = None;
+    for (depth, mass) in rows {
+        let Some(bottom_depth) = parse_f64(depth) else {
+            continue;
+        };
+        if let Some((top_depth, mass)) = top_depth.zip(parse_f64(mass)) {
+            records.push(CoreMassRecord { top_depth, bottom_depth, mass });
+        }
+        top_depth = Some(bottom_depth);
+    }
+    records
+}
+
+fn wave_intervals<'a, I>(rows: I) -> Vec<DepthInterval>
+where
+    I: IntoIterator<Item = (Option<&'a str>, Option<&'a str>, Option<&'a str>)>,
+{
+    rows.into_iter()
+        .filter_map(|(top, bottom, velocity)| DepthInterval::from_strs(top, bottom, velocity))
+        .collect()
+}
+
+impl ElasticWaveSource for crate::boring_structs_400::Boring400 {
+    fn p_wave_velocities(&self) -> Vec<DepthInterval> {
+        wave_intervals(self.core.p_wave_tests.iter().map(|t| {
+            (t.start_depth.as_deref(), t.end_depth.as_deref(), t.velocity.as_deref())
+        }))
+    }
+
+    fn s_wave_velocities(&self) -> Vec<DepthInterval> {
+        wave_intervals(self.core.s_wave_tests.iter().map(|t| {
+            (t.start_depth.as_deref(), t.end_depth.as_deref(), t.velocity.as_deref())
+        }))
+    }
+
+    fn core_masses(&self) -> Vec<CoreMassRecord> {
+        core_mass_records(self.core.core_masses.iter().map(|m| (m.depth.as_deref(), m.core_mass.as_deref())))
+    }
+}
+
+impl ElasticWaveSource for crate::boring_structs_300::Boring300 {
+    fn p_wave_velocities(&self) -> Vec<DepthInterval> {
+        wave_intervals(self.core.p_wave_tests.iter().map(|t| {
+            (t.start_depth.as_deref(), t.end_depth.as_deref(), t.velocity.as_deref())
+        }))
+    }
+
+    fn s_wave_velocities(&self) -> Vec<DepthInterval> {
+        wave_intervals(self.core.s_wave_tests.iter().map(|t| {
+            (t.start_depth.as_deref(), t.end_depth.as_deref(), t.velocity.as_deref())
+        }))
+    }
+}
+
+impl ElasticWaveSource for crate::boring_structs_210::Boring210 {
+    fn p_wave_velocities(&self) -> Vec<DepthInterval> {
+        wave_intervals(self.core.p_wave_tests.iter().map(|t| {
+            (t.start_depth.as_deref(), t.end_depth.as_deref(), t.velocity.as_deref())
+        }))
+    }
+
+    fn s_wave_velocities(&self) -> Vec<DepthInterval> {
+        wave_intervals(self.core.s_wave_tests.iter().map(|t| {
+            (t.start_depth.as_deref(), t.end_depth.as_deref(), t.velocity.as_deref())
+        }))
+    }
+}
+
+impl ElasticWaveSource for crate::boring_structs_200::Boring200 {
+    fn p_wave_velocities(&self) -> Vec<DepthInterval> {
+        wave_intervals(self.core.p_wave_tests.iter().map(|t| {
+            (t.start_depth.as_deref(), t.end_depth.as_deref(), t.velocity.as_deref())
+        }))
+    }
+
+    fn s_wave_velocities(&self) -> Vec<DepthInterval> {
+        wave_intervals(self.core.s_wave_tests.iter().map(|t| {
+            (t.start_depth.as_deref(), t.end_depth.as_deref(), t.velocity.as_deref())
+        }))
+    }
+}
+
+impl ElasticWaveSource for crate::boring_structs_110::Boring110 {
+    fn p_wave_velocities(&self) -> Vec<DepthInterval> {
+        wave_intervals(self.core.p_wave_test.iter().map(|t| {
+            (t.start_depth.as_deref(), t.end_depth.as_deref(), t.velocity.as_deref())
+        }))
+    }
+
+    fn s_wave_velocities(&self) -> Vec<DepthInterval> {
+        wave_intervals(self.core.s_wave_test.iter().map(|t| {
+            (t.start_depth.as_deref(), t.end_depth.as_deref(), t.velocity.as_deref())
+        }))
+    }
+
+    fn measured_densities(&self) -> Vec<DepthInterval> {
+        self.core
+            .soil_test_result
+            .iter()
+            .filter_map(|r| {
+                DepthInterval::from_strs(
+                    r.start_depth.as_deref(),
+                    r.end_depth.as_deref(),
+                    r.wet_density.as_deref(),
+                )
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::layer::layers_from_bottom_depths;
+
+    fn interval(top_depth: f64, bottom_depth: f64, value: f64) -> DepthInterval {
+        DepthInterval { top_depth, bottom_depth, value }
+    }
+
+    #[test]
+    fn test_poisson_ratio() {
+        // Vp = √3・Vs → ν = 0.25
+        let nu = poisson_ratio(3.0_f64.sqrt() * 200.0, 200.0).unwrap();
+        assert!((nu - 0.25).abs() < 1e-9);
+
+        // Vp ≦ Vs は不成立
+        assert!(poisson_ratio(200.0, 200.0).is_none());
+    }
+
+    #[test]
+    fn test_density_table_uses_last_keyword() {
+        let table = DensityTable::default();
+        assert_eq!(table.lookup("シルト質砂"), Some(1.8));
+        assert_eq!(table.lookup("砂質シルト"), Some(1.7));
+        assert_eq!(table.lookup("砂岩"), Some(2.3));
+        assert_eq!(table.lookup("礫混じり砂"), Some(1.8));
+        assert_eq!(table.lookup("不明"), None);
+    }
+
+    #[test]
+    fn test_calculate_merges_p_and_s_intervals() {
+        let p_waves = vec![interval(0.0, 2.0, 1500.0), interval(2.0, 6.0, 1800.0)];
+        let s_waves = vec![interval(0.0, 4.0, 200.0), interval(4.0, 6.0, 300.0)];
+        let layers = layers_from_bottom_depths(vec![
+            (Some("3.0"), Some("砂"), None),
+            (Some("6.0"), Some("粘土"), None),
+        ]);
+
+        let result = ElasticModuliCalculator::default()
+            .calculate_from(&p_waves, &s_waves, &[], &[], &layers);
+
+        assert_eq!(result.len(), 3);
+        assert_eq!((result[0].top_depth, result[0].bottom_depth), (0.0, 2.0));
+        assert_eq!((result[1].top_depth, result[1].bottom_depth), (2.0, 4.0));
+        assert_eq!(result[2].vs, Some(300.0));
+
+        // G0 = 1.8 × 200² = 72000 kN/m²
+        assert_eq!(result[0].density_source, DensitySource::SoilType("砂".to_string()));
+        assert!((result[0].shear_modulus.unwrap() - 72000.0).abs() < 1e-6);
+
+        let nu = result[0].poisson_ratio.unwrap();
+        let e0 = result[0].youngs_modulus.unwrap();
+        assert!((e0 - 2.0 * (1.0 + nu) * 72000.0).abs() < 1e-6);
+
+        // 3.0mより深い区間は粘土の密度
+        assert_eq!(result[2].density, 1.6);
+    }
+
+    #[test]
+    fn test_density_priority() {
+        let s_waves = vec![interval(0.0, 2.0, 100.0), interval(2.0, 4.0, 100.0)];
+        let densities = vec![interval(0.5, 1.5, 1.95)];
+        let core_masses = vec![CoreMassRecord { top_depth: 2.0, bottom_depth: 4.0, mass: 10.0 }];
+
+        let without_core = ElasticModuliCalculator::default()
+            .calculate_from(&[], &s_waves, &densities, &core_masses, &[]);
+        assert_eq!(without_core[0].density_source, DensitySource::SoilTest);
+        assert_eq!(without_core[0].density, 1.95);
+        assert_eq!(without_core[1].density_source, DensitySource::Default);
+        assert!(without_core[1].poisson_ratio.is_none());
+
+        let with_core = ElasticModuliCalculator::new(DensityTable::default(), Some(0.05))
+            .calculate_from(&[], &s_waves, &densities, &core_masses, &[]);
+        assert_eq!(with_core[1].density_source, DensitySource::CoreMass);
+        // 10kg / (π/4 × 0.05² × 2m) ≒ 2.546 t/m³
+        assert!((with_core[1].density - 2.546).abs() < 0.001);
+    }
+
+    #[test]
+    fn test_core_mass_records_skip_first_record() {
+        // 最初の記録（下端3.0m）は上端深度がわからないので区間にしない
+        let rows = vec![
+            (Some("3.00"), Some("4.2")),
+            (Some("4.00"), Some("3.9")),
+            (None, Some("1.0")),
+            (Some("5.50"), Some("6.1")),
+        ];
+        let records = core_mass_records(rows);
+        assert_eq!(
+            records,
+            vec![
+                CoreMassRecord { top_depth: 3.0, bottom_depth: 4.0, mass: 3.9 },
+                CoreMassRecord { top_depth: 4.0, bottom_depth: 5.5, mass: 6.1 },
+            ]
+        );
+        assert!(core_mass_records(vec![(Some("1.00"), Some("2.0"))]).is_empty());
+    }
+
+    #[test]
+    fn test_to_csv() {
+        let result = ElasticModuliCalculator::default()
+            .calculate_from(&[], &[interval(0.0, 1.0, 100.0)], &[], &[], &[]);
+        let csv = to_csv(&result);
+        let lines: Vec<&str> = csv.lines().collect();
+        assert_eq!(lines.len(), 2);
+        assert_eq!(lines[1], "0,1,,100,1.8,default,,18000,");
+
+        let layers = layers_from_bottom_depths(vec![(Some("1.0"), Some("砂,礫混じり"), None)]);
+        let result = ElasticModuliCalculator::default()
+            .calculate_from(&[], &[interval(0.0, 1.0, 100.0)], &[], &[], &layers);
+        let csv = to_csv(&result);
+        assert!(csv.lines().nth(1).unwrap().contains(",\"soil_type:砂,礫混じり\","));
+    }
+}
@@ -0,0 +1,538 @@
+//! 深度の標高換算モジュール
+//!
+//! 孔口標高と孔跡（[`Trajectory`]）から、層・標準貫入試験・孔内水位と、孔内水平載荷試験・
+//! 透水試験・ルジオン試験・P波/S波速度の試験区間の深度を標高に換算する。
+//! 斜めボーリングでは掘進長ではなく鉛直深度で換算する。
+//!
+//! 孔口標高には "T.P.+12.34"、"D.L.-1.2"、"標高 5.0m" のように基準面を前置した記載がある。
+//! [`CollarElevation::parse`] は基準面と値を分けて読み、基準面の記載がなければT.P.とする。
+//! 桁区切りのカンマ（"1,234.5"）は除き、基準面の記載に数字が残る場合（"BM1 5.0" など）は読まない。
+//! 孔口標高がない場合は [`ElevationError::Missing`] を返す。孔口を0mとした相対標高
+//! （[`HeightDatum::GroundLevel`]）で代用するときは [`DepthElevations::elevation_profile_or_ground_level`] を使う。
+
+use serde::Serialize;
+use thiserror::Error;
+
+use crate::elastic::{DepthInterval, ElasticWaveSource};
+use crate::groundwater::{GroundwaterLevels, WaterLevelReading, WaterLevelSeries};
+use crate::lateral_load::LateralLoadTests;
+use crate::layer::{SoilLayer, SoilLayers};
+use crate::local_coordinate::{normalize, HeightDatum};
+use crate::lugeon::LugeonTests;
+use crate::permeability::PermeabilityTests;
+use crate::spt::{SptRecord, StandardPenetrationTests};
+use crate::trajectory::{BoreholeTrajectory, Trajectory};
+use crate::types::csv_field;
+
+/// 標高換算のエラー
+#[derive(Error, Debug, Clone, PartialEq)]
+pub enum ElevationError {
+    #[error("孔口標高がありません")]
+    Missing,
+
+    #[error("孔口標高を数値として読めません: {0}")]
+    Invalid(String),
+}
+
+/// 孔口標高
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct CollarElevation {
+    /// 基準面
+    pub datum: HeightDatum,
+    /// 標高 (m)
+    pub value: f64,
+}
+
+impl CollarElevation {
+    /// 孔口標高の記載を読む
+    ///
+    /// # Example
+    /// ```ignore
+    /// let collar = CollarElevation::parse(Some("T.P.+12.34"))?;
+    /// assert_eq!((collar.datum, collar.value), (HeightDatum::TokyoPeil, 12.34));
+    /// ```
+    pub fn parse(value: Option<&str>) -> Result<Self, ElevationError> {
+        let raw = value.map(str::trim).filter(|v| !v.is_empty()).ok_or(ElevationError::Missing)?;
+        // 空白は区切りとして残す（"BM1 5.0" を "BM15.0" と読まない）。符号・区切りの後の空白は詰める
+        let mut text = strip_thousands(&raw.split_whitespace().map(normalize).collect::<Vec<_>>().join(" "));
+        for sign in ["+", "-", "=", ":"] {
+            text = text.replace(&format!("{} ", sign), sign);
+        }
+        let text = text.trim_end_matches('M').trim_end();
+
+        // 前置された基準面の後ろで、数値として読める最初の位置
+        let (label, number) = text
+            .char_indices()
+            .map(|(i, _)| text.split_at(i))
+            .find_map(|(label, rest)| {
+                let number = rest.trim_start_matches(['=', ':', ' ']).parse::<f64>().ok()?;
+                number.is_finite().then_some((label, number))
+            })
+            .ok_or_else(|| ElevationError::Invalid(raw.to_string()))?;
+
+        let label = label.trim_end_matches(['=', ':', ' ']);
+        // 基準面に数字が残るのは数値の一部を取り違えている
+        if label.contains(|c: char| c.is_ascii_digit()) {
+            return Err(ElevationError::Invalid(raw.to_string()));
+        }
+        let datum = if label.is_empty() {
+            HeightDatum::TokyoPeil
+        } else {
+            HeightDatum::from_label(label).unwrap_or_else(|| HeightDatum::Other(label.to_string()))
+        };
+        Ok(Self { datum, value: number })
+    }
+
+    /// 孔口を0mとする相対標高
+    pub fn ground_level() -> Self {
+        Self {
+            datum: HeightDatum::GroundLevel,
+            value: 0.0,
+        }
+    }
+}
+
+/// 層の上端・下端標高
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct LayerElevation {
+    /// 層
+    pub layer: SoilLayer,
+    /// 上端標高 (m)
+    pub top_elevation: f64,
+    /// 下端標高 (m)
+    pub bottom_elevation: f64,
+}
+
+/// 標準貫入試験の標高
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+pub struct SptElevation {
+    /// 標準貫入試験
+    pub record: SptRecord,
+    /// 開始深度の標高 (m)
+    pub start_elevation: f64,
+    /// 試験区間中央の標高 (m)
+    pub mid_elevation: f64,
+}
+
+/// 孔内水位の標高
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct WaterLevelElevation {
+    /// 測定
+    pub reading: WaterLevelReading,
+    /// 水位標高 (m)。水位なしの場合はNone
+    pub elevation: Option<f64>,
+}
+
+/// 原位置試験の種別
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+pub enum TestKind {
+    /// 孔内水平載荷試験（値は変形係数 kN/m²）
+    LateralLoad,
+    /// 透水試験（値は透水係数 m/s）
+    Permeability,
+    /// ルジオン試験（値は報告されたルジオン値）
+    Lugeon,
+    /// P波速度（値は m/s）
+    PWave,
+    /// S波速度（値は m/s）
+    SWave,
+}
+
+impl TestKind {
+    /// CSVに出力する名称
+    pub fn label(&self) -> &'static str {
+        match self {
+            TestKind::LateralLoad => "lateral_load",
+            TestKind::Permeability => "permeability",
+            TestKind::Lugeon => "lugeon",
+            TestKind::PWave => "p_wave",
+            TestKind::SWave => "s_wave",
+        }
+    }
+}
+
+/// 原位置試験の試験区間の標高
+///
+/// 孔内水平載荷試験のように深度が1点の試験は、上端と下端を同じ深度とする。
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+pub struct TestElevation {
+    /// 試験の種別
+    pub kind: TestKind,
+    /// 上端深度 (m)
+    pub top_depth: f64,
+    /// 下端深度 (m)
+    pub bottom_depth: f64,
+    /// 上端標高 (m)
+    pub top_elevation: f64,
+    /// 下端標高 (m)
+    pub bottom_elevation: f64,
+    /// 試験結果の代表値（単位は [`TestKind`] を参照）
+    pub value: Option<f64>,
+}
+
+/// 深度を標高に換算するための孔口標高と孔跡
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct ElevationProfile {
+    /// 孔口標高
+    pub collar: CollarElevation,
+    /// 孔跡
+    pub trajectory: Trajectory,
+}
+
+impl ElevationProfile {
+    /// 孔口標高と孔跡から作成
+    pub fn new(collar: CollarElevation, trajectory: Trajectory) -> Self {
+        Self { collar, trajectory }
+    }
+
+    /// 掘進長（XMLの深度）の標高 (m)
+    pub fn elevation_at(&self, depth: f64) -> f64 {
+        self.collar.value - self.trajectory.point_at(depth).vertical_depth
+    }
+
+    /// 層の上端・下端標高
+    pub fn layers(&self, layers: &[SoilLayer]) -> Vec<LayerElevation> {
+        layers
+            .iter()
+            .map(|layer| LayerElevation {
+                layer: layer.clone(),
+                top_elevation: self.elevation_at(layer.top_depth),
+                bottom_elevation: self.elevation_at(layer.bottom_depth),
+            })
+            .collect()
+    }
+
+    /// 標準貫入試験の標高
+    pub fn spt(&self, records: &[SptRecord]) -> Vec<SptElevation> {
+        records
+            .iter()
+            .map(|r| SptElevation {
+                record: *r,
+                start_elevation: self.elevation_at(r.start_depth),
+                mid_elevation: self.elevation_at(r.mid_depth()),
+            })
+            .collect()
+    }
+
+    /// 孔内水位の標高
+    pub fn water_levels(&self, series: &WaterLevelSeries) -> Vec<WaterLevelElevation> {
+        series
+            .readings
+            .iter()
+            .map(|r| WaterLevelElevation {
+                reading: r.clone(),
+                elevation: r.depth.map(|d| self.elevation_at(d)),
+            })
+            .collect()
+    }
+
+    /// 試験区間の標高
+    pub fn test(&self, kind: TestKind, top_depth: f64, bottom_depth: f64, value: Option<f64>) -> TestElevation {
+        TestElevation {
+            kind,
+            top_depth,
+            bottom_depth,
+            top_elevation: self.elevation_at(top_depth),
+            bottom_elevation: self.elevation_at(bottom_depth),
+            value,
+        }
+    }
+
+    /// 深度区間ごとの値（P波・S波速度など）の標高
+    pub fn intervals(&self, kind: TestKind, intervals: &[DepthInterval]) -> Vec<TestElevation> {
+        intervals
+            .iter()
+            .map(|i| self.test(kind, i.top_depth, i.bottom_depth, Some(i.value)))
+            .collect()
+    }
+}
+
+/// 深度を標高に換算するためのトレイト
+pub trait DepthElevations: BoreholeTrajectory + SoilLayers + StandardPenetrationTests + GroundwaterLevels {
+    /// 孔口標高と孔跡を取得
+    fn elevation_profile(&self) -> Result<ElevationProfile, ElevationError> {
+        let collar = CollarElevation::parse(self.collar_elevation().as_deref())?;
+        Ok(self.profile_with(collar))
+    }
+
+    /// 孔口標高と孔跡を取得（孔口標高がない・読めない場合は孔口を0mとする）
+    fn elevation_profile_or_ground_level(&self) -> ElevationProfile {
+        self.elevation_profile()
+            .unwrap_or_else(|_| self.profile_with(CollarElevation::ground_level()))
+    }
+
+    /// 層の上端・下端標高
+    ///
+    /// # Example
+    /// ```ignore
+    /// for l in boring.layer_elevations()? {
+    ///     println!("{:?}: {:.2} 〜 {:.2}", l.layer.name, l.top_elevation, l.bottom_elevation);
+    /// }
+    /// ```
+    fn layer_elevations(&self) -> Result<Vec<LayerElevation>, ElevationError> {
+        Ok(self.elevation_profile()?.layers(&self.soil_layers()))
+    }
+
+    /// 標準貫入試験の標高
+    fn spt_elevations(&self) -> Result<Vec<SptElevation>, ElevationError> {
+        Ok(self.elevation_profile()?.spt(&self.spt_records()))
+    }
+
+    /// 孔内水位の標高
+    fn water_level_elevations(&self) -> Result<Vec<WaterLevelElevation>, ElevationError> {
+        Ok(self.elevation_profile()?.water_levels(&self.water_level_series()))
+    }
+
+    /// 孔内水平載荷試験の試験深度の標高
+    fn lateral_load_elevations(&self) -> Result<Vec<TestElevation>, ElevationError>
+    where
+        Self: LateralLoadTests,
+    {
+        let profile = self.elevation_profile()?;
+        Ok(self
+            .lateral_load_tests()
+            .iter()
+            .map(|t| profile.test(TestKind::LateralLoad, t.depth, t.depth, t.deformation_modulus))
+            .collect())
+    }
+
+    /// 透水試験の試験区間の標高（深度のない試験は除く）
+    fn permeability_elevations(&self) -> Result<Vec<TestElevation>, ElevationError>
+    where
+        Self: PermeabilityTests,
+    {
+        let profile = self.elevation_profile()?;
+        Ok(self
+            .permeability_tests()
+            .iter()
+            .filter_map(|t| {
+                let value = t.coefficient.map(|c| c.value);
+                Some(profile.test(TestKind::Permeability, t.top_depth?, t.bottom_depth?, value))
+            })
+            .collect())
+    }
+
+    /// ルジオン試験の試験区間の標高（深度のない試験は除く、v1.10にはない）
+    fn lugeon_elevations(&self) -> Result<Vec<TestElevation>, ElevationError>
+    where
+        Self: LugeonTests,
+    {
+        let profile = self.elevation_profile()?;
+        Ok(self
+            .lugeon_tests()
+            .iter()
+            .filter_map(|t| {
+                Some(profile.test(TestKind::Lugeon, t.top_depth?, t.bottom_depth?, t.reported_lugeon_value))
+            })
+            .collect())
+    }
+
+    /// P波・S波速度の測定区間の標高
+    fn elastic_wave_elevations(&self) -> Result<Vec<TestElevation>, ElevationError>
+    where
+        Self: ElasticWaveSource,
+    {
+        let profile = self.elevation_profile()?;
+        let mut elevations = profile.intervals(TestKind::PWave, &self.p_wave_velocities());
+        elevations.extend(profile.intervals(TestKind::SWave, &self.s_wave_velocities()));
+        Ok(elevations)
+    }
+
+    /// 孔口標高を指定して孔跡と組み合わせる
+    fn profile_with(&self, collar: CollarElevation) -> ElevationProfile {
+        let mut trajectory = self.trajectory();
+        trajectory.collar_elevation = Some(collar.value);
+        ElevationProfile::new(collar, trajectory)
+    }
+}
+
+impl DepthElevations for crate::boring_structs_400::Boring400 {}
+
+impl DepthElevations for crate::boring_structs_300::Boring300 {}
+
+impl DepthElevations for crate::boring_structs_210::Boring210 {}
+
+impl DepthElevations for crate::boring_structs_200::Boring200 {}
+
+impl DepthElevations for crate::boring_structs_110::Boring110 {}
+
+/// 桁区切りのカンマ（数字の間で、後ろに3桁続くもの）を除く
+fn strip_thousands(text: &str) -> String {
+    let chars: Vec<char> = text.chars().collect();
+    chars
+        .iter()
+        .enumerate()
+        .filter(|&(i, &c)| {
+            let separator = c == ','
+                && i > 0
+                && chars[i - 1].is_ascii_digit()
+                && chars[i + 1..].iter().take_while(|c| c.is_ascii_digit()).count() == 3;
+            !separator
+        })
+        .map(|(_, c)| *c)
+        .collect()
+}
+
+/// 基準面の表記
+pub(crate) fn datum_label(datum: &HeightDatum) -> String {
+    match datum {
+        HeightDatum::TokyoPeil => "T.P.".to_string(),
+        HeightDatum::DatumLevel => "D.L.".to_string(),
+        HeightDatum::BenchMark => "K.B.M.".to_string(),
+        HeightDatum::GroundLevel => "G.L.".to_string(),
+        HeightDatum::Other(label) => label.clone(),
+    }
+}
+
+/// 層の標高をCSV文字列に変換
+///
+/// 値がない項目は空欄とする。
+pub fn layers_to_csv(datum: &HeightDatum, layers: &[LayerElevation]) -> String {
+    let mut csv = String::from("datum,top_depth_m,bottom_depth_m,top_elevation_m,bottom_elevation_m,name,symbol\n");
+    let label = csv_field(&datum_label(datum));
+    let opt = |v: &Option<String>| csv_field(v.as_deref().unwrap_or_default());
+    for l in layers {
+        csv.push_str(&format!(
+            "{},{},{},{:.3},{:.3},{},{}\n",
+            label,
+            l.layer.top_depth,
+            l.layer.bottom_depth,
+            l.top_elevation,
+            l.bottom_elevation,
+            opt(&l.layer.name),
+            opt(&l.layer.symbol),
+        ));
+    }
+    csv
+}
+
+/// 標準貫入試験の標高をCSV文字列に変換
+pub fn spt_to_csv(datum: &HeightDatum, records: &[SptElevation]) -> String {
+    let mut csv = String::from("datum,start_depth_m,start_elevation_m,mid_elevation_m,n_value\n");
+    let label = csv_field(&datum_label(datum));
+    for s in records {
+        csv.push_str(&format!(
+            "{},{},{:.3},{:.3},{}\n",
+            label,
+            s.record.start_depth,
+            s.start_elevation,
+            s.mid_elevation,
+            s.record.n_value(),
+        ));
+    }
+    csv
+}
+
+/// 孔内水位の標高をCSV文字列に変換
+///
+/// 値がない項目は空欄とする。
+pub fn water_levels_to_csv(datum: &HeightDatum, levels: &[WaterLevelElevation]) -> String {
+    let mut csv = String::from("datum,date,depth_m,elevation_m\n");
+    let label = csv_field(&datum_label(datum));
+    let opt = |v: Option<f64>| v.map(|v| format!("{:.3}", v)).unwrap_or_default();
+    for w in levels {
+        csv.push_str(&format!(
+            "{},{},{},{}\n",
+            label,
+            w.reading
+                .date
+                .map(|d| format!("{:04}-{:02}-{:02}", d.year, d.month, d.day))
+                .unwrap_or_default(),
+            w.reading.depth.map(|d| d.to_string()).unwrap_or_default(),
+            opt(w.elevation),
+        ));
+    }
+    csv
+}
+
+/// 原位置試験の標高をCSV文字列に変換
+///
+/// 値がない項目は空欄とする。
+pub fn tests_to_csv(datum: &HeightDatum, tests: &[TestElevation]) -> String {
+    let mut csv = String::from("datum,kind,top_depth_m,bottom_depth_m,top_elevation_m,bottom_elevation_m,value\n");
+    let label = csv_field(&datum_label(datum));
+    for t in tests {
+        csv.push_str(&format!(
+            "{},{},{},{},{:.3},{:.3},{}\n",
+            label,
+            t.kind.label(),
+            t.top_depth,
+            t.bottom_depth,
+            t.top_elevation,
+            t.bottom_elevation,
+            t.value.map(|v| v.to_string()).unwrap_or_default(),
+        ));
+    }
+    csv
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parser::sample;
+
+    #[test]
+    fn test_parse_collar_elevation() {
+        let parse = |s: &str| CollarElevation::parse(Some(s)).map(|c| (c.datum, c.value));
+        assert_eq!(parse("12.34"), Ok((HeightDatum::TokyoPeil, 12.34)));
+        assert_eq!(parse("T.P.+12.34"), Ok((HeightDatum::TokyoPeil, 12.34)));
+        assert_eq!(parse("Ｔ．Ｐ．－１．２０ｍ"), Ok((HeightDatum::TokyoPeil, -1.2)));
+        assert_eq!(parse("D.L.=3.5"), Ok((HeightDatum::DatumLevel, 3.5)));
+        assert_eq!(parse("標高 5.0m"), Ok((HeightDatum::TokyoPeil, 5.0)));
+        assert_eq!(parse("A.P.+2.1"), Ok((HeightDatum::Other("A.P.".to_string()), 2.1)));
+        assert_eq!(CollarElevation::parse(Some(" ")), Err(ElevationError::Missing));
+        assert_eq!(CollarElevation::parse(None), Err(ElevationError::Missing));
+        assert!(matches!(parse("不明"), Err(ElevationError::Invalid(_))));
+
+        // 桁区切りと空白
+        assert_eq!(parse("TP+1,234.5"), Ok((HeightDatum::TokyoPeil, 1234.5)));
+        assert_eq!(parse("T.P. + 12.34 m"), Ok((HeightDatum::TokyoPeil, 12.34)));
+        assert!(matches!(parse("BM1 5.0"), Err(ElevationError::Invalid(_))));
+        assert!(matches!(parse("BM1,5.0"), Err(ElevationError::Invalid(_))));
+    }
+
+    #[test]
+    fn test_profile_uses_vertical_depth() {
+        let collar = CollarElevation::parse(Some("T.P.+10.0")).unwrap();
+        let profile = ElevationProfile::new(collar, Trajectory::new(60.0, 0.0, None));
+        let layers = vec![SoilLayer { top_depth: 0.0, bottom_depth: 4.0, name: Some("砂".to_string()), symbol: None }];
+        let elevations = profile.layers(&layers);
+        assert!((elevations[0].bottom_elevation - 8.0).abs() < 1e-9);
+
+        let csv = layers_to_csv(&profile.collar.datum, &elevations);
+        assert_eq!(csv.lines().nth(1), Some("T.P.,0,4,10.000,8.000,砂,"));
+
+        let datum = HeightDatum::Other("A.P.,旧".to_string());
+        let layers = vec![SoilLayer { top_depth: 0.0, bottom_depth: 4.0, name: Some("砂,\"細\"".to_string()), symbol: None }];
+        let csv = layers_to_csv(&datum, &profile.layers(&layers));
+        assert_eq!(csv.lines().nth(1), Some("\"A.P.,旧\",0,4,10.000,8.000,\"砂,\"\"細\"\"\","));
+    }
+
+    #[test]
+    fn test_in_situ_test_elevations() {
+        let boring: crate::boring_structs_210::Boring210 = sample("BED0210.XML");
+        let profile = boring.elevation_profile().unwrap();
+
+        let lateral = boring.lateral_load_elevations().unwrap();
+        assert_eq!(lateral.len(), boring.lateral_load_tests().len());
+        assert_eq!((lateral[0].kind, lateral[0].top_depth, lateral[0].bottom_depth), (TestKind::LateralLoad, 10.0, 10.0));
+        assert_eq!(lateral[0].value, Some(1310.0));
+        // 掘進角度15°の斜めボーリングなので、標高は鉛直深度で換算する
+        assert!((lateral[0].top_elevation - (0.23 - 10.0 * 15f64.to_radians().cos())).abs() < 1e-9);
+
+        let lugeon = boring.lugeon_elevations().unwrap();
+        assert_eq!((lugeon[0].kind, lugeon[0].top_depth, lugeon[0].bottom_depth), (TestKind::Lugeon, 3.0, 8.0));
+        assert_eq!(lugeon[0].bottom_elevation, profile.elevation_at(8.0));
+
+        let permeability = boring.permeability_elevations().unwrap();
+        assert_eq!(permeability.len(), boring.permeability_tests().len());
+        assert!(permeability.iter().all(|t| t.kind == TestKind::Permeability && t.top_elevation > t.bottom_elevation));
+
+        let waves = boring.elastic_wave_elevations().unwrap();
+        assert_eq!(waves.len(), boring.p_wave_velocities().len() + boring.s_wave_velocities().len());
+        assert!(waves.iter().any(|t| t.kind == TestKind::SWave));
+
+        let csv = tests_to_csv(&profile.collar.datum, &lateral);
+        assert_eq!(csv.lines().nth(1), Some("T.P.,lateral_load,10,10,-9.429,-9.429,1310"));
+    }
+}
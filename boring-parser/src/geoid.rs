@@ -0,0 +1,211 @@
+//! ジオイドモデルモジュール
+//!
+//! 国土地理院のジオイドモデル（日本のジオイド2011 / GSIGEO2011 形式のASCファイル）を読み込み、
+//! 任意の地点のジオイド高を求める。
+//!
+//! # 高さの関係
+//! 楕円体高 h、標高 H、ジオイド高 N の間には `h = H + N` の関係がある。
+//!
+//! # ファイル形式
+//! 1行目がヘッダ、2行目以降が格子点のジオイド高（m）。
+//! ```text
+//! 20.00000 120.00000 0.016667 0.025000 1801 1201 1 ver2.2
+//! 999.0000 999.0000 ... (南から北へ1行ずつ、各行は西から東へ経度方向の点数分)
+//! ```
+//! ヘッダは 南端緯度 西端経度 緯度間隔 経度間隔 緯度方向点数 経度方向点数 種別 バージョン。
+//! 値 `999.0000` はデータなし（海域など）を表す。
+
+use std::path::Path;
+
+use crate::transform::{TransformError, read_param_file};
+
+/// データなしを表す値
+const NO_DATA: f64 = 999.0;
+
+/// 格子端の判定で許容する誤差（格子間隔に対する比）
+const EDGE_TOLERANCE: f64 = 1e-9;
+
+/// ジオイドモデル（格子データ）
+///
+/// # Example
+/// ```ignore
+/// let geoid = GeoidModel::load("params/gsigeo2011_ver2_2.asc")?;
+/// let n = geoid.undulation(36.10377479, 140.087855041)?;
+/// ```
+#[derive(Debug, Clone)]
+pub struct GeoidModel {
+    /// 南端緯度（度）
+    south: f64,
+    /// 西端経度（度）
+    west: f64,
+    /// 緯度間隔（度）
+    lat_interval: f64,
+    /// 経度間隔（度）
+    lon_interval: f64,
+    /// 緯度方向の点数
+    rows: usize,
+    /// 経度方向の点数
+    cols: usize,
+    /// ジオイド高（南西端から行優先、データなしはNone）
+    heights: Vec<Option<f64>>,
+    /// モデルのバージョン（ヘッダ末尾）
+    version: Option<String>,
+}
+
+impl GeoidModel {
+    /// ASCファイルを読み込む
+    pub fn load<P: AsRef<Path>>(path: P) -> Result<Self, TransformError> {
+        let content = read_param_file(&path)?;
+        Self::parse(&content)
+    }
+
+    /// ASCファイルの内容をパース
+    pub fn parse(content: &str) -> Result<Self, TransformError> {
+        let mut lines = content.lines();
+        let header: Vec<&str> = lines
+            .next()
+            .map(|line| line.split_whitespace().collect())
+            .unwrap_or_default();
+        if header.len() < 6 {
+            return Err(TransformError::Parse(
+                "geoid header must have at least 6 fields".to_string(),
+            ));
+        }
+
+        let float = |i: usize| -> Result<f64, TransformError> {
+            header[i]
+                .parse::<f64>()
+                .map_err(|_| TransformError::Parse(format!("invalid geoid header: {}", header[i])))
+        };
+        let count = |i: usize| -> Result<usize, TransformError> {
+            header[i]
+                .parse::<usize>()
+                .ok()
+                .filter(|n| *n >= 2)
+                .ok_or_else(|| TransformError::Parse(format!("invalid geoid header: {}", header[i])))
+        };
+
+        let (south, west) = (float(0)?, float(1)?);
+        let (lat_interval, lon_interval) = (float(2)?, float(3)?);
+        let (rows, cols) = (count(4)?, count(5)?);
+        if lat_interval <= 0.0 || lon_interval <= 0.0 {
+            return Err(TransformError::Parse("geoid interval must be positive".to_string()));
+        }
+
+        let heights = lines
+            .flat_map(str::split_whitespace)
+            .map(|s| {
+                s.parse::<f64>()
+                    .map(|v| (v < NO_DATA).then_some(v))
+                    .map_err(|_| TransformError::Parse(format!("invalid geoid height: {}", s)))
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+        if heights.len() != rows * cols {
+            return Err(TransformError::Parse(format!(
+                "geoid grid has {} values, expected {}",
+                heights.len(),
+                rows * cols
+            )));
+        }
+
+        Ok(Self {
+            south,
+            west,
+            lat_interval,
+            lon_interval,
+            rows,
+            cols,
+            heights,
+            version: header.get(7).map(|s| s.to_string()),
+        })
+    }
+
+    /// モデルのバージョン
+    pub fn version(&self) -> Option<&str> {
+        self.version.as_deref()
+    }
+
+    /// ジオイド高（m）を取得
+    ///
+    /// 周囲4格子点から双一次補間する。格子の範囲外、または周囲にデータなしの
+    /// 格子点がある場合は `None`。
+    pub fn undulation(&self, lat: f64, lon: f64) -> Option<f64> {
+        let y = grid_index((lat - self.south) / self.lat_interval, self.rows)?;
+        let x = grid_index((lon - self.west) / self.lon_interval, self.cols)?;
+
+        // 北端・東端上の点は1つ内側のセルで補間する
+        let row = (y.floor() as usize).min(self.rows - 2);
+        let col = (x.floor() as usize).min(self.cols - 2);
+        let (dy, dx) = (y - row as f64, x - col as f64);
+
+        let sw = self.height(row, col)?;
+        let se = self.height(row, col + 1)?;
+        let nw = self.height(row + 1, col)?;
+        let ne = self.height(row + 1, col + 1)?;
+
+        Some(sw * (1.0 - dy) * (1.0 - dx) + se * (1.0 - dy) * dx + nw * dy * (1.0 - dx) + ne * dy * dx)
+    }
+
+    /// 標高から楕円体高を求める (`h = H + N`)
+    pub fn ellipsoidal_height(&self, lat: f64, lon: f64, orthometric_height: f64) -> Option<f64> {
+        Some(orthometric_height + self.undulation(lat, lon)?)
+    }
+
+    /// 楕円体高から標高を求める (`H = h - N`)
+    pub fn orthometric_height(&self, lat: f64, lon: f64, ellipsoidal_height: f64) -> Option<f64> {
+        Some(ellipsoidal_height - self.undulation(lat, lon)?)
+    }
+
+    /// 格子点のジオイド高
+    fn height(&self, row: usize, col: usize) -> Option<f64> {
+        self.heights[row * self.cols + col]
+    }
+}
+
+/// 格子番号（小数）を範囲内に収める（範囲外はNone）
+fn grid_index(index: f64, count: usize) -> Option<f64> {
+    let max = (count - 1) as f64;
+    (-EDGE_TOLERANCE..=max + EDGE_TOLERANCE)
+        .contains(&index)
+        .then(|| index.clamp(0.0, max))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// 36.0°N〜36.1°N、140.0°E〜140.2°E の3×3格子
+    const GRID: &str = "36.00000 140.00000 0.050000 0.100000 3 3 1 ver2.2\n\
+                        40.0000 40.2000 40.4000\n\
+                        40.1000 40.3000 999.0000\n\
+                        40.2000 40.4000 40.6000\n";
+
+    #[test]
+    fn test_parse_and_interpolate() {
+        let geoid = GeoidModel::parse(GRID).unwrap();
+        assert_eq!(geoid.version(), Some("ver2.2"));
+
+        // 格子点
+        assert!((geoid.undulation(36.0, 140.0).unwrap() - 40.0).abs() < 1e-9);
+        assert!((geoid.undulation(36.1, 140.1).unwrap() - 40.4).abs() < 1e-9);
+        // セル中央は4点の平均
+        let n = geoid.undulation(36.025, 140.05).unwrap();
+        assert!((n - 40.15).abs() < 1e-9);
+
+        // h = H + N と逆算
+        let h = geoid.ellipsoidal_height(36.025, 140.05, 25.0).unwrap();
+        assert!((h - 65.15).abs() < 1e-9);
+        assert!((geoid.orthometric_height(36.025, 140.05, h).unwrap() - 25.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_no_data_and_out_of_grid() {
+        let geoid = GeoidModel::parse(GRID).unwrap();
+        // 東側のセルはデータなしの格子点を含む
+        assert!(geoid.undulation(36.025, 140.15).is_none());
+        assert!(geoid.undulation(35.99, 140.05).is_none());
+        assert!(geoid.undulation(36.05, 140.21).is_none());
+
+        assert!(GeoidModel::parse("36.0 140.0 0.05 0.1 3 3\n1.0 2.0\n").is_err());
+    }
+}
@@ -0,0 +1,507 @@
+//! 孔内水位の時系列を扱うモジュール
+//!
+//! 孔内水位の測定記録を測定年月日・掘削深度の順に並べた時系列として取得し、
+//! 掘削終了後の安定水位の推定、孔口標高を用いた水位標高への換算、
+//! 被圧水と自由地下水の区別を行う。
+//!
+//! 掘削中か掘削終了後かは掘削状況の記述、測定時の掘削深度と総掘進長、
+//! 掘削工程の日付の順に判定する。
+//!
+//! 掘削状況コード（v4.00は削孔状況コード）と水位種別コードは、同梱のDTD
+//! （docs/dtd）にコード表がなく、サンプルデータでも掘削終了後の2回の測定が
+//! どちらも掘削状況コード "1" で記入されているため、判定には使わずに値だけを保持する。
+
+use serde::Serialize;
+
+use crate::elevation::CollarElevation;
+use crate::transform::SurveyStartDate;
+use crate::types::parse_f64;
+
+/// 水位なしを表すために使われる値（例: "-99.99"）の閾値
+const NO_WATER_SENTINEL: f64 = -99.0;
+
+/// 測定時の掘削段階
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+pub enum ReadingStage {
+    /// 掘削中
+    DuringDrilling,
+    /// 掘削終了後
+    AfterCompletion,
+    /// 判定できない
+    Unknown,
+}
+
+/// 地下水の種別
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+pub enum AquiferType {
+    /// 自由地下水（不圧）
+    Unconfined,
+    /// 被圧地下水
+    Confined,
+    /// 水位なし
+    NoWater,
+    /// 記載なし
+    Unknown,
+}
+
+impl AquiferType {
+    /// 水位種別の記述から判定
+    ///
+    /// 記述に「被圧」「不圧」「自由」「水位無し」等が含まれればそれに従う。
+    pub fn from_description(description: Option<&str>) -> Self {
+        let text = description.unwrap_or_default();
+        if text.contains("被圧") {
+            AquiferType::Confined
+        } else if text.contains("不圧") || text.contains("自由") {
+            AquiferType::Unconfined
+        } else if ["水位無し", "水位なし", "無水", "水位無"].iter().any(|k| text.contains(k)) {
+            AquiferType::NoWater
+        } else {
+            AquiferType::Unknown
+        }
+    }
+}
+
+/// 孔内水位の測定1回分
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct WaterLevelReading {
+    /// 測定年月日
+    pub date: Option<SurveyStartDate>,
+    /// 測定時の掘削深度 (m)（v1.10は記録値、以降は掘削工程から推定）
+    pub drilling_depth: Option<f64>,
+    /// 孔内水位 GL-m（水位なしの場合はNone）
+    pub depth: Option<f64>,
+    /// 水位標高 (m)
+    pub elevation: Option<f64>,
+    /// 掘削状況コード（v4.00は削孔状況コード、解釈せずに保持）
+    pub status_code: Option<String>,
+    /// 水位種別コード（v2.00〜v3.00、解釈せずに保持）
+    pub aquifer_code: Option<String>,
+    /// 掘削段階
+    pub stage: ReadingStage,
+    /// 地下水の種別
+    pub aquifer: AquiferType,
+    /// 水位種別の記述
+    pub remarks: Option<String>,
+}
+
+/// 安定水位の判定根拠
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+pub enum StabilisedBasis {
+    /// 掘削終了後の最終測定
+    AfterCompletion,
+    /// 掘削終了後の測定がないため最終測定で代用
+    LatestReading,
+}
+
+/// 安定水位の推定結果
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct StabilisedWaterLevel {
+    /// 採用した測定
+    pub reading: WaterLevelReading,
+    /// 判定根拠
+    pub basis: StabilisedBasis,
+    /// 直前の掘削終了後の測定との差が許容値以内か（比較できない場合はNone）
+    pub stable: Option<bool>,
+}
+
+/// 孔内水位の時系列
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct WaterLevelSeries {
+    /// 孔口標高 (m)
+    pub ground_elevation: Option<f64>,
+    /// 測定年月日・掘削深度順の測定
+    pub readings: Vec<WaterLevelReading>,
+}
+
+impl WaterLevelSeries {
+    /// 測定記録から時系列を作成
+    ///
+    /// 測定年月日・掘削深度の順に並べ替え、孔口標高から水位標高を求める。
+    /// 日付のない測定は末尾に置く。
+    pub fn new(ground_elevation: Option<f64>, mut readings: Vec<WaterLevelReading>) -> Self {
+        for reading in &mut readings {
+            reading.elevation = ground_elevation.zip(reading.depth).map(|(g, d)| g - d);
+        }
+        readings.sort_by(|a, b| {
+            let key = |r: &WaterLevelReading| (r.date.is_none(), r.date);
+            key(a).cmp(&key(b)).then_with(|| {
+                a.drilling_depth
+                    .unwrap_or(f64::INFINITY)
+                    .total_cmp(&b.drilling_depth.unwrap_or(f64::INFINITY))
+            })
+        });
+        Self { ground_elevation, readings }
+    }
+
+    /// 安定水位を推定
+    ///
+    /// 掘削終了後の最後の測定を安定水位とする。掘削終了後の測定が2回以上あれば、
+    /// 直前の測定との差が `tolerance` (m) 以内かを `stable` に示す。
+    pub fn stabilised(&self, tolerance: f64) -> Option<StabilisedWaterLevel> {
+        let after: Vec<&WaterLevelReading> = self
+            .readings
+            .iter()
+            .filter(|r| r.depth.is_some() && r.stage == ReadingStage::AfterCompletion)
+            .collect();
+
+        if let Some(&last) = after.last() {
+            let stable = after
+                .len()
+                .checked_sub(2)
+                .and_then(|i| Some((after[i].depth? - last.depth?).abs() <= tolerance));
+            return Some(StabilisedWaterLevel {
+                reading: last.clone(),
+                basis: StabilisedBasis::AfterCompletion,
+                stable,
+            });
+        }
+
+        self.readings.iter().rev().find(|r| r.depth.is_some()).map(|r| StabilisedWaterLevel {
+            reading: r.clone(),
+            basis: StabilisedBasis::LatestReading,
+            stable: None,
+        })
+    }
+
+    /// 被圧地下水の測定
+    pub fn confined(&self) -> impl Iterator<Item = &WaterLevelReading> {
+        self.readings.iter().filter(|r| r.aquifer == AquiferType::Confined)
+    }
+
+    /// 被圧と明記されていない（水位のある）測定
+    pub fn free_water(&self) -> impl Iterator<Item = &WaterLevelReading> {
+        self.readings
+            .iter()
+            .filter(|r| r.depth.is_some() && r.aquifer != AquiferType::Confined)
+    }
+}
+
+/// 掘削工程の記録（測定日, 掘削深度）から、指定日までの最大掘削深度を求める
+fn drilling_depth_at(progress: &[(SurveyStartDate, f64)], date: SurveyStartDate) -> Option<f64> {
+    progress
+        .iter()
+        .filter(|(d, _)| *d <= date)
+        .map(|(_, depth)| *depth)
+        .reduce(f64::max)
+}
+
+/// 掘削状況の記述と掘削工程から掘削段階を判定
+fn reading_stage(
+    status: Option<&str>,
+    date: Option<SurveyStartDate>,
+    drilling_depth: Option<f64>,
+    progress: &[(SurveyStartDate, f64)],
+    total_length: Option<f64>,
+) -> ReadingStage {
+    let status = status.unwrap_or_default();
+    if ["終了", "完了", "後"].iter().any(|k| status.contains(k)) {
+        return ReadingStage::AfterCompletion;
+    }
+    if status.contains("中") {
+        return ReadingStage::DuringDrilling;
+    }
+
+    // 掘削深度が総掘進長に達していれば掘削終了後とみなす
+    if let (Some(depth), Some(total)) = (drilling_depth, total_length) {
+        return if depth >= total { ReadingStage::AfterCompletion } else { ReadingStage::DuringDrilling };
+    }
+    // 最終の掘削工程より後の測定は掘削終了後とみなす
+    if let (Some(date), Some(last)) = (date, progress.iter().map(|(d, _)| *d).max()) {
+        return if date > last { ReadingStage::AfterCompletion } else { ReadingStage::DuringDrilling };
+    }
+    ReadingStage::Unknown
+}
+
+/// 孔口標高の値（"T.P.+12.34" のような基準面の前置を除く）
+fn collar_elevation(value: Option<&str>) -> Option<f64> {
+    CollarElevation::parse(value).ok().map(|c| c.value)
+}
+
+/// 孔内水位の値をパース（水位なしを表す負の大きな値は除く）
+fn parse_water_level(value: Option<&str>) -> Option<f64> {
+    parse_f64(value).filter(|v| *v > NO_WATER_SENTINEL)
+}
+
+/// 孔内水位の時系列を取得するためのトレイト
+pub trait GroundwaterLevels {
+    /// 孔内水位を時系列として取得
+    fn water_level_series(&self) -> WaterLevelSeries;
+}
+
+fn series_210(
+    levels: &[crate::boring_structs_210::BoreholeWaterLevel210],
+    processes: &[crate::boring_structs_210::DrillingProcess210],
+    basic: &crate::boring_structs_210::BoringBasicInfo210,
+) -> WaterLevelSeries {
+    let progress: Vec<(SurveyStartDate, f64)> = processes
+        .iter()
+        .filter_map(|p| {
+            Some((
+                SurveyStartDate::parse(p.measurement_date.as_deref())?,
+                parse_f64(p.drilling_depth.as_deref())?,
+            ))
+        })
+        .collect();
+    let total_length = parse_f64(basic.total_length.as_deref());
+
+    let readings = levels
+        .iter()
+        .map(|l| {
+            let date = SurveyStartDate::parse(l.measurement_date.as_deref());
+            let drilling_depth = date.and_then(|d| drilling_depth_at(&progress, d));
+            let depth = parse_water_level(l.water_level.as_deref());
+            let mut aquifer = AquiferType::from_description(l.water_level_type_remarks.as_deref());
+            if depth.is_none() && aquifer == AquiferType::Unknown {
+                aquifer = AquiferType::NoWater;
+            }
+            WaterLevelReading {
+                date,
+                drilling_depth,
+                depth,
+                elevation: None,
+                status_code: l.drilling_status_code.clone(),
+                aquifer_code: l.water_level_type_code.clone(),
+                stage: reading_stage(
+                    l.drilling_status.as_deref(),
+                    date,
+                    drilling_depth,
+                    &progress,
+                    total_length,
+                ),
+                aquifer,
+                remarks: l.water_level_type_remarks.clone(),
+            }
+        })
+        .collect();
+
+    WaterLevelSeries::new(collar_elevation(basic.elevation.as_deref()), readings)
+}
+
+impl GroundwaterLevels for crate::boring_structs_400::Boring400 {
+    fn water_level_series(&self) -> WaterLevelSeries {
+        let progress: Vec<(SurveyStartDate, f64)> = self
+            .core
+            .drilling_processes
+            .iter()
+            .filter_map(|p| {
+                let date = p.measurement_date.as_deref().or(p.end_date.as_deref());
+                Some((SurveyStartDate::parse(date)?, parse_f64(p.drilling_depth.as_deref())?))
+            })
+            .collect();
+        let basic = &self.title.boring_basic_info;
+        let total_length = parse_f64(basic.total_drilling_length.as_deref());
+
+        let readings = self
+            .core
+            .borehole_water_levels
+            .iter()
+            .map(|l| {
+                let date = SurveyStartDate::parse(l.measurement_date.as_deref());
+                let drilling_depth = date.and_then(|d| drilling_depth_at(&progress, d));
+                let depth = parse_water_level(l.water_level.as_deref());
+                let mut aquifer = AquiferType::from_description(l.water_level_type_remarks.as_deref());
+                if depth.is_none() && aquifer == AquiferType::Unknown {
+                    aquifer = AquiferType::NoWater;
+                }
+                WaterLevelReading {
+                    date,
+                    drilling_depth,
+                    depth,
+                    elevation: None,
+                    status_code: l.drilling_status_code.clone(),
+                    aquifer_code: None,
+                    stage: reading_stage(
+                        l.drilling_status.as_deref(),
+                        date,
+                        drilling_depth,
+                        &progress,
+                        total_length,
+                    ),
+                    aquifer,
+                    remarks: l.water_level_type_remarks.clone(),
+                }
+            })
+            .collect();
+
+        WaterLevelSeries::new(collar_elevation(basic.surface_elevation.as_deref()), readings)
+    }
+}
+
+impl GroundwaterLevels for crate::boring_structs_300::Boring300 {
+    fn water_level_series(&self) -> WaterLevelSeries {
+        series_210(
+            &self.core.borehole_water_levels,
+            &self.core.drilling_processes,
+            &self.title.boring_basic_info,
+        )
+    }
+}
+
+impl GroundwaterLevels for crate::boring_structs_210::Boring210 {
+    fn water_level_series(&self) -> WaterLevelSeries {
+        series_210(
+            &self.core.borehole_water_levels,
+            &self.core.drilling_processes,
+            &self.title.boring_basic_info,
+        )
+    }
+}
+
+impl GroundwaterLevels for crate::boring_structs_200::Boring200 {
+    fn water_level_series(&self) -> WaterLevelSeries {
+        series_210(
+            &self.core.borehole_water_levels,
+            &self.core.drilling_processes,
+            &self.title.boring_basic_info,
+        )
+    }
+}
+
+impl GroundwaterLevels for crate::boring_structs_110::Boring110 {
+    fn water_level_series(&self) -> WaterLevelSeries {
+        let progress: Vec<(SurveyStartDate, f64)> = self
+            .core
+            .drilling_process
+            .iter()
+            .filter_map(|p| {
+                let date = SurveyStartDate::from_parts(
+                    p.measurement_year.as_deref(),
+                    p.measurement_month.as_deref(),
+                    p.measurement_day.as_deref(),
+                )?;
+                Some((date, parse_f64(p.drilling_depth.as_deref())?))
+            })
+            .collect();
+        let basic = &self.title.boring_basic_info;
+        let total_length = parse_f64(basic.total_length.as_deref());
+
+        let readings = self
+            .core
+            .borehole_water_levels
+            .iter()
+            .map(|l| {
+                let date =
+                    SurveyStartDate::from_parts(l.year.as_deref(), l.month.as_deref(), l.day.as_deref());
+                let drilling_depth = parse_f64(l.depth.as_deref());
+                let depth = parse_water_level(l.water_level.as_deref());
+                let mut aquifer = AquiferType::from_description(l.water_level_type.as_deref());
+                if depth.is_none() && aquifer == AquiferType::Unknown {
+                    aquifer = AquiferType::NoWater;
+                }
+                WaterLevelReading {
+                    date,
+                    drilling_depth,
+                    depth,
+                    elevation: None,
+                    status_code: None,
+                    aquifer_code: None,
+                    stage: reading_stage(None, date, drilling_depth, &progress, total_length),
+                    aquifer,
+                    remarks: l.water_level_type.clone(),
+                }
+            })
+            .collect();
+
+        WaterLevelSeries::new(collar_elevation(basic.elevation.as_deref()), readings)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parser::sample;
+
+    fn reading(date: (i32, u32, u32), depth: Option<f64>, stage: ReadingStage) -> WaterLevelReading {
+        WaterLevelReading {
+            date: Some(SurveyStartDate::new(date.0, date.1, date.2)),
+            drilling_depth: None,
+            depth,
+            elevation: None,
+            status_code: None,
+            aquifer_code: None,
+            stage,
+            aquifer: AquiferType::Unknown,
+            remarks: None,
+        }
+    }
+
+    #[test]
+    fn test_series_sorting_and_elevation() {
+        let series = WaterLevelSeries::new(
+            Some(12.5),
+            vec![
+                reading((2001, 5, 21), Some(5.05), ReadingStage::AfterCompletion),
+                reading((2001, 5, 10), Some(2.0), ReadingStage::DuringDrilling),
+            ],
+        );
+        assert_eq!(series.readings[0].date, Some(SurveyStartDate::new(2001, 5, 10)));
+        assert!((series.readings[1].elevation.unwrap() - 7.45).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_stabilised() {
+        let series = WaterLevelSeries::new(
+            None,
+            vec![
+                reading((2001, 5, 10), Some(2.0), ReadingStage::DuringDrilling),
+                reading((2001, 5, 20), Some(5.10), ReadingStage::AfterCompletion),
+                reading((2001, 5, 21), Some(5.05), ReadingStage::AfterCompletion),
+                reading((2001, 5, 22), None, ReadingStage::AfterCompletion),
+            ],
+        );
+        let stabilised = series.stabilised(0.1).unwrap();
+        assert_eq!(stabilised.basis, StabilisedBasis::AfterCompletion);
+        assert_eq!(stabilised.reading.depth, Some(5.05));
+        assert_eq!(stabilised.stable, Some(true));
+
+        let during_only = WaterLevelSeries::new(
+            None,
+            vec![reading((2001, 5, 10), Some(2.0), ReadingStage::DuringDrilling)],
+        );
+        let stabilised = during_only.stabilised(0.1).unwrap();
+        assert_eq!(stabilised.basis, StabilisedBasis::LatestReading);
+        assert_eq!(stabilised.stable, None);
+    }
+
+    #[test]
+    fn test_reading_stage_and_aquifer() {
+        let progress = vec![
+            (SurveyStartDate::new(2001, 5, 1), 3.0),
+            (SurveyStartDate::new(2001, 5, 19), 27.0),
+        ];
+        let date = Some(SurveyStartDate::new(2001, 5, 20));
+        assert_eq!(reading_stage(None, date, None, &progress, None), ReadingStage::AfterCompletion);
+        assert_eq!(reading_stage(Some("掘削中"), date, None, &progress, None), ReadingStage::DuringDrilling);
+        assert_eq!(reading_stage(None, None, Some(27.0), &[], Some(27.0)), ReadingStage::AfterCompletion);
+        assert_eq!(reading_stage(None, None, Some(3.0), &progress, Some(27.0)), ReadingStage::DuringDrilling);
+        assert_eq!(drilling_depth_at(&progress, SurveyStartDate::new(2001, 5, 10)), Some(3.0));
+
+        assert_eq!(AquiferType::from_description(Some("清水位、被圧")), AquiferType::Confined);
+        assert_eq!(AquiferType::from_description(Some("水位無し")), AquiferType::NoWater);
+        assert_eq!(AquiferType::from_description(None), AquiferType::Unknown);
+        assert_eq!(parse_water_level(Some("-99.99")), None);
+    }
+
+    #[test]
+    fn test_sample_stabilised_water_level() {
+        // 掘削工程の最終日 (2001-05-19) より後の2回の測定。掘削状況コードはどちらも "1"
+        let series = sample::<crate::boring_structs_210::Boring210>("BED0210.XML").water_level_series();
+        assert_eq!(series.readings.len(), 2);
+        assert!(series.readings.iter().all(|r| r.stage == ReadingStage::AfterCompletion));
+        assert!(series.readings.iter().all(|r| r.status_code.as_deref() == Some("1")));
+        assert_eq!(series.readings[0].aquifer, AquiferType::NoWater);
+        assert_eq!(series.readings[1].aquifer_code.as_deref(), Some("13"));
+
+        let stabilised = series.stabilised(0.1).unwrap();
+        assert_eq!(stabilised.basis, StabilisedBasis::AfterCompletion);
+        assert_eq!(stabilised.reading.date, Some(SurveyStartDate::new(2001, 5, 21)));
+        assert_eq!(stabilised.reading.depth, Some(5.05));
+
+        let series = sample::<crate::boring_structs_400::Boring400>("BED0400.XML").water_level_series();
+        let stabilised = series.stabilised(0.1).unwrap();
+        assert_eq!(stabilised.basis, StabilisedBasis::AfterCompletion);
+        assert_eq!(stabilised.reading.date, Some(SurveyStartDate::new(2001, 5, 21)));
+        assert_eq!(stabilised.reading.aquifer, AquiferType::Confined);
+    }
+}
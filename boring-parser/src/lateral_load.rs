@@ -0,0 +1,392 @@
+//! 孔内水平載荷試験の設計用地盤定数を算定するモジュール
+//!
+//! 孔内水平載荷試験の変形係数から、道路橋示方書の式により
+//! 杭の水平方向地盤反力係数 k_H を算定する。
+//! 最寄りの標準貫入試験から推定した変形係数 E0 = 2800・N との比較と、
+//! 試験深度の土質・岩種区分との対応付けもあわせて行う。
+//!
+//! # 単位
+//! - 初期圧・降伏圧・変形係数: kN/m²
+//! - 水平方向地盤反力係数: kN/m³
+//! - 杭径: m、杭の曲げ剛性: kN・m²
+//!
+//! # 算定式
+//! - k_H0 = α・E0 / 0.3
+//! - k_H = k_H0・(B_H / 0.3)^(-3/4)
+//! - B_H = √(D / β)、β = (k_H・D / 4EI)^(1/4)（曲げ剛性を指定した場合。未指定ならB_H = D）
+//!
+//! αは孔内水平載荷試験で常時4・地震時8、標準貫入試験で常時1・地震時2。
+
+use serde::Serialize;
+
+use crate::layer::{SoilLayer, SoilLayers, layer_at};
+use crate::spt::{SptRecord, StandardPenetrationTests, nearest_spt};
+use crate::types::{csv_field, parse_f64};
+
+/// 標準貫入試験のN値から変形係数を推定する係数 (kN/m²)
+pub const SPT_MODULUS_FACTOR: f64 = 2800.0;
+
+/// k_H の基準載荷幅 (m)
+const REFERENCE_WIDTH: f64 = 0.3;
+
+/// 孔内水平載荷試験1件分のデータ（バージョン共通）
+#[derive(Debug, Clone, PartialEq)]
+pub struct LateralLoadTestRecord {
+    /// 試験深度 (m)
+    pub depth: f64,
+    /// 試験方法コード
+    pub method_code: Option<String>,
+    /// 初期圧 (kN/m²)
+    pub initial_pressure: Option<f64>,
+    /// 降伏圧 (kN/m²)
+    pub yield_pressure: Option<f64>,
+    /// 変形係数 (kN/m²)
+    pub deformation_modulus: Option<f64>,
+}
+
+/// 変形係数の推定方法ごとの係数α
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ModulusSource {
+    /// 孔内水平載荷試験
+    LateralLoadTest,
+    /// 標準貫入試験（E0 = 2800・N）
+    Spt,
+}
+
+impl ModulusSource {
+    /// 係数α（常時, 地震時）
+    pub fn alpha(&self) -> (f64, f64) {
+        match self {
+            ModulusSource::LateralLoadTest => (4.0, 8.0),
+            ModulusSource::Spt => (1.0, 2.0),
+        }
+    }
+}
+
+/// 杭の諸元
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct PileSpec {
+    /// 杭径 (m)
+    pub diameter: f64,
+    /// 杭の曲げ剛性 EI (kN・m²)。指定すると換算載荷幅 B_H を収束計算で求める
+    pub flexural_rigidity: Option<f64>,
+}
+
+impl PileSpec {
+    /// 杭の諸元を作成
+    pub fn new(diameter: f64, flexural_rigidity: Option<f64>) -> Self {
+        Self { diameter, flexural_rigidity }
+    }
+
+    /// 変形係数 E0 から水平方向地盤反力係数 k_H (kN/m³) を算定
+    ///
+    /// 曲げ剛性を指定した場合は B_H と β が整合するまで反復計算する。
+    pub fn subgrade_reaction(&self, e0: f64, alpha: f64) -> Option<f64> {
+        if !(e0 > 0.0 && self.diameter > 0.0) {
+            return None;
+        }
+        let kh0 = alpha * e0 / REFERENCE_WIDTH;
+        let kh_for = |width: f64| kh0 * (width / REFERENCE_WIDTH).powf(-0.75);
+
+        let Some(ei) = self.flexural_rigidity.filter(|ei| *ei > 0.0) else {
+            return Some(kh_for(self.diameter));
+        };
+
+        let mut kh = kh_for(self.diameter);
+        for _ in 0..100 {
+            let beta = (kh * self.diameter / (4.0 * ei)).powf(0.25);
+            let next = kh_for((self.diameter / beta).sqrt());
+            if (next - kh).abs() <= kh * 1e-9 {
+                return Some(next);
+            }
+            kh = next;
+        }
+        Some(kh)
+    }
+}
+
+/// 孔内水平載荷試験1件の算定結果
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct LateralLoadInterval {
+    /// 試験深度 (m)
+    pub depth: f64,
+    /// 試験方法コード
+    pub method_code: Option<String>,
+    /// 試験深度の層の上端深度 (m)
+    pub layer_top_depth: Option<f64>,
+    /// 試験深度の層の下端深度 (m)
+    pub layer_bottom_depth: Option<f64>,
+    /// 試験深度の土質岩種名
+    pub layer_name: Option<String>,
+    /// 初期圧 (kN/m²)
+    pub initial_pressure: Option<f64>,
+    /// 降伏圧 (kN/m²)
+    pub yield_pressure: Option<f64>,
+    /// 孔内水平載荷試験の変形係数 (kN/m²)
+    pub deformation_modulus: Option<f64>,
+    /// 比較に用いた標準貫入試験の開始深度 (m)
+    pub spt_depth: Option<f64>,
+    /// 比較に用いたN値
+    pub n_value: Option<f64>,
+    /// N値から推定した変形係数 2800・N (kN/m²)
+    pub spt_modulus: Option<f64>,
+    /// 変形係数の比（試験値 / 2800・N）
+    pub modulus_ratio: Option<f64>,
+    /// 試験値による k_H 常時 (kN/m³)
+    pub kh_normal: Option<f64>,
+    /// 試験値による k_H 地震時 (kN/m³)
+    pub kh_seismic: Option<f64>,
+    /// N値による k_H 常時 (kN/m³)
+    pub spt_kh_normal: Option<f64>,
+    /// N値による k_H 地震時 (kN/m³)
+    pub spt_kh_seismic: Option<f64>,
+}
+
+/// 孔内水平載荷試験から設計用地盤定数を算定する
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct LateralLoadCalculator {
+    pile: PileSpec,
+    /// 比較対象とする標準貫入試験の試験深度からの最大距離 (m)
+    max_spt_distance: f64,
+}
+
+impl LateralLoadCalculator {
+    /// 算定器を作成
+    ///
+    /// # Arguments
+    /// * `pile` - 杭の諸元
+    /// * `max_spt_distance` - 比較対象とする標準貫入試験の最大距離 (m)。これより離れたN値は使わない
+    pub fn new(pile: PileSpec, max_spt_distance: f64) -> Self {
+        Self { pile, max_spt_distance }
+    }
+
+    /// ボーリングデータの全孔内水平載荷試験について算定
+    ///
+    /// # Example
+    /// ```ignore
+    /// let calculator = LateralLoadCalculator::new(PileSpec::new(1.0, None), 1.0);
+    /// let intervals = calculator.calculate(&boring);
+    /// let csv = to_csv(&intervals);
+    /// ```
+    pub fn calculate<T>(&self, boring: &T) -> Vec<LateralLoadInterval>
+    where
+        T: LateralLoadTests + StandardPenetrationTests + SoilLayers,
+    {
+        self.calculate_from(&boring.lateral_load_tests(), &boring.spt_records(), &boring.soil_layers())
+    }
+
+    /// 試験データ・標準貫入試験・層区分から算定
+    pub fn calculate_from(
+        &self,
+        tests: &[LateralLoadTestRecord],
+        spts: &[SptRecord],
+        layers: &[SoilLayer],
+    ) -> Vec<LateralLoadInterval> {
+        let (alpha_normal, alpha_seismic) = ModulusSource::LateralLoadTest.alpha();
+        let (spt_alpha_normal, spt_alpha_seismic) = ModulusSource::Spt.alpha();
+
+        tests
+            .iter()
+            .map(|test| {
+                let layer = layer_at(layers, test.depth);
+                let spt = nearest_spt(spts, test.depth)
+                    .filter(|s| (s.mid_depth() - test.depth).abs() <= self.max_spt_distance);
+                let n_value = spt.map(|s| s.n_value());
+                let spt_modulus = n_value.map(|n| SPT_MODULUS_FACTOR * n);
+                let e0 = test.deformation_modulus;
+
+                LateralLoadInterval {
+                    depth: test.depth,
+                    method_code: test.method_code.clone(),
+                    layer_top_depth: layer.map(|l| l.top_depth),
+                    layer_bottom_depth: layer.map(|l| l.bottom_depth),
+                    layer_name: layer.and_then(|l| l.name.clone()),
+                    initial_pressure: test.initial_pressure,
+                    yield_pressure: test.yield_pressure,
+                    deformation_modulus: e0,
+                    spt_depth: spt.map(|s| s.start_depth),
+                    n_value,
+                    spt_modulus,
+                    modulus_ratio: e0
+                        .zip(spt_modulus)
+                        .filter(|&(_, es)| es > 0.0)
+                        .map(|(e, es)| e / es),
+                    kh_normal: e0.and_then(|e| self.pile.subgrade_reaction(e, alpha_normal)),
+                    kh_seismic: e0.and_then(|e| self.pile.subgrade_reaction(e, alpha_seismic)),
+                    spt_kh_normal: spt_modulus
+                        .and_then(|e| self.pile.subgrade_reaction(e, spt_alpha_normal)),
+                    spt_kh_seismic: spt_modulus
+                        .and_then(|e| self.pile.subgrade_reaction(e, spt_alpha_seismic)),
+                }
+            })
+            .collect()
+    }
+}
+
+/// 算定結果をCSV文字列に変換
+pub fn to_csv(intervals: &[LateralLoadInterval]) -> String {
+    let mut csv = String::from(
+        "depth_m,method_code,layer_top_m,layer_bottom_m,layer_name,initial_pressure_kn_m2,yield_pressure_kn_m2,e_test_kn_m2,spt_depth_m,n_value,e_spt_kn_m2,modulus_ratio,kh_normal_kn_m3,kh_seismic_kn_m3,spt_kh_normal_kn_m3,spt_kh_seismic_kn_m3\n",
+    );
+    let opt = |v: Option<f64>| v.map(|v| format!("{}", v)).unwrap_or_default();
+
+    for i in intervals {
+        csv.push_str(&format!(
+            "{},{},{},{},{},{},{},{},{},{},{},{},{},{},{},{}\n",
+            i.depth,
+            csv_field(i.method_code.as_deref().unwrap_or_default()),
+            opt(i.layer_top_depth),
+            opt(i.layer_bottom_depth),
+            csv_field(i.layer_name.as_deref().unwrap_or_default()),
+            opt(i.initial_pressure),
+            opt(i.yield_pressure),
+            opt(i.deformation_modulus),
+            opt(i.spt_depth),
+            opt(i.n_value),
+            opt(i.spt_modulus),
+            opt(i.modulus_ratio),
+            opt(i.kh_normal),
+            opt(i.kh_seismic),
+            opt(i.spt_kh_normal),
+            opt(i.spt_kh_seismic),
+        ));
+    }
+
+    csv
+}
+
+/// 孔内水平載荷試験データを取得するためのトレイト
+pub trait LateralLoadTests {
+    /// 孔内水平載荷試験を取得（試験深度がパースできないものは除く）
+    fn lateral_load_tests(&self) -> Vec<LateralLoadTestRecord>;
+}
+
+fn records_210(tests: &[crate::boring_structs_210::BoreholeHorizontalLoadTest210]) -> Vec<LateralLoadTestRecord> {
+    tests
+        .iter()
+        .filter_map(|t| {
+            Some(LateralLoadTestRecord {
+                depth: parse_f64(t.test_depth.as_deref())?,
+                method_code: t.test_method_code.clone(),
+                initial_pressure: parse_f64(t.initial_pressure.as_deref()),
+                yield_pressure: parse_f64(t.yield_pressure.as_deref()),
+                deformation_modulus: parse_f64(t.deformation_modulus.as_deref()),
+            })
+        })
+        .collect()
+}
+
+impl LateralLoadTests for crate::boring_structs_400::Boring400 {
+    fn lateral_load_tests(&self) -> Vec<LateralLoadTestRecord> {
+        records_210(&self.core.borehole_loading_tests)
+    }
+}
+
+impl LateralLoadTests for crate::boring_structs_300::Boring300 {
+    fn lateral_load_tests(&self) -> Vec<LateralLoadTestRecord> {
+        records_210(&self.core.borehole_horizontal_load_tests)
+    }
+}
+
+impl LateralLoadTests for crate::boring_structs_210::Boring210 {
+    fn lateral_load_tests(&self) -> Vec<LateralLoadTestRecord> {
+        records_210(&self.core.borehole_horizontal_load_tests)
+    }
+}
+
+impl LateralLoadTests for crate::boring_structs_200::Boring200 {
+    fn lateral_load_tests(&self) -> Vec<LateralLoadTestRecord> {
+        records_210(&self.core.borehole_horizontal_load_tests)
+    }
+}
+
+impl LateralLoadTests for crate::boring_structs_110::Boring110 {
+    fn lateral_load_tests(&self) -> Vec<LateralLoadTestRecord> {
+        self.core
+            .borehole_horizontal_load_test
+            .iter()
+            .filter_map(|t| {
+                Some(LateralLoadTestRecord {
+                    depth: parse_f64(t.depth.as_deref())?,
+                    method_code: t.method_code.clone(),
+                    initial_pressure: parse_f64(t.initial_pressure.as_deref()),
+                    yield_pressure: parse_f64(t.yield_pressure.as_deref()),
+                    deformation_modulus: parse_f64(t.deformation_coefficient.as_deref()),
+                })
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_subgrade_reaction_without_rigidity() {
+        // B_H = D = 0.3m のとき k_H = k_H0 = α・E0 / 0.3
+        let pile = PileSpec::new(0.3, None);
+        let kh = pile.subgrade_reaction(1310.0, 4.0).unwrap();
+        assert!((kh - 4.0 * 1310.0 / 0.3).abs() < 1e-6);
+
+        // D = 1.2m: (1.2/0.3)^(-3/4) = 4^(-0.75)
+        let pile = PileSpec::new(1.2, None);
+        let kh = pile.subgrade_reaction(1310.0, 4.0).unwrap();
+        assert!((kh - 4.0 * 1310.0 / 0.3 * 4f64.powf(-0.75)).abs() < 1e-6);
+
+        assert!(pile.subgrade_reaction(0.0, 4.0).is_none());
+    }
+
+    #[test]
+    fn test_subgrade_reaction_converges_with_rigidity() {
+        let pile = PileSpec::new(1.0, Some(1.0e6));
+        let kh = pile.subgrade_reaction(24700.0, 4.0).unwrap();
+
+        // 収束解は B_H = √(D/β) と整合する
+        let beta = (kh * 1.0 / (4.0 * 1.0e6)).powf(0.25);
+        let width = (1.0 / beta).sqrt();
+        let expected = 4.0 * 24700.0 / 0.3 * (width / 0.3).powf(-0.75);
+        assert!((kh - expected).abs() / expected < 1e-6);
+    }
+
+    #[test]
+    fn test_calculate_from() {
+        let tests = vec![LateralLoadTestRecord {
+            depth: 10.0,
+            method_code: Some("02".to_string()),
+            initial_pressure: Some(19.6),
+            yield_pressure: Some(133.3),
+            deformation_modulus: Some(1.31e3),
+        }];
+        let spts = vec![
+            SptRecord { start_depth: 9.15, blows: 2.0, penetration: Some(30.0) },
+            SptRecord { start_depth: 10.15, blows: 4.0, penetration: Some(30.0) },
+        ];
+        let layers = crate::layer::layers_from_bottom_depths(vec![
+            (Some("8.0"), Some("砂"), None),
+            (Some("12.0"), Some("シルト"), None),
+        ]);
+
+        let calculator = LateralLoadCalculator::new(PileSpec::new(0.3, None), 1.0);
+        let result = calculator.calculate_from(&tests, &spts, &layers);
+
+        assert_eq!(result.len(), 1);
+        let r = &result[0];
+        assert_eq!(r.layer_name.as_deref(), Some("シルト"));
+        assert_eq!(r.spt_depth, Some(10.15));
+        assert_eq!(r.spt_modulus, Some(11200.0));
+        assert!((r.modulus_ratio.unwrap() - 1310.0 / 11200.0).abs() < 1e-9);
+        assert!((r.spt_kh_seismic.unwrap() - 2.0 * 11200.0 / 0.3).abs() < 1e-6);
+
+        // 最寄りのN値が離れすぎている場合は比較しない
+        let calculator = LateralLoadCalculator::new(PileSpec::new(0.3, None), 0.1);
+        let result = calculator.calculate_from(&tests, &spts, &layers);
+        assert!(result[0].n_value.is_none());
+        assert!(result[0].kh_normal.is_some());
+
+        let mut result = result;
+        result[0].layer_name = Some("シルト,砂混じり".to_string());
+        let csv = to_csv(&result);
+        assert!(csv.lines().nth(1).unwrap().starts_with("10,02,8,12,\"シルト,砂混じり\","));
+    }
+}
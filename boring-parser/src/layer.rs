@@ -0,0 +1,339 @@
+//! 土質・岩種区分の層区分を取得するモジュール
+//!
+//! 各バージョンで名称・構造が異なる土質岩種区分（v1.10: 地質区分、v3.00: 岩石土区分、
+//! v4.00: 工学的地質区分名現場土質名）を、上端・下端深度を持つ層のリストとして
+//! 統一的に取得できます。
+//!
+//! 地層岩体区分（v1.10: 地層区分）は上端・下端深度と地層岩体名を持つ層のリストとして
+//! [`StratumLayers`] で取得できます。
+//!
+//! 土質名・記号から土質の大分類（[`SoilClass`]）を判定できます。
+
+use serde::Serialize;
+
+use crate::types::parse_f64;
+
+/// 土質・岩種区分の1層
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct SoilLayer {
+    /// 上端深度 (m)
+    pub top_depth: f64,
+    /// 下端深度 (m)
+    pub bottom_depth: f64,
+    /// 土質岩種名（例: "シルト質砂"）
+    pub name: Option<String>,
+    /// 土質岩種記号（例: "SM"）
+    pub symbol: Option<String>,
+}
+
+impl SoilLayer {
+    /// 指定深度がこの層に含まれるか（上端を含み下端を含まない）
+    pub fn contains(&self, depth: f64) -> bool {
+        self.top_depth <= depth && depth < self.bottom_depth
+    }
+
+    /// 層厚 (m)
+    pub fn thickness(&self) -> f64 {
+        self.bottom_depth - self.top_depth
+    }
+}
+
+/// 下端深度の並びから層リストを組み立てる
+///
+/// XMLでは各層が下端深度のみを持つため、直上の層の下端深度（先頭は0m）を上端深度とする。
+/// 深度がパースできない行は読み飛ばす。
+pub fn layers_from_bottom_depths<'a, I>(rows: I) -> Vec<SoilLayer>
+where
+    I: IntoIterator<Item = (Option<&'a str>, Option<&'a str>, Option<&'a str>)>,
+{
+    let mut layers = Vec::new();
+    let mut top_depth = 0.0;
+
+    for (depth, name, symbol) in rows {
+        let Some(bottom_depth) = parse_f64(depth) else {
+            continue;
+        };
+        layers.push(SoilLayer {
+            top_depth,
+            bottom_depth,
+            name: non_empty(name),
+            symbol: non_empty(symbol),
+        });
+        top_depth = bottom_depth;
+    }
+
+    layers
+}
+
+/// 上端・下端深度の並びから層リストを組み立てる
+///
+/// 地層岩体区分のように各層が上端・下端深度を持つ場合に使う。記号はない。
+/// どちらかの深度がパースできない行は読み飛ばす。
+pub fn layers_from_depth_ranges<'a, I>(rows: I) -> Vec<SoilLayer>
+where
+    I: IntoIterator<Item = (Option<&'a str>, Option<&'a str>, Option<&'a str>)>,
+{
+    rows.into_iter()
+        .filter_map(|(top, bottom, name)| {
+            Some(SoilLayer {
+                top_depth: parse_f64(top)?,
+                bottom_depth: parse_f64(bottom)?,
+                name: non_empty(name),
+                symbol: None,
+            })
+        })
+        .collect()
+}
+
+/// 指定深度を含む層を取得
+pub fn layer_at(layers: &[SoilLayer], depth: f64) -> Option<&SoilLayer> {
+    layers.iter().find(|layer| layer.contains(depth))
+}
+
+/// 最も後ろに現れるキーワードの値を取得
+///
+/// 日本語の土質名は主たる土質が末尾に来るため（例: シルト質砂は砂）、土質名の判定に使う。
+pub(crate) fn last_keyword<K: AsRef<str>, T: Copy>(name: &str, keywords: &[(K, T)]) -> Option<T> {
+    keywords
+        .iter()
+        .filter_map(|(k, value)| name.rfind(k.as_ref()).map(|i| (i + k.as_ref().len(), *value)))
+        .max_by_key(|(end, _)| *end)
+        .map(|(_, value)| value)
+}
+
+/// 土質の大分類
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize)]
+pub enum SoilClass {
+    /// 粘性土（シルトを含む）
+    Clay,
+    /// 砂質土
+    Sand,
+    /// 礫質土
+    Gravel,
+    /// 有機質土（腐植土・泥炭）
+    Organic,
+    /// 岩
+    Rock,
+}
+
+impl SoilClass {
+    /// 土質名から判定（最も後ろに現れるキーワードで決める。例: シルト質砂は砂質土）
+    pub fn from_name(name: &str) -> Option<Self> {
+        const KEYWORDS: [(&str, SoilClass); 9] = [
+            ("腐植", SoilClass::Organic),
+            ("有機", SoilClass::Organic),
+            ("泥炭", SoilClass::Organic),
+            ("礫", SoilClass::Gravel),
+            ("砂", SoilClass::Sand),
+            ("シルト", SoilClass::Clay),
+            ("粘土", SoilClass::Clay),
+            ("粘性土", SoilClass::Clay),
+            ("岩", SoilClass::Rock),
+        ];
+        last_keyword(name, &KEYWORDS)
+    }
+
+    /// 土質・岩種区分の層から判定（名称で判定できない場合は記号の1文字目）
+    pub fn from_layer(layer: &SoilLayer) -> Option<Self> {
+        layer
+            .name
+            .as_deref()
+            .and_then(Self::from_name)
+            .or_else(|| Self::from_letter(layer.symbol.as_deref()?.chars().next()?))
+    }
+
+    /// 記号の文字
+    pub(crate) fn letter(&self) -> char {
+        match self {
+            Self::Clay => 'c',
+            Self::Sand => 's',
+            Self::Gravel => 'g',
+            Self::Organic => 'p',
+            Self::Rock => 'r',
+        }
+    }
+
+    /// 記号の文字（大文字）から判定
+    pub(crate) fn from_letter(c: char) -> Option<Self> {
+        match c {
+            'C' | 'M' => Some(Self::Clay),
+            'S' => Some(Self::Sand),
+            'G' => Some(Self::Gravel),
+            'P' | 'O' => Some(Self::Organic),
+            'R' => Some(Self::Rock),
+            _ => None,
+        }
+    }
+}
+
+fn non_empty(value: Option<&str>) -> Option<String> {
+    let trimmed = value?.trim();
+    (!trimmed.is_empty()).then(|| trimmed.to_string())
+}
+
+/// 土質・岩種区分の層リストを取得するためのトレイト
+pub trait SoilLayers {
+    /// 土質・岩種区分を上端・下端深度付きの層リストとして取得
+    fn soil_layers(&self) -> Vec<SoilLayer>;
+}
+
+impl SoilLayers for crate::boring_structs_400::Boring400 {
+    fn soil_layers(&self) -> Vec<SoilLayer> {
+        layers_from_bottom_depths(self.core.engineering_geology_classifications.iter().map(|c| {
+            (c.depth.as_deref(), c.name.as_deref(), c.symbol.as_deref())
+        }))
+    }
+}
+
+impl SoilLayers for crate::boring_structs_300::Boring300 {
+    fn soil_layers(&self) -> Vec<SoilLayer> {
+        layers_from_bottom_depths(self.core.rock_soil_classifications.iter().map(|c| {
+            (
+                c.depth.as_deref(),
+                c.rock_soil_name.as_deref(),
+                c.rock_soil_symbol.as_deref(),
+            )
+        }))
+    }
+}
+
+impl SoilLayers for crate::boring_structs_210::Boring210 {
+    fn soil_layers(&self) -> Vec<SoilLayer> {
+        layers_from_bottom_depths(self.core.soil_rock_classifications.iter().map(|c| {
+            (c.depth.as_deref(), c.classification1.as_deref(), c.code1.as_deref())
+        }))
+    }
+}
+
+impl SoilLayers for crate::boring_structs_200::Boring200 {
+    fn soil_layers(&self) -> Vec<SoilLayer> {
+        layers_from_bottom_depths(self.core.soil_rock_classifications.iter().map(|c| {
+            (c.depth.as_deref(), c.classification1.as_deref(), c.code1.as_deref())
+        }))
+    }
+}
+
+impl SoilLayers for crate::boring_structs_110::Boring110 {
+    fn soil_layers(&self) -> Vec<SoilLayer> {
+        // v1.10には土質岩種記号がない（地質コードは分類コードのため記号には使わない）
+        layers_from_bottom_depths(
+            self.core
+                .geological_classifications
+                .iter()
+                .map(|c| (c.depth.as_deref(), c.classification1.as_deref(), None)),
+        )
+    }
+}
+
+/// 地層岩体区分の層リストを取得するためのトレイト
+pub trait StratumLayers {
+    /// 地層岩体区分を上端・下端深度付きの層リストとして取得（名称は地層岩体名）
+    fn stratum_layers(&self) -> Vec<SoilLayer>;
+}
+
+impl StratumLayers for crate::boring_structs_400::Boring400 {
+    fn stratum_layers(&self) -> Vec<SoilLayer> {
+        layers_from_depth_ranges(self.core.stratum_rock_classifications.iter().map(|c| {
+            (c.start_depth.as_deref(), c.end_depth.as_deref(), c.stratum_rock_name.as_deref())
+        }))
+    }
+}
+
+impl StratumLayers for crate::boring_structs_300::Boring300 {
+    fn stratum_layers(&self) -> Vec<SoilLayer> {
+        layers_from_depth_ranges(self.core.stratum_rock_classifications.iter().map(|c| {
+            (c.start_depth.as_deref(), c.end_depth.as_deref(), c.stratum_rock_name.as_deref())
+        }))
+    }
+}
+
+impl StratumLayers for crate::boring_structs_210::Boring210 {
+    fn stratum_layers(&self) -> Vec<SoilLayer> {
+        layers_from_depth_ranges(self.core.stratum_rock_classifications.iter().map(|c| {
+            (c.start_depth.as_deref(), c.end_depth.as_deref(), c.stratum_rock_name.as_deref())
+        }))
+    }
+}
+
+impl StratumLayers for crate::boring_structs_200::Boring200 {
+    fn stratum_layers(&self) -> Vec<SoilLayer> {
+        layers_from_depth_ranges(self.core.stratum_rock_classifications.iter().map(|c| {
+            (c.start_depth.as_deref(), c.end_depth.as_deref(), c.stratum_rock_name.as_deref())
+        }))
+    }
+}
+
+impl StratumLayers for crate::boring_structs_110::Boring110 {
+    fn stratum_layers(&self) -> Vec<SoilLayer> {
+        layers_from_depth_ranges(self.core.geostratum_classifications.iter().map(|c| {
+            (c.start_depth.as_deref(), c.end_depth.as_deref(), c.rock_body_name.as_deref())
+        }))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_layers_from_bottom_depths() {
+        let rows = vec![
+            (Some("1.80"), Some("　埋土（砂）"), Some("FI")),
+            (Some("3.00"), Some("シルト質砂"), Some("SM")),
+            (Some(""), Some("不明"), None),
+            (Some("5.50"), Some("粘土"), Some("")),
+        ];
+        let layers = layers_from_bottom_depths(rows);
+
+        assert_eq!(layers.len(), 3);
+        assert_eq!(layers[0].top_depth, 0.0);
+        assert_eq!(layers[0].bottom_depth, 1.8);
+        assert_eq!(layers[0].name.as_deref(), Some("埋土（砂）"));
+        assert_eq!(layers[1].top_depth, 1.8);
+        assert_eq!(layers[2].top_depth, 3.0);
+        assert_eq!(layers[2].symbol, None);
+    }
+
+    #[test]
+    fn test_layer_at() {
+        let layers = layers_from_bottom_depths(vec![
+            (Some("2.0"), Some("砂"), None),
+            (Some("4.0"), Some("粘土"), None),
+        ]);
+
+        assert_eq!(layer_at(&layers, 0.0).unwrap().name.as_deref(), Some("砂"));
+        assert_eq!(layer_at(&layers, 2.0).unwrap().name.as_deref(), Some("粘土"));
+        assert!(layer_at(&layers, 4.0).is_none());
+    }
+
+    #[test]
+    fn test_soil_class() {
+        assert_eq!(SoilClass::from_name("シルト質砂"), Some(SoilClass::Sand));
+        assert_eq!(SoilClass::from_name("砂質シルト"), Some(SoilClass::Clay));
+        assert_eq!(SoilClass::from_name("埋土"), None);
+
+        let layer = |name: Option<&str>, symbol: &str| SoilLayer {
+            top_depth: 0.0,
+            bottom_depth: 1.0,
+            name: name.map(str::to_string),
+            symbol: Some(symbol.to_string()),
+        };
+        assert_eq!(SoilClass::from_layer(&layer(Some("礫混じり砂"), "G")), Some(SoilClass::Sand));
+        assert_eq!(SoilClass::from_layer(&layer(None, "Rh")), Some(SoilClass::Rock));
+        assert_eq!(SoilClass::from_layer(&layer(None, "Bk")), None);
+    }
+
+    #[test]
+    fn test_layers_from_depth_ranges() {
+        let layers = layers_from_depth_ranges(vec![
+            (Some("0.00"), Some("24.55"), Some("○○層")),
+            (Some("24.55"), None, Some("不明")),
+            (Some("24.55"), Some("30.15"), Some("")),
+        ]);
+
+        assert_eq!(layers.len(), 2);
+        assert_eq!((layers[0].top_depth, layers[0].bottom_depth), (0.0, 24.55));
+        assert_eq!(layers[0].name.as_deref(), Some("○○層"));
+        assert_eq!(layers[1].name, None);
+    }
+}
@@ -1,10 +1,34 @@
+pub mod accuracy;
+pub mod batch;
 pub mod boring_structs_110;
 pub mod boring_structs_200;
 pub mod boring_structs_210;
 pub mod boring_structs_300;
 pub mod boring_structs_400;
+pub mod collection;
 pub mod coordinate;
+pub mod correlation;
+pub mod dms;
+pub mod duplicate;
+pub mod elastic;
+pub mod elevation;
 pub mod error;
+pub mod geoid;
+pub mod groundwater;
+pub mod lateral_load;
+pub mod layer;
+pub mod local_coordinate;
+pub mod location_export;
+pub mod lugeon;
+pub mod mesh_code;
+pub mod param_cache;
 pub mod parser;
+pub mod permeability;
+pub mod plane_rectangular;
+pub mod section;
+pub mod semi_dynamic;
+pub mod spt;
+pub mod trajectory;
 pub mod transform;
 pub mod types;
+pub mod wgs84;
@@ -0,0 +1,616 @@
+//! ローカル座標の解釈モジュール
+//!
+//! `ローカル座標` の座標定義は自由記述で、平面直角座標・現場座標・道路の測点などが
+//! 混在している。[`LocalCoordinates::parse`] は座標定義からよく使われる形式を判別し、
+//! 型付きの座標に変換する。
+//!
+//! # 判別する座標定義
+//! - 平面直角座標: 系番号を含むX・Y（"9系X"、"平面直角座標IX系 Y"、"X(第9系)" など）
+//! - 現場座標: 系番号のないX・Y。原点・回転角を記載していれば（"X（原点 9系 X=-35000 Y=-6000 回転30度）"）
+//!   平面直角座標に変換できる
+//! - 測点: "測点"・"No."・"STA"・"追加距離"・"KP" など。値は "No.12+15.5"（1測点20m）、
+//!   "1K250"、"1+250" の形式に対応する
+//! - 離れ: "離れ"・"オフセット" など。左を負、右を正とする
+//! - 高さ: "D.L."・"T.P."・"標高"・"K.B.M." など
+//!
+//! 平面直角座標（原点を記載した現場座標を含む）はJGD2011の緯度経度に変換し、
+//! 経度緯度情報と照合できる。測点は路線の線形がないため変換しない。
+
+use serde::Serialize;
+
+use crate::coordinate::GeoCoordinate;
+use crate::plane_rectangular::{PlaneRectangular, PlaneRectangularZone};
+use crate::transform::{JgdTransformer, SurveyDate};
+use crate::types::parse_f64;
+
+/// 測点の間隔 (m)
+const STATION_INTERVAL: f64 = 20.0;
+
+/// ローカル座標の記載（座標定義と座標の組）
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct LocalCoordinateEntry {
+    /// 座標定義
+    pub definition: String,
+    /// 座標
+    pub value: String,
+}
+
+/// 現場座標の原点
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+pub struct SiteOrigin {
+    /// 平面直角座標の系（記載がない場合はNone）
+    pub zone: Option<PlaneRectangularZone>,
+    /// 原点の平面直角座標X (m)
+    pub x: f64,
+    /// 原点の平面直角座標Y (m)
+    pub y: f64,
+    /// 現場座標のX軸の方向角（平面直角座標のX軸から時計回り、度）
+    pub rotation: f64,
+}
+
+/// ローカル座標の水平位置
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub enum LocalHorizontal {
+    /// 平面直角座標
+    PlaneRectangular(PlaneRectangular),
+    /// 現場座標
+    SiteGrid {
+        /// X座標 (m)
+        x: f64,
+        /// Y座標 (m)
+        y: f64,
+        /// 原点（記載がない場合はNone）
+        origin: Option<SiteOrigin>,
+    },
+    /// 測点と離れ
+    Chainage {
+        /// 追加距離 (m)
+        station: f64,
+        /// 中心からの離れ（左が負、右が正、m）
+        offset: Option<f64>,
+        /// 測点の座標定義
+        label: String,
+    },
+}
+
+/// 高さの基準
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub enum HeightDatum {
+    /// 東京湾平均海面（T.P.、標高）
+    TokyoPeil,
+    /// 工事用基準面（D.L.）
+    DatumLevel,
+    /// 仮ベンチマーク（K.B.M.、B.M.）
+    BenchMark,
+    /// 地表面（孔口標高が不明な場合の孔口基準の相対高さ）
+    GroundLevel,
+    /// その他（座標定義をそのまま保持）
+    Other(String),
+}
+
+impl HeightDatum {
+    /// 正規化済み（半角・大文字）の記載から基準を判別（"T.P."、"D.L."、"K.B.M." など）
+    ///
+    /// 英字の略号は単語の一部（"OUTPUT" の "TP" など）には一致しない。
+    pub fn from_label(label: &str) -> Option<Self> {
+        let has = |keys: &[&str]| keys.iter().any(|k| has_keyword(label, k));
+        if has(&["T.P", "TP", "標高"]) {
+            Some(HeightDatum::TokyoPeil)
+        } else if has(&["D.L", "DL"]) {
+            Some(HeightDatum::DatumLevel)
+        } else if has(&["B.M", "BM", "KBM"]) {
+            Some(HeightDatum::BenchMark)
+        } else if has(&["G.L", "GL"]) {
+            Some(HeightDatum::GroundLevel)
+        } else {
+            None
+        }
+    }
+}
+
+/// ローカル座標の高さ
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct LocalHeight {
+    /// 基準
+    pub datum: HeightDatum,
+    /// 高さ (m)
+    pub value: f64,
+}
+
+/// 解釈したローカル座標
+#[derive(Debug, Clone, PartialEq, Default, Serialize)]
+pub struct LocalCoordinates {
+    /// 水平位置
+    pub horizontal: Option<LocalHorizontal>,
+    /// 高さ
+    pub height: Option<LocalHeight>,
+    /// 解釈できなかった記載
+    pub unrecognized: Vec<LocalCoordinateEntry>,
+}
+
+/// 座標定義の種類
+enum EntryKind {
+    X,
+    Y,
+    Station,
+    Offset,
+    Height(HeightDatum),
+}
+
+impl LocalCoordinates {
+    /// ローカル座標の記載を解釈する
+    ///
+    /// # Example
+    /// ```ignore
+    /// let local = LocalCoordinates::parse(&boring.local_coordinate_entries());
+    /// if let Some(LocalHorizontal::Chainage { station, offset, .. }) = &local.horizontal {
+    ///     println!("追加距離 {}m 離れ {:?}m", station, offset);
+    /// }
+    /// ```
+    pub fn parse(entries: &[LocalCoordinateEntry]) -> Self {
+        let mut result = Self::default();
+        let mut x: Option<(f64, &LocalCoordinateEntry)> = None;
+        let mut y: Option<(f64, &LocalCoordinateEntry)> = None;
+        let mut station: Option<(f64, &LocalCoordinateEntry)> = None;
+        let mut offset: Option<f64> = None;
+
+        for entry in entries {
+            let definition = normalize(&entry.definition);
+            let value = normalize(&entry.value);
+            let parsed = match classify(&definition) {
+                Some(EntryKind::X) => parse_f64(Some(&value)).map(|v| x = Some((v, entry))),
+                Some(EntryKind::Y) => parse_f64(Some(&value)).map(|v| y = Some((v, entry))),
+                Some(EntryKind::Station) => {
+                    parse_station(&value, &definition).map(|v| station = Some((v, entry)))
+                }
+                Some(EntryKind::Offset) => {
+                    parse_offset(&value, &definition).map(|v| offset = Some(v))
+                }
+                Some(EntryKind::Height(datum)) => parse_f64(Some(&value))
+                    .map(|value| result.height = Some(LocalHeight { datum, value })),
+                None => None,
+            };
+            if parsed.is_none() {
+                result.unrecognized.push(entry.clone());
+            }
+        }
+
+        result.horizontal = if let Some((station, entry)) = station {
+            Some(LocalHorizontal::Chainage {
+                station,
+                offset,
+                label: entry.definition.clone(),
+            })
+        } else if let (Some((x, x_entry)), Some((y, y_entry))) = (x, y) {
+            let x_definition = normalize(&x_entry.definition);
+            let y_definition = normalize(&y_entry.definition);
+            let origin = parse_origin(&x_definition).or_else(|| parse_origin(&y_definition));
+            // 原点の記載にある系は現場座標の系であり、座標そのものの系ではない
+            let zone = zone_of(axis_part(&x_definition)).or_else(|| zone_of(axis_part(&y_definition)));
+            let has_origin = x_definition.contains("原点") || y_definition.contains("原点");
+            match zone {
+                // 原点を記載しているのに解釈できない場合も現場座標とする
+                Some(zone) if !has_origin => {
+                    Some(LocalHorizontal::PlaneRectangular(PlaneRectangular { zone, x, y }))
+                }
+                _ => Some(LocalHorizontal::SiteGrid { x, y, origin }),
+            }
+        } else {
+            // 片方だけのX・Yは解釈できない
+            for (_, entry) in [x, y].into_iter().flatten() {
+                result.unrecognized.push(entry.clone());
+            }
+            None
+        };
+
+        result
+    }
+
+    /// 平面直角座標を取得（平面直角座標、または系・原点を記載した現場座標の場合）
+    pub fn to_plane_rectangular(&self) -> Option<PlaneRectangular> {
+        match self.horizontal.as_ref()? {
+            LocalHorizontal::PlaneRectangular(p) => Some(*p),
+            LocalHorizontal::SiteGrid {
+                x,
+                y,
+                origin: Some(origin),
+            } => {
+                let (sin, cos) = origin.rotation.to_radians().sin_cos();
+                Some(PlaneRectangular {
+                    zone: origin.zone?,
+                    x: origin.x + x * cos - y * sin,
+                    y: origin.y + x * sin + y * cos,
+                })
+            }
+            _ => None,
+        }
+    }
+
+    /// JGD2011の (緯度, 経度) に変換
+    pub fn to_jgd2011(&self) -> Option<(f64, f64)> {
+        Some(self.to_plane_rectangular()?.to_geographic())
+    }
+}
+
+/// ローカル座標と経度緯度情報の照合結果
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+pub struct LocalCoordinateCheck {
+    /// ローカル座標から求めた平面直角座標
+    pub local: PlaneRectangular,
+    /// 経度緯度情報をJGD2011に変換して同じ系に投影した平面直角座標
+    pub declared: PlaneRectangular,
+    /// 水平距離 (m)
+    pub distance: f64,
+}
+
+/// ローカル座標を取得するためのトレイト
+pub trait SurveyLocalCoordinates: GeoCoordinate + SurveyDate {
+    /// ローカル座標の記載
+    fn local_coordinate_entries(&self) -> Vec<LocalCoordinateEntry>;
+
+    /// ローカル座標を解釈
+    fn local_coordinates(&self) -> LocalCoordinates {
+        LocalCoordinates::parse(&self.local_coordinate_entries())
+    }
+
+    /// ローカル座標を経度緯度情報と照合する
+    ///
+    /// ローカル座標が平面直角座標に変換できない場合、経度緯度情報が変換できない場合はNone。
+    ///
+    /// # Example
+    /// ```ignore
+    /// if let Some(check) = boring.check_local_coordinates(&transformer) {
+    ///     println!("ローカル座標と経度緯度の差: {:.1}m", check.distance);
+    /// }
+    /// ```
+    fn check_local_coordinates(&self, transformer: &JgdTransformer) -> Option<LocalCoordinateCheck>
+    where
+        Self: Sized,
+    {
+        let local = self.local_coordinates().to_plane_rectangular()?;
+        let result = transformer.transform_document(self).ok()?;
+        let declared = PlaneRectangular::from_geographic(result.latitude, result.longitude, local.zone);
+        Some(LocalCoordinateCheck {
+            local,
+            declared,
+            distance: (local.x - declared.x).hypot(local.y - declared.y),
+        })
+    }
+}
+
+impl SurveyLocalCoordinates for crate::boring_structs_400::Boring400 {
+    fn local_coordinate_entries(&self) -> Vec<LocalCoordinateEntry> {
+        entries_210(&self.title.local_coordinates)
+    }
+}
+
+impl SurveyLocalCoordinates for crate::boring_structs_300::Boring300 {
+    fn local_coordinate_entries(&self) -> Vec<LocalCoordinateEntry> {
+        entries_210(&self.title.local_coordinates)
+    }
+}
+
+impl SurveyLocalCoordinates for crate::boring_structs_210::Boring210 {
+    fn local_coordinate_entries(&self) -> Vec<LocalCoordinateEntry> {
+        entries_210(&self.title.local_coordinates)
+    }
+}
+
+impl SurveyLocalCoordinates for crate::boring_structs_200::Boring200 {
+    fn local_coordinate_entries(&self) -> Vec<LocalCoordinateEntry> {
+        self.title
+            .local_coordinate
+            .as_ref()
+            .map(|c| {
+                entries_xyz([
+                    (&c.x_definition, &c.x),
+                    (&c.y_definition, &c.y),
+                    (&c.z_definition, &c.z),
+                ])
+            })
+            .unwrap_or_default()
+    }
+}
+
+impl SurveyLocalCoordinates for crate::boring_structs_110::Boring110 {
+    fn local_coordinate_entries(&self) -> Vec<LocalCoordinateEntry> {
+        self.title
+            .local_coordinate_definition
+            .as_ref()
+            .map(|c| {
+                entries_xyz([
+                    (&c.x_definition, &c.x),
+                    (&c.y_definition, &c.y),
+                    (&c.z_definition, &c.z),
+                ])
+            })
+            .unwrap_or_default()
+    }
+}
+
+/// v2.10以降の `ローカル座標` の記載
+fn entries_210(
+    coordinates: &[crate::boring_structs_210::LocalCoordinate210],
+) -> Vec<LocalCoordinateEntry> {
+    coordinates
+        .iter()
+        .filter_map(|c| entry(&c.definition, &c.coordinate))
+        .collect()
+}
+
+/// v1.10・v2.00の `X座標定義`〜`Z座標` の記載
+fn entries_xyz(pairs: [(&Option<String>, &Option<String>); 3]) -> Vec<LocalCoordinateEntry> {
+    pairs
+        .into_iter()
+        .filter_map(|(definition, value)| entry(definition, value))
+        .collect()
+}
+
+/// 座標のある記載（座標定義がない場合は空文字列）
+fn entry(definition: &Option<String>, value: &Option<String>) -> Option<LocalCoordinateEntry> {
+    let value = value.as_deref()?.trim();
+    (!value.is_empty()).then(|| LocalCoordinateEntry {
+        definition: definition.as_deref().unwrap_or_default().trim().to_string(),
+        value: value.to_string(),
+    })
+}
+
+/// 全角英数字・記号を半角に、英字を大文字にし、空白を除く
+pub(crate) fn normalize(text: &str) -> String {
+    text.chars()
+        .filter_map(|c| {
+            let c = match c {
+                '\u{FF01}'..='\u{FF5E}' => char::from_u32(c as u32 - 0xFEE0).unwrap_or(c),
+                '−' | 'ー' => '-',
+                _ => c,
+            };
+            (!c.is_whitespace()).then(|| c.to_ascii_uppercase())
+        })
+        .collect()
+}
+
+/// 記載にキーワードを含むか
+///
+/// 英字のキーワードは前後が英字でない場合のみ一致する（"STATUS" は "STA" に一致しない）。
+fn has_keyword(text: &str, keyword: &str) -> bool {
+    if !keyword.is_ascii() {
+        return text.contains(keyword);
+    }
+    text.match_indices(keyword).any(|(i, _)| {
+        let before = text[..i].chars().next_back();
+        let after = text[i + keyword.len()..].chars().next();
+        !before.is_some_and(|c| c.is_ascii_alphabetic()) && !after.is_some_and(|c| c.is_ascii_alphabetic())
+    })
+}
+
+/// 座標定義の種類を判別（正規化済みの文字列）
+fn classify(definition: &str) -> Option<EntryKind> {
+    let has = |keys: &[&str]| keys.iter().any(|k| has_keyword(definition, k));
+
+    if has(&["離れ", "オフセット", "OFFSET", "中心から"]) {
+        return Some(EntryKind::Offset);
+    }
+    if has(&["測点", "NO.", "STA", "追加距離", "距離程", "キロ程", "KP"]) {
+        return Some(EntryKind::Station);
+    }
+    if let Some(datum) = HeightDatum::from_label(definition) {
+        return Some(EntryKind::Height(datum));
+    }
+
+    // 系番号・原点の記載を除いて軸を判定する（"IX系" のXを軸と取り違えない）
+    let axis = strip_zone(axis_part(definition));
+    if axis.contains('Z') || axis.contains("高さ") {
+        Some(EntryKind::Height(HeightDatum::Other(definition.to_string())))
+    } else if axis.contains('X') {
+        Some(EntryKind::X)
+    } else if axis.contains('Y') {
+        Some(EntryKind::Y)
+    } else {
+        None
+    }
+}
+
+/// 測点を追加距離 (m) に変換
+fn parse_station(value: &str, definition: &str) -> Option<f64> {
+    let value = value
+        .trim_start_matches("NO.")
+        .trim_start_matches("NO")
+        .trim_start_matches("STA.")
+        .trim_start_matches("STA");
+
+    // "1K250"、"1K+250"
+    if let Some((km, m)) = value.split_once('K') {
+        let m = m.trim_start_matches('+');
+        let m = if m.is_empty() { 0.0 } else { parse_f64(Some(m))? };
+        return Some(parse_f64(Some(km))? * 1000.0 + m);
+    }
+    // "12+15.5"（No.）、"1+250"（STA、KP）
+    if let Some((major, minor)) = value.split_once('+') {
+        let unit = if has_keyword(definition, "NO.") || definition.contains("測点") {
+            STATION_INTERVAL
+        } else {
+            1000.0
+        };
+        return Some(parse_f64(Some(major))? * unit + parse_f64(Some(minor))?);
+    }
+
+    let number = parse_f64(Some(value))?;
+    if has_keyword(definition, "KP") || definition.contains("キロ程") || has_keyword(definition, "KM") {
+        Some(number * 1000.0)
+    } else {
+        Some(number)
+    }
+}
+
+/// 離れを左が負、右が正の値 (m) に変換
+fn parse_offset(value: &str, definition: &str) -> Option<f64> {
+    let (sign, rest) = if let Some(rest) = value.strip_prefix('左').or(value.strip_prefix('L')) {
+        (-1.0, rest)
+    } else if let Some(rest) = value.strip_prefix('右').or(value.strip_prefix('R')) {
+        (1.0, rest)
+    } else if definition.contains('左') {
+        (-1.0, value)
+    } else {
+        (1.0, value)
+    };
+    Some(sign * parse_f64(Some(rest.trim_end_matches('M')))?)
+}
+
+/// 座標定義から平面直角座標の系を取得
+fn zone_of(definition: &str) -> Option<PlaneRectangularZone> {
+    let (before, _) = definition.split_once('系')?;
+    let before = before.trim_start_matches(|c: char| !c.is_ascii_alphanumeric());
+    let digits: String = before
+        .chars()
+        .rev()
+        .take_while(|c| c.is_ascii_digit())
+        .collect::<Vec<_>>()
+        .into_iter()
+        .rev()
+        .collect();
+    if !digits.is_empty() {
+        return PlaneRectangularZone::new(digits.parse().ok()?);
+    }
+    let roman: String = before
+        .chars()
+        .rev()
+        .take_while(|c| matches!(c, 'I' | 'V' | 'X'))
+        .collect::<Vec<_>>()
+        .into_iter()
+        .rev()
+        .collect();
+    PlaneRectangularZone::all().find(|z| z.roman() == roman)
+}
+
+/// 原点の記載を除いた座標定義
+fn axis_part(definition: &str) -> &str {
+    definition.split("原点").next().unwrap_or_default()
+}
+
+/// 系番号（"9系"、"IX系"）を除いた座標定義
+fn strip_zone(definition: &str) -> String {
+    let Some((before, after)) = definition.split_once('系') else {
+        return definition.to_string();
+    };
+    let kept = before.trim_end_matches(|c: char| c.is_ascii_digit() || matches!(c, 'I' | 'V' | 'X'));
+    format!("{}{}", kept, after)
+}
+
+/// 座標定義に記載された現場座標の原点（"原点 9系 X=-35000 Y=-6000 回転30度"）
+fn parse_origin(definition: &str) -> Option<SiteOrigin> {
+    let (_, text) = definition.split_once("原点")?;
+    let zone = zone_of(text);
+    // "IX系" のXを原点のXと取り違えない
+    let text = strip_zone(text);
+    let number_after = |key: &str| -> Option<f64> {
+        let (_, rest) = text.split_once(key)?;
+        let rest = rest.trim_start_matches([':', '=', '＝']);
+        let number: String = rest
+            .chars()
+            .take_while(|c| c.is_ascii_digit() || matches!(c, '.' | '-' | '+'))
+            .collect();
+        parse_f64(Some(&number))
+    };
+    Some(SiteOrigin {
+        zone,
+        x: number_after("X")?,
+        y: number_after("Y")?,
+        rotation: number_after("回転角")
+            .or_else(|| number_after("回転"))
+            .unwrap_or(0.0),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entries(pairs: &[(&str, &str)]) -> Vec<LocalCoordinateEntry> {
+        pairs
+            .iter()
+            .map(|(definition, value)| LocalCoordinateEntry {
+                definition: definition.to_string(),
+                value: value.to_string(),
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_parse_plane_rectangular_and_site_grid() {
+        // サンプルXMLの記載は系番号がないため現場座標
+        let local = LocalCoordinates::parse(&entries(&[("X", "3000.000"), ("Y", "4000.000"), ("D.L.", "50.00")]));
+        assert_eq!(local.horizontal, Some(LocalHorizontal::SiteGrid { x: 3000.0, y: 4000.0, origin: None }));
+        assert_eq!(local.height, Some(LocalHeight { datum: HeightDatum::DatumLevel, value: 50.0 }));
+        assert!(local.unrecognized.is_empty());
+        assert_eq!(local.to_jgd2011(), None);
+
+        let local = LocalCoordinates::parse(&entries(&[("平面直角座標ＩＸ系 Ｘ", "-35000.0"), ("IX系Y", "-6000.0")]));
+        let zone = PlaneRectangularZone::new(9).unwrap();
+        assert_eq!(
+            local.to_plane_rectangular(),
+            Some(PlaneRectangular { zone, x: -35000.0, y: -6000.0 })
+        );
+        let (lat, lng) = local.to_jgd2011().unwrap();
+        assert!((lat - 35.684).abs() < 0.01 && (lng - 139.767).abs() < 0.01);
+
+        // 原点と回転角を記載した現場座標
+        let local = LocalCoordinates::parse(&entries(&[
+            ("現場X（原点 9系 X=-35000 Y=-6000 回転90度）", "10"),
+            ("現場Y", "0"),
+        ]));
+        let p = local.to_plane_rectangular().unwrap();
+        assert_eq!(p.zone, zone);
+        assert!((p.x - -35000.0).abs() < 1e-9 && (p.y - -5990.0).abs() < 1e-9);
+
+        // ローマ数字の系番号で原点を記載した現場座標
+        let local = LocalCoordinates::parse(&entries(&[
+            ("現場X（原点 IX系 X=-35000 Y=-6000 回転90度）", "10"),
+            ("現場Y", "0"),
+        ]));
+        let p = local.to_plane_rectangular().unwrap();
+        assert_eq!(p.zone, zone);
+        assert!((p.x - -35000.0).abs() < 1e-9 && (p.y - -5990.0).abs() < 1e-9);
+
+        // 原点を解釈できない場合は現場座標のまま
+        let local = LocalCoordinates::parse(&entries(&[("現場X（原点 IX系）", "10"), ("現場Y", "0")]));
+        assert_eq!(local.horizontal, Some(LocalHorizontal::SiteGrid { x: 10.0, y: 0.0, origin: None }));
+        assert_eq!(local.to_plane_rectangular(), None);
+    }
+
+    #[test]
+    fn test_keywords_match_whole_tokens() {
+        assert_eq!(HeightDatum::from_label("T.P."), Some(HeightDatum::TokyoPeil));
+        assert_eq!(HeightDatum::from_label("TP+"), Some(HeightDatum::TokyoPeil));
+        assert_eq!(HeightDatum::from_label("K.B.M."), Some(HeightDatum::BenchMark));
+        assert_eq!(HeightDatum::from_label("KBM"), Some(HeightDatum::BenchMark));
+        assert_eq!(HeightDatum::from_label("OUTPUT"), None);
+        assert_eq!(HeightDatum::from_label("MIDLINE"), None);
+
+        assert!(matches!(classify("STA"), Some(EntryKind::Station)));
+        assert!(matches!(classify("測点NO."), Some(EntryKind::Station)));
+        assert!(classify("STATUS").is_none());
+        assert!(classify("BACKPACK").is_none());
+        assert!(matches!(classify("MIDLINEX"), Some(EntryKind::X)));
+    }
+
+    #[test]
+    fn test_parse_chainage() {
+        let local = LocalCoordinates::parse(&entries(&[("測点", "No.12+15.5"), ("中心からの離れ", "左5.0m"), ("T.P.", "12.3")]));
+        assert_eq!(
+            local.horizontal,
+            Some(LocalHorizontal::Chainage { station: 255.5, offset: Some(-5.0), label: "測点".to_string() })
+        );
+        assert_eq!(local.height.as_ref().unwrap().datum, HeightDatum::TokyoPeil);
+        assert_eq!(local.to_jgd2011(), None);
+
+        assert_eq!(parse_station("1K250", "KP"), Some(1250.0));
+        assert_eq!(parse_station("1+250.5", "STA"), Some(1250.5));
+        assert_eq!(parse_station("1.25", "KP"), Some(1250.0));
+        assert_eq!(parse_offset("R3.5", "離れ"), Some(3.5));
+
+        // 解釈できない記載
+        let local = LocalCoordinates::parse(&entries(&[("X", "3000"), ("備考", "現況")]));
+        assert_eq!(local.horizontal, None);
+        assert_eq!(local.unrecognized.len(), 2);
+    }
+}
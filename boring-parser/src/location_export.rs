@@ -0,0 +1,183 @@
+//! 調査位置の書き出しモジュール
+//!
+//! ボーリングの調査位置をWGS84のGeoJSON・CSVに書き出す。
+//! 位置の推定精度（[`HorizontalAccuracy`]）を属性に含めるため、地図上で
+//! 精度の低い地点（縮尺の小さい地形図から読み取った位置など）を区別できる。
+
+use serde::Serialize;
+
+use crate::accuracy::{AcquisitionMethod, HorizontalAccuracy};
+use crate::coordinate::GeoLocation;
+use crate::transform::JgdTransformer;
+use crate::types::csv_field;
+
+/// 書き出す調査位置
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct LocationFeature {
+    /// 名前（ボーリング名など）
+    pub name: String,
+    /// 経度 (度, WGS84)
+    pub longitude: f64,
+    /// 緯度 (度, WGS84)
+    pub latitude: f64,
+    /// 水平精度
+    pub accuracy: HorizontalAccuracy,
+}
+
+impl LocationFeature {
+    /// 緯度経度情報をWGS84に変換して作成
+    ///
+    /// # Example
+    /// ```ignore
+    /// let feature = LocationFeature::from_location(name, &boring.geo_location(), &transformer)?;
+    /// std::fs::write("borings.geojson", to_geojson(&[feature]))?;
+    /// ```
+    pub fn from_location(
+        name: impl Into<String>,
+        location: &GeoLocation,
+        transformer: &JgdTransformer,
+    ) -> Option<Self> {
+        let (longitude, latitude) = location.to_wgs84(transformer)?;
+        Some(Self {
+            name: name.into(),
+            longitude,
+            latitude,
+            accuracy: location.horizontal_accuracy(),
+        })
+    }
+
+    /// 取得方法の名前
+    fn method_name(&self) -> Option<String> {
+        self.accuracy.method.as_ref().map(|m| match m {
+            AcquisitionMethod::Survey => "survey".to_string(),
+            AcquisitionMethod::MapReading { .. } => "map_reading".to_string(),
+            AcquisitionMethod::Other(description) => format!("other:{}", description),
+        })
+    }
+
+    /// 地形図の縮尺分母
+    fn map_scale(&self) -> Option<u32> {
+        match self.accuracy.method {
+            Some(AcquisitionMethod::MapReading { scale }) => scale,
+            _ => None,
+        }
+    }
+}
+
+/// 調査位置をGeoJSON (FeatureCollection) 文字列に変換
+///
+/// 各地点の `properties` に `name`、`acquisition_method`、`map_scale`、
+/// `horizontal_uncertainty_m` を含める。値がない項目は `null` とする。
+pub fn to_geojson(features: &[LocationFeature]) -> String {
+    let opt = |v: Option<String>| v.unwrap_or_else(|| "null".to_string());
+
+    let items: Vec<String> = features
+        .iter()
+        .map(|f| {
+            format!(
+                concat!(
+                    "{{\"type\":\"Feature\",",
+                    "\"geometry\":{{\"type\":\"Point\",\"coordinates\":[{},{}]}},",
+                    "\"properties\":{{\"name\":{},\"acquisition_method\":{},\"map_scale\":{},",
+                    "\"horizontal_uncertainty_m\":{}}}}}"
+                ),
+                f.longitude,
+                f.latitude,
+                json_string(&f.name),
+                opt(f.method_name().map(|m| json_string(&m))),
+                opt(f.map_scale().map(|s| s.to_string())),
+                opt(f.accuracy.uncertainty_m.map(|u| u.to_string())),
+            )
+        })
+        .collect();
+
+    format!(
+        "{{\"type\":\"FeatureCollection\",\"features\":[{}]}}\n",
+        items.join(",")
+    )
+}
+
+/// 調査位置をCSV文字列に変換
+///
+/// 値がない項目は空欄とする。
+pub fn to_csv(features: &[LocationFeature]) -> String {
+    let mut csv = String::from(
+        "name,longitude,latitude,acquisition_method,map_scale,horizontal_uncertainty_m\n",
+    );
+    let opt = |v: Option<String>| v.unwrap_or_default();
+
+    for f in features {
+        csv.push_str(&format!(
+            "{},{},{},{},{},{}\n",
+            csv_field(&f.name),
+            f.longitude,
+            f.latitude,
+            opt(f.method_name()),
+            opt(f.map_scale().map(|s| s.to_string())),
+            opt(f.accuracy.uncertainty_m.map(|u| format!("{:.2}", u))),
+        ));
+    }
+    csv
+}
+
+/// JSONの文字列リテラル
+fn json_string(value: &str) -> String {
+    let mut out = String::with_capacity(value.len() + 2);
+    out.push('"');
+    for c in value.chars() {
+        match c {
+            '"' => out.push_str("\\\""),
+            '\\' => out.push_str("\\\\"),
+            '\n' => out.push_str("\\n"),
+            '\r' => out.push_str("\\r"),
+            '\t' => out.push_str("\\t"),
+            c if (c as u32) < 0x20 => out.push_str(&format!("\\u{:04x}", c as u32)),
+            c => out.push(c),
+        }
+    }
+    out.push('"');
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn features() -> Vec<LocationFeature> {
+        vec![
+            LocationFeature {
+                name: "No.1, \"本線\"".to_string(),
+                longitude: 135.8328,
+                latitude: 34.9981,
+                accuracy: HorizontalAccuracy::new(Some("02"), Some("1,000分の1地形図"), Some("2")),
+            },
+            LocationFeature {
+                name: "No.2".to_string(),
+                longitude: 135.84,
+                latitude: 35.0,
+                accuracy: HorizontalAccuracy::new(None, None, None),
+            },
+        ]
+    }
+
+    #[test]
+    fn test_to_geojson() {
+        let geojson = to_geojson(&features());
+        assert!(geojson.starts_with("{\"type\":\"FeatureCollection\",\"features\":[{\"type\":\"Feature\""));
+        assert!(geojson.contains("\"coordinates\":[135.8328,34.9981]"));
+        assert!(geojson.contains("\"name\":\"No.1, \\\"本線\\\"\""));
+        assert!(geojson.contains("\"acquisition_method\":\"map_reading\",\"map_scale\":1000,\"horizontal_uncertainty_m\":0.7"));
+        assert!(geojson.contains(
+            "\"acquisition_method\":null,\"map_scale\":null,\"horizontal_uncertainty_m\":null"
+        ));
+    }
+
+    #[test]
+    fn test_to_csv() {
+        let csv = to_csv(&features());
+        let lines: Vec<&str> = csv.lines().collect();
+        assert_eq!(lines.len(), 3);
+        assert_eq!(lines[1], "\"No.1, \"\"本線\"\"\",135.8328,34.9981,map_reading,1000,0.70");
+        assert_eq!(lines[2], "No.2,135.84,35,,,");
+    }
+}
@@ -0,0 +1,367 @@
+//! 地域メッシュコードモジュール
+//!
+//! JIS X 0410 の地域メッシュ（1次〜3次、2分の1・4分の1地域メッシュ）を
+//! JGD2011の緯度経度から求め、調査位置の `コード1次`〜`コード3次` と照合する。
+//!
+//! | 区分 | 緯度間隔 | 経度間隔 | 桁数 |
+//! |---|---|---|---|
+//! | 1次メッシュ | 40分 | 1度 | 4 |
+//! | 2次メッシュ | 5分 | 7分30秒 | 6 |
+//! | 3次メッシュ | 30秒 | 45秒 | 8 |
+//! | 2分の1メッシュ | 15秒 | 22.5秒 | 9 |
+//! | 4分の1メッシュ | 7.5秒 | 11.25秒 | 10 |
+
+use serde::Serialize;
+
+use crate::coordinate::GeoCoordinate;
+use crate::transform::{JgdTransformer, SurveyDate, TransformError};
+
+/// 1次メッシュあたりの4分の1メッシュの数（緯度・経度とも）
+const QUARTERS_PER_FIRST: u32 = 320;
+/// 2次メッシュあたりの4分の1メッシュの数
+const QUARTERS_PER_SECOND: u32 = 40;
+/// 3次メッシュあたりの4分の1メッシュの数
+const QUARTERS_PER_THIRD: u32 = 4;
+/// 2分の1メッシュあたりの4分の1メッシュの数
+const QUARTERS_PER_HALF: u32 = 2;
+
+/// 4分の1メッシュの緯度間隔（秒）
+const QUARTER_LAT_SECONDS: f64 = 7.5;
+/// 4分の1メッシュの経度間隔（秒）
+const QUARTER_LON_SECONDS: f64 = 11.25;
+
+/// メッシュ境界上の点を北・東側のメッシュに含めるための許容誤差（メッシュ間隔に対する比）
+const BOUNDARY_EPSILON: f64 = 1e-9;
+
+/// メッシュの区分
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize)]
+pub enum MeshLevel {
+    /// 1次メッシュ（約80km）
+    First,
+    /// 2次メッシュ（約10km）
+    Second,
+    /// 3次メッシュ（約1km）
+    Third,
+    /// 2分の1地域メッシュ（約500m）
+    Half,
+    /// 4分の1地域メッシュ（約250m）
+    Quarter,
+}
+
+/// 地域メッシュコード
+///
+/// # Example
+/// ```ignore
+/// let mesh = MeshCode::from_lat_lng(35.6812, 139.7671)?;
+/// assert_eq!(mesh.code(MeshLevel::Third), "53394611");
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct MeshCode {
+    /// 赤道からの4分の1メッシュの番号（緯度方向）
+    lat_index: u32,
+    /// 東経100度からの4分の1メッシュの番号（経度方向）
+    lon_index: u32,
+}
+
+impl MeshCode {
+    /// JGD2011の緯度経度（度）から地域メッシュコードを求める
+    ///
+    /// 地域メッシュの定義域（北緯0〜66度40分、東経100〜200度）の外ではNone。
+    /// メッシュ境界上の点は北・東側のメッシュに含める。
+    pub fn from_lat_lng(lat: f64, lng: f64) -> Option<Self> {
+        let lat_index = (lat * 3600.0 / QUARTER_LAT_SECONDS + BOUNDARY_EPSILON).floor();
+        let lon_index = ((lng - 100.0) * 3600.0 / QUARTER_LON_SECONDS + BOUNDARY_EPSILON).floor();
+        let limit = (100 * QUARTERS_PER_FIRST) as f64;
+        if !(0.0..limit).contains(&lat_index) || !(0.0..limit).contains(&lon_index) {
+            return None;
+        }
+        Some(Self {
+            lat_index: lat_index as u32,
+            lon_index: lon_index as u32,
+        })
+    }
+
+    /// 1次メッシュコード（4桁）
+    pub fn first(&self) -> String {
+        format!(
+            "{:02}{:02}",
+            self.lat_index / QUARTERS_PER_FIRST,
+            self.lon_index / QUARTERS_PER_FIRST
+        )
+    }
+
+    /// 2次メッシュの区画番号（2桁、1次メッシュ内）
+    pub fn second(&self) -> String {
+        let digit = |i: u32| i % QUARTERS_PER_FIRST / QUARTERS_PER_SECOND;
+        format!("{}{}", digit(self.lat_index), digit(self.lon_index))
+    }
+
+    /// 3次メッシュの区画番号（2桁、2次メッシュ内）
+    pub fn third(&self) -> String {
+        let digit = |i: u32| i % QUARTERS_PER_SECOND / QUARTERS_PER_THIRD;
+        format!("{}{}", digit(self.lat_index), digit(self.lon_index))
+    }
+
+    /// 指定した区分のメッシュコード（上位の区分から連結したもの）
+    pub fn code(&self, level: MeshLevel) -> String {
+        match level {
+            MeshLevel::First => self.first(),
+            MeshLevel::Second => self.first() + &self.second(),
+            MeshLevel::Third => self.first() + &self.second() + &self.third(),
+            MeshLevel::Half => {
+                let half = quadrant(
+                    self.lat_index % QUARTERS_PER_THIRD / QUARTERS_PER_HALF,
+                    self.lon_index % QUARTERS_PER_THIRD / QUARTERS_PER_HALF,
+                );
+                format!("{}{}", self.code(MeshLevel::Third), half)
+            }
+            MeshLevel::Quarter => {
+                let quarter = quadrant(
+                    self.lat_index % QUARTERS_PER_HALF,
+                    self.lon_index % QUARTERS_PER_HALF,
+                );
+                format!("{}{}", self.code(MeshLevel::Half), quarter)
+            }
+        }
+    }
+
+    /// 指定した区分のメッシュの南西端（緯度, 経度）
+    pub fn south_west(&self, level: MeshLevel) -> (f64, f64) {
+        let unit = match level {
+            MeshLevel::First => QUARTERS_PER_FIRST,
+            MeshLevel::Second => QUARTERS_PER_SECOND,
+            MeshLevel::Third => QUARTERS_PER_THIRD,
+            MeshLevel::Half => QUARTERS_PER_HALF,
+            MeshLevel::Quarter => 1,
+        };
+        let lat = (self.lat_index / unit * unit) as f64 * QUARTER_LAT_SECONDS / 3600.0;
+        let lon = (self.lon_index / unit * unit) as f64 * QUARTER_LON_SECONDS / 3600.0 + 100.0;
+        (lat, lon)
+    }
+}
+
+/// 分割メッシュの番号（南西1, 南東2, 北西3, 北東4）
+fn quadrant(north: u32, east: u32) -> u32 {
+    1 + north * 2 + east
+}
+
+/// 調査位置に記載された地域メッシュコード
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct DeclaredMeshCode {
+    /// コード1次
+    pub first: Option<String>,
+    /// コード2次
+    pub second: Option<String>,
+    /// コード3次
+    pub third: Option<String>,
+}
+
+impl DeclaredMeshCode {
+    /// 区分ごとの記載値（1次〜3次）
+    fn levels(&self) -> [(MeshLevel, Option<&str>); 3] {
+        [
+            (MeshLevel::First, declared_value(&self.first)),
+            (MeshLevel::Second, declared_value(&self.second)),
+            (MeshLevel::Third, declared_value(&self.third)),
+        ]
+    }
+
+    /// 記載値がメッシュコードと一致しない区分
+    fn mismatches(&self, mesh: &MeshCode) -> Vec<MeshLevel> {
+        self.levels()
+            .into_iter()
+            .filter(|(level, declared)| declared.is_some_and(|d| d != part(mesh, *level)))
+            .map(|(level, _)| level)
+            .collect()
+    }
+}
+
+/// 空白を除いた記載値（空の場合はNone）
+fn declared_value(value: &Option<String>) -> Option<&str> {
+    value.as_deref().map(str::trim).filter(|s| !s.is_empty())
+}
+
+/// 区分ごとのコード（調査位置の記載と同じく、上位の区分を含まない）
+fn part(mesh: &MeshCode, level: MeshLevel) -> String {
+    match level {
+        MeshLevel::First => mesh.first(),
+        MeshLevel::Second => mesh.second(),
+        MeshLevel::Third => mesh.third(),
+        MeshLevel::Half | MeshLevel::Quarter => mesh.code(level),
+    }
+}
+
+/// 地域メッシュコードの照合結果
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct MeshCodeCheck {
+    /// 調査位置の記載値
+    pub declared: DeclaredMeshCode,
+    /// JGD2011の座標から求めた3次メッシュコード（8桁）
+    pub computed: String,
+    /// 記載値と一致しない区分
+    pub mismatched: Vec<MeshLevel>,
+    /// 記載のない区分
+    pub missing: Vec<MeshLevel>,
+    /// 測地系変換前の座標から求めた3次メッシュコード（入力がJGD2011の場合はNone）
+    pub computed_input_datum: Option<String>,
+    /// 記載値が測地系変換前の座標から求めたコードと一致するか
+    pub matches_input_datum: bool,
+}
+
+impl MeshCodeCheck {
+    /// 記載値がすべて座標と一致するか
+    pub fn is_consistent(&self) -> bool {
+        self.mismatched.is_empty() && self.missing.is_empty()
+    }
+}
+
+/// 調査位置の地域メッシュコードを取得するためのトレイト
+pub trait SurveyMeshCode: GeoCoordinate + SurveyDate {
+    /// 調査位置の `コード1次`〜`コード3次`
+    fn declared_mesh_code(&self) -> DeclaredMeshCode;
+
+    /// 記載された地域メッシュコードを座標と照合する
+    ///
+    /// 座標は調査開始日に応じてJGD2011に変換してからメッシュコードを求める。
+    /// 旧測地系の座標からコードを求めた文書を見分けられるよう、変換前の座標から
+    /// 求めたコードとの一致も返す。
+    ///
+    /// # Example
+    /// ```ignore
+    /// let check = boring.check_mesh_code(&transformer)?;
+    /// if !check.is_consistent() {
+    ///     println!("記載 {:?} / 座標 {} ({:?})", check.declared, check.computed, check.mismatched);
+    /// }
+    /// ```
+    fn check_mesh_code(&self, transformer: &JgdTransformer) -> Result<MeshCodeCheck, TransformError>
+    where
+        Self: Sized,
+    {
+        let result = transformer.transform_document(self)?;
+        let computed = MeshCode::from_lat_lng(result.latitude, result.longitude)
+            .ok_or_else(|| TransformError::OutOfCoverage("地域メッシュの範囲外です".to_string()))?;
+
+        let declared = self.declared_mesh_code();
+        let mismatched = declared.mismatches(&computed);
+        let missing = declared
+            .levels()
+            .into_iter()
+            .filter(|(_, value)| value.is_none())
+            .map(|(level, _)| level)
+            .collect();
+
+        // 変換前の座標（JGD2011でない場合のみ）
+        let input = self
+            .geo_location()
+            .to_decimal()
+            .and_then(|(lng, lat)| MeshCode::from_lat_lng(lat, lng))
+            .filter(|input| *input != computed);
+        let matches_input_datum = match &input {
+            Some(input) => declared.mismatches(input).is_empty(),
+            None => mismatched.is_empty(),
+        };
+
+        Ok(MeshCodeCheck {
+            computed: computed.code(MeshLevel::Third),
+            computed_input_datum: input.map(|m| m.code(MeshLevel::Third)),
+            declared,
+            mismatched,
+            missing,
+            matches_input_datum,
+        })
+    }
+}
+
+impl SurveyMeshCode for crate::boring_structs_400::Boring400 {
+    fn declared_mesh_code(&self) -> DeclaredMeshCode {
+        let p = &self.title.survey_position;
+        DeclaredMeshCode {
+            first: p.code1.clone(),
+            second: p.code2.clone(),
+            third: p.code3.clone(),
+        }
+    }
+}
+
+impl SurveyMeshCode for crate::boring_structs_300::Boring300 {
+    fn declared_mesh_code(&self) -> DeclaredMeshCode {
+        let p = &self.title.survey_position;
+        DeclaredMeshCode {
+            first: p.code1.clone(),
+            second: p.code2.clone(),
+            third: p.code3.clone(),
+        }
+    }
+}
+
+impl SurveyMeshCode for crate::boring_structs_210::Boring210 {
+    fn declared_mesh_code(&self) -> DeclaredMeshCode {
+        let p = &self.title.survey_position;
+        DeclaredMeshCode {
+            first: p.code1.clone(),
+            second: p.code2.clone(),
+            third: p.code3.clone(),
+        }
+    }
+}
+
+impl SurveyMeshCode for crate::boring_structs_200::Boring200 {
+    fn declared_mesh_code(&self) -> DeclaredMeshCode {
+        let p = &self.title.survey_position;
+        DeclaredMeshCode {
+            first: p.code1.clone(),
+            second: p.code2.clone(),
+            third: p.code3.clone(),
+        }
+    }
+}
+
+impl SurveyMeshCode for crate::boring_structs_110::Boring110 {
+    fn declared_mesh_code(&self) -> DeclaredMeshCode {
+        let p = &self.title.survey_position;
+        DeclaredMeshCode {
+            first: p.code1.clone(),
+            second: p.code2.clone(),
+            third: p.code3.clone(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_mesh_code_levels() {
+        // 東京駅 (35.6812, 139.7671)
+        let mesh = MeshCode::from_lat_lng(35.6812, 139.7671).unwrap();
+        assert_eq!(mesh.first(), "5339");
+        assert_eq!(mesh.second(), "46");
+        assert_eq!(mesh.third(), "11");
+        assert_eq!(mesh.code(MeshLevel::Third), "53394611");
+        assert_eq!(mesh.code(MeshLevel::Half), "533946113");
+        assert_eq!(mesh.code(MeshLevel::Quarter), "5339461132");
+
+        // 南西端
+        let (lat, lon) = mesh.south_west(MeshLevel::Third);
+        assert!((lat - (35.0 + 40.0 / 60.0 + 30.0 / 3600.0)).abs() < 1e-12);
+        assert!((lon - (139.0 + 45.0 / 60.0 + 45.0 / 3600.0)).abs() < 1e-12);
+
+        // 境界上の点は北・東側に含める (北緯35度40分30秒)
+        let boundary = MeshCode::from_lat_lng(35.675, 139.76).unwrap();
+        assert_eq!(boundary.third(), "10");
+        assert!(MeshCode::from_lat_lng(35.0, 99.9).is_none());
+    }
+
+    #[test]
+    fn test_declared_mismatches() {
+        let mesh = MeshCode::from_lat_lng(35.6812, 139.7671).unwrap();
+        let declared = DeclaredMeshCode {
+            first: Some("5339".to_string()),
+            second: Some(" 46 ".to_string()),
+            third: Some("12".to_string()),
+        };
+        assert_eq!(declared.mismatches(&mesh), vec![MeshLevel::Third]);
+        assert!(DeclaredMeshCode::default().mismatches(&mesh).is_empty());
+    }
+}
@@ -0,0 +1,640 @@
+//! パラメータキャッシュモジュール
+//!
+//! TKY2JGD/PatchJGD系パラメータファイルのパース結果をコンパクトなバイナリ形式で
+//! 保存・読み込みする。大きなTKY2JGD.parのパースを起動のたびに行わずに済む。
+//!
+//! パラメータ表はメッシュコード順に並べた固定長レコードで保存し、読み込み時には
+//! 展開しない。変換ではバイト列上を二分探索してパラメータを取り出すため、
+//! 読み込みはヘッダの確認だけで終わる。[`ParamCache::from_bytes`] は所有権を持つ
+//! バイト列を受け取り、`include_bytes!` で埋め込んだキャッシュ（WASM・組み込み向け）や
+//! メモリマップしたファイルをコピーせずに使える。
+//!
+//! # バイナリ形式
+//! すべてリトルエンディアン。
+//! ```text
+//! magic      b"JGDCACHE"
+//! version    u32
+//! tky        文字列(ファイル名) + パラメータ表(水平)
+//! count      u32 (パッチファイル数)
+//! patch      文字列(ファイル名) + 種別 u8 + 地震発生日 (有無 u8, 年 i32, 月 u32, 日 u32)
+//!            + パラメータ表(水平補正は水平、標高補正・ジオイドは標高)
+//! 文字列     長さ u32 + UTF-8
+//! パラメータ表 件数 u32 + レコード × 件数 (メッシュコード昇順)
+//!   水平     メッシュコード u32, 緯度 f64, 経度 f64
+//!   標高     メッシュコード u32, 標高 f64
+//! ```
+
+use jgdtrans::{Format, ParData, Parameter};
+use std::collections::HashMap;
+use std::fs;
+use std::path::Path;
+use std::sync::Arc;
+
+use crate::transform::{
+    EarthquakeCatalogue, JgdTransformer, ParamFile, StepKind, SurveyStartDate, TransformError,
+    file_name_of, parse_param_file, read_param_file,
+};
+
+/// キャッシュファイルの識別子
+const MAGIC: &[u8; 8] = b"JGDCACHE";
+
+/// キャッシュ形式のバージョン
+const VERSION: u32 = 2;
+
+/// 複数の変換器で共有するキャッシュのバイト列
+type SharedBytes = Arc<dyn AsRef<[u8]> + Send + Sync>;
+
+/// メモリ上のPatchJGD系パラメータファイル
+#[derive(Debug, Clone, Copy)]
+pub struct PatchSource<'a> {
+    /// ファイル名（地震カタログの照合に使用）
+    pub file_name: &'a str,
+    /// ファイルの内容
+    pub content: &'a str,
+    /// 標高補正ファイルか
+    pub elevation: bool,
+}
+
+impl<'a> PatchSource<'a> {
+    /// 水平補正ファイル (PatchJGD形式)
+    pub fn horizontal(file_name: &'a str, content: &'a str) -> Self {
+        Self { file_name, content, elevation: false }
+    }
+
+    /// 標高補正ファイル (PatchJGD_H/HyokoRev形式)
+    pub fn elevation(file_name: &'a str, content: &'a str) -> Self {
+        Self { file_name, content, elevation: true }
+    }
+}
+
+/// メッシュコードごとのパラメータ表
+pub(crate) enum ParamTable {
+    /// パラメータファイルをパースした表
+    Parsed(HashMap<u32, Parameter>),
+    /// キャッシュのバイト列上のレコード
+    Records(Records),
+}
+
+impl ParamTable {
+    /// メッシュコードのパラメータ
+    pub(crate) fn get(&self, code: u32) -> Option<Parameter> {
+        match self {
+            Self::Parsed(parameters) => parameters.get(&code).cloned(),
+            Self::Records(records) => records.get(code),
+        }
+    }
+
+    /// メッシュコードのパラメータがあるか
+    pub(crate) fn contains(&self, code: u32) -> bool {
+        match self {
+            Self::Parsed(parameters) => parameters.contains_key(&code),
+            Self::Records(records) => records.position(code).is_some(),
+        }
+    }
+
+    /// すべてのパラメータ (メッシュコード順)
+    pub(crate) fn entries(&self) -> Vec<(u32, Parameter)> {
+        match self {
+            Self::Parsed(parameters) => {
+                let mut entries: Vec<(u32, Parameter)> =
+                    parameters.iter().map(|(code, p)| (*code, p.clone())).collect();
+                entries.sort_by_key(|(code, _)| *code);
+                entries
+            }
+            Self::Records(records) => (0..records.count).map(|i| records.entry(i)).collect(),
+        }
+    }
+}
+
+/// キャッシュのバイト列上の、メッシュコード順に並んだ固定長レコード
+pub(crate) struct Records {
+    /// キャッシュ全体のバイト列
+    bytes: SharedBytes,
+    /// 先頭レコードの位置
+    start: usize,
+    /// 件数
+    count: usize,
+    /// レコードの内容
+    layout: Layout,
+}
+
+impl Records {
+    /// `i` 番目のレコード
+    fn record(&self, i: usize) -> &[u8] {
+        let width = self.layout.width();
+        let start = self.start + i * width;
+        &(*self.bytes).as_ref()[start..start + width]
+    }
+
+    /// `i` 番目のメッシュコードとパラメータ
+    fn entry(&self, i: usize) -> (u32, Parameter) {
+        let record = self.record(i);
+        (read_u32(record), self.layout.read(&record[4..]))
+    }
+
+    /// メッシュコードのレコードの位置（二分探索）
+    fn position(&self, code: u32) -> Option<usize> {
+        let (mut low, mut high) = (0, self.count);
+        while low < high {
+            let mid = low + (high - low) / 2;
+            match read_u32(self.record(mid)).cmp(&code) {
+                std::cmp::Ordering::Less => low = mid + 1,
+                std::cmp::Ordering::Greater => high = mid,
+                std::cmp::Ordering::Equal => return Some(mid),
+            }
+        }
+        None
+    }
+
+    fn get(&self, code: u32) -> Option<Parameter> {
+        self.position(code).map(|i| self.entry(i).1)
+    }
+}
+
+/// レコードの内容
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Layout {
+    /// 緯度・経度 (TKY2JGD、PatchJGD)
+    Horizontal,
+    /// 標高 (PatchJGD_H、HyokoRev)
+    Altitude,
+}
+
+impl Layout {
+    /// 変換ステップの種別に対応するレコードの内容
+    fn of(kind: StepKind) -> Self {
+        match kind {
+            StepKind::PatchElevation | StepKind::Geoid => Self::Altitude,
+            StepKind::Tky2Jgd | StepKind::PatchHorizontal | StepKind::SemiDynamic | StepKind::Wgs84 => {
+                Self::Horizontal
+            }
+        }
+    }
+
+    /// レコードのバイト数（メッシュコードを含む）
+    fn width(self) -> usize {
+        match self {
+            Self::Horizontal => 4 + 8 * 2,
+            Self::Altitude => 4 + 8,
+        }
+    }
+
+    /// メッシュコードに続く値を読む
+    fn read(self, values: &[u8]) -> Parameter {
+        let f64_at = |i: usize| f64::from_le_bytes(values[i * 8..(i + 1) * 8].try_into().unwrap());
+        match self {
+            Self::Horizontal => Parameter::new(f64_at(0), f64_at(1), 0.0),
+            Self::Altitude => Parameter::new(0.0, 0.0, f64_at(0)),
+        }
+    }
+
+    /// メッシュコードに続く値を書く
+    fn write(self, buf: &mut Vec<u8>, parameter: &Parameter) {
+        match self {
+            Self::Horizontal => {
+                buf.extend_from_slice(&parameter.latitude.to_le_bytes());
+                buf.extend_from_slice(&parameter.longitude.to_le_bytes());
+            }
+            Self::Altitude => buf.extend_from_slice(&parameter.altitude.to_le_bytes()),
+        }
+    }
+}
+
+/// パース済みのパラメータ一式
+///
+/// # Example
+/// ```ignore
+/// let cache = ParamCache::from_dir("params/TKY2JGD.par", "params/patch_files")?;
+/// cache.write("params/jgd.cache")?;
+///
+/// let transformer = JgdTransformer::from_cache(ParamCache::load("params/jgd.cache")?)?;
+///
+/// // メモリマップしたファイルから読み込む（memmap2クレートを使う場合）
+/// let map = unsafe { memmap2::Mmap::map(&File::open("params/jgd.cache")?)? };
+/// let transformer = JgdTransformer::from_cache(ParamCache::from_bytes(map)?)?;
+/// ```
+pub struct ParamCache {
+    /// TKY2JGDパラメータファイル名
+    pub(crate) tky_file_name: String,
+    /// TKY2JGDのパラメータ
+    pub(crate) tky_parameters: ParamTable,
+    /// PatchJGD系パラメータファイル (ファイル名順)
+    pub(crate) param_files: Vec<ParamFile>,
+}
+
+impl ParamCache {
+    /// パラメータファイルを読み込む
+    ///
+    /// 地震カタログは [`EarthquakeCatalogue::for_patch_dir`] で取得する。
+    pub fn from_dir<P: AsRef<Path>>(tky_path: P, patch_dir: P) -> Result<Self, TransformError> {
+        let catalogue = EarthquakeCatalogue::for_patch_dir(&patch_dir)?;
+        Self::from_dir_with_catalogue(tky_path, patch_dir, &catalogue)
+    }
+
+    /// 地震カタログを指定してパラメータファイルを読み込む
+    pub fn from_dir_with_catalogue<P: AsRef<Path>>(
+        tky_path: P,
+        patch_dir: P,
+        catalogue: &EarthquakeCatalogue,
+    ) -> Result<Self, TransformError> {
+        let mut cache = Self::load_tky(tky_path)?;
+        cache.param_files = JgdTransformer::load_patch_files(patch_dir, catalogue)?;
+        Ok(cache)
+    }
+
+    /// メモリ上のパラメータファイルの内容をパース
+    pub fn from_sources(
+        tky_file_name: &str,
+        tky_content: &str,
+        patches: &[PatchSource<'_>],
+        catalogue: &EarthquakeCatalogue,
+    ) -> Result<Self, TransformError> {
+        let mut param_files = patches
+            .iter()
+            .map(|p| parse_param_file(p.file_name, p.content, p.elevation, catalogue))
+            .collect::<Result<Vec<_>, _>>()?;
+        param_files.sort_by(|a, b| a.file_name.cmp(&b.file_name));
+
+        Ok(Self {
+            tky_file_name: tky_file_name.to_string(),
+            tky_parameters: parse_tky(tky_content)?,
+            param_files,
+        })
+    }
+
+    /// TKY2JGDパラメータファイルのみを読み込む
+    pub(crate) fn load_tky<P: AsRef<Path>>(tky_path: P) -> Result<Self, TransformError> {
+        let content = read_param_file(&tky_path)?;
+        Ok(Self {
+            tky_file_name: file_name_of(tky_path.as_ref()),
+            tky_parameters: parse_tky(&content)?,
+            param_files: Vec::new(),
+        })
+    }
+
+    /// 含まれるパラメータファイル名 (TKY2JGD、PatchJGD系の順)
+    pub fn file_names(&self) -> impl Iterator<Item = &str> {
+        std::iter::once(self.tky_file_name.as_str())
+            .chain(self.param_files.iter().map(|f| f.file_name.as_str()))
+    }
+
+    /// キャッシュファイルを書き出す
+    pub fn write<P: AsRef<Path>>(&self, path: P) -> Result<(), TransformError> {
+        fs::write(path, self.to_bytes())?;
+        Ok(())
+    }
+
+    /// キャッシュファイルを読み込む
+    ///
+    /// ファイルをメモリに読み込むだけで、パラメータ表は展開しない。
+    /// ファイルをメモリマップする場合は [`ParamCache::from_bytes`] にマップを渡す。
+    pub fn load<P: AsRef<Path>>(path: P) -> Result<Self, TransformError> {
+        Self::from_bytes(fs::read(path)?)
+    }
+
+    /// バイナリ形式に変換
+    ///
+    /// パラメータはメッシュコード順に並べるため、同じ内容からは同じバイト列になる。
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut buf = Vec::new();
+        buf.extend_from_slice(MAGIC);
+        put_u32(&mut buf, VERSION);
+
+        put_str(&mut buf, &self.tky_file_name);
+        put_table(&mut buf, &self.tky_parameters, Layout::of(StepKind::Tky2Jgd));
+
+        put_u32(&mut buf, self.param_files.len() as u32);
+        for file in &self.param_files {
+            put_str(&mut buf, &file.file_name);
+            buf.push(kind_code(file.kind));
+            let date = file.earthquake_date;
+            buf.push(date.is_some() as u8);
+            let date = date.unwrap_or(SurveyStartDate::new(0, 0, 0));
+            buf.extend_from_slice(&date.year.to_le_bytes());
+            put_u32(&mut buf, date.month);
+            put_u32(&mut buf, date.day);
+            put_table(&mut buf, &file.parameters, Layout::of(file.kind));
+        }
+        buf
+    }
+
+    /// バイナリ形式から読み込む
+    ///
+    /// バイト列はコピーせずに保持し、パラメータは変換のたびにバイト列から二分探索で取り出す。
+    /// `Vec<u8>`、`include_bytes!` の `&'static [u8]`、メモリマップなどを渡せる。
+    ///
+    /// # Errors
+    /// 識別子・バージョンが一致しない、データが途中で切れている、またはレコードが
+    /// メッシュコード順に並んでいない（重複を含む）場合は `TransformError::Parse`
+    pub fn from_bytes<B>(bytes: B) -> Result<Self, TransformError>
+    where
+        B: AsRef<[u8]> + Send + Sync + 'static,
+    {
+        let shared: SharedBytes = Arc::new(bytes);
+        let mut reader = Reader { shared: &shared, pos: 0 };
+        if reader.take(MAGIC.len())? != MAGIC {
+            return Err(TransformError::Parse("not a parameter cache".to_string()));
+        }
+        let version = reader.u32()?;
+        if version != VERSION {
+            return Err(TransformError::Parse(format!(
+                "unsupported parameter cache version: {}",
+                version
+            )));
+        }
+
+        let tky_file_name = reader.string()?;
+        let tky_parameters = reader.table(Layout::of(StepKind::Tky2Jgd))?;
+
+        let count = reader.u32()?;
+        let mut param_files = Vec::new();
+        for _ in 0..count {
+            let file_name = reader.string()?;
+            let kind = kind_from_code(reader.u8()?)?;
+            let has_date = reader.u8()? != 0;
+            let date = SurveyStartDate::new(reader.i32()?, reader.u32()?, reader.u32()?);
+            param_files.push(ParamFile {
+                file_name,
+                kind,
+                earthquake_date: has_date.then_some(date),
+                parameters: reader.table(Layout::of(kind))?,
+            });
+        }
+
+        if reader.pos != reader.bytes().len() {
+            return Err(TransformError::Parse("trailing data in parameter cache".to_string()));
+        }
+
+        Ok(Self {
+            tky_file_name,
+            tky_parameters,
+            param_files,
+        })
+    }
+}
+
+/// TKY2JGDパラメータファイルの内容をパース
+fn parse_tky(content: &str) -> Result<ParamTable, TransformError> {
+    ParData::from_str(content, Format::TKY2JGD)
+        .map(|data| ParamTable::Parsed(data.parameter))
+        .map_err(|e| TransformError::Parse(format!("TKY2JGD parse error: {}", e)))
+}
+
+/// パッチファイル種別のコード
+fn kind_code(kind: StepKind) -> u8 {
+    match kind {
+        StepKind::PatchHorizontal => 1,
+        StepKind::PatchElevation => 2,
+        StepKind::Geoid => 3,
+        // パッチファイルには現れない
+        StepKind::Tky2Jgd | StepKind::SemiDynamic | StepKind::Wgs84 => 0,
+    }
+}
+
+/// コードからパッチファイル種別を取得
+fn kind_from_code(code: u8) -> Result<StepKind, TransformError> {
+    match code {
+        1 => Ok(StepKind::PatchHorizontal),
+        2 => Ok(StepKind::PatchElevation),
+        3 => Ok(StepKind::Geoid),
+        _ => Err(TransformError::Parse(format!("invalid patch kind in cache: {}", code))),
+    }
+}
+
+fn put_u32(buf: &mut Vec<u8>, value: u32) {
+    buf.extend_from_slice(&value.to_le_bytes());
+}
+
+fn put_str(buf: &mut Vec<u8>, value: &str) {
+    put_u32(buf, value.len() as u32);
+    buf.extend_from_slice(value.as_bytes());
+}
+
+fn put_table(buf: &mut Vec<u8>, table: &ParamTable, layout: Layout) {
+    let entries = table.entries();
+    put_u32(buf, entries.len() as u32);
+    for (code, parameter) in &entries {
+        put_u32(buf, *code);
+        layout.write(buf, parameter);
+    }
+}
+
+fn read_u32(bytes: &[u8]) -> u32 {
+    u32::from_le_bytes(bytes[..4].try_into().unwrap())
+}
+
+/// バイト列の読み取り位置
+struct Reader<'a> {
+    shared: &'a SharedBytes,
+    pos: usize,
+}
+
+impl<'a> Reader<'a> {
+    fn bytes(&self) -> &'a [u8] {
+        (**self.shared).as_ref()
+    }
+
+    fn take(&mut self, len: usize) -> Result<&'a [u8], TransformError> {
+        let bytes = self.bytes();
+        let end = self
+            .pos
+            .checked_add(len)
+            .filter(|end| *end <= bytes.len())
+            .ok_or_else(|| TransformError::Parse("parameter cache is truncated".to_string()))?;
+        let slice = &bytes[self.pos..end];
+        self.pos = end;
+        Ok(slice)
+    }
+
+    fn array<const N: usize>(&mut self) -> Result<[u8; N], TransformError> {
+        let mut array = [0; N];
+        array.copy_from_slice(self.take(N)?);
+        Ok(array)
+    }
+
+    fn u8(&mut self) -> Result<u8, TransformError> {
+        Ok(self.take(1)?[0])
+    }
+
+    fn u32(&mut self) -> Result<u32, TransformError> {
+        Ok(u32::from_le_bytes(self.array()?))
+    }
+
+    fn i32(&mut self) -> Result<i32, TransformError> {
+        Ok(i32::from_le_bytes(self.array()?))
+    }
+
+    fn string(&mut self) -> Result<String, TransformError> {
+        let len = self.u32()? as usize;
+        String::from_utf8(self.take(len)?.to_vec())
+            .map_err(|_| TransformError::Parse("invalid file name in parameter cache".to_string()))
+    }
+
+    /// パラメータ表（レコードは展開せず、位置だけを記録する）
+    ///
+    /// 二分探索できるよう、メッシュコードが重複なく昇順に並んでいることを確かめる。
+    fn table(&mut self, layout: Layout) -> Result<ParamTable, TransformError> {
+        let count = self.u32()? as usize;
+        let start = self.pos;
+        let len = count
+            .checked_mul(layout.width())
+            .ok_or_else(|| TransformError::Parse("parameter cache is truncated".to_string()))?;
+        let records = self.take(len)?;
+        let codes: Vec<u32> = records.chunks_exact(layout.width()).map(read_u32).collect();
+        if codes.windows(2).any(|pair| pair[0] >= pair[1]) {
+            return Err(TransformError::Parse(
+                "parameter cache records are not sorted by mesh code".to_string(),
+            ));
+        }
+        Ok(ParamTable::Records(Records {
+            bytes: Arc::clone(self.shared),
+            start,
+            count,
+            layout,
+        }))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// ヘッダ行と固定幅のパラメータ行からなるparファイル
+    fn par(header: usize, lines: &[String]) -> String {
+        let mut content = "header\n".repeat(header);
+        for line in lines {
+            content.push_str(line);
+            content.push('\n');
+        }
+        content
+    }
+
+    fn sample_cache() -> ParamCache {
+        let tky = par(2, &[
+            format!("53394611 {:>9} {:>9}", "11.64926", "-11.65470"),
+            format!("53394612 {:>9} {:>9}", "11.64804", "-11.65513"),
+        ]);
+        let patch = par(16, &[format!("53394611 {:>9} {:>9}", "-0.00350", "0.05090")]);
+        let geoid = par(16, &[format!("53394611    {:>9}", "0.05100")]);
+        let patches = [
+            PatchSource::horizontal("touhokutaiheiyouoki2011.par", &patch),
+            PatchSource::elevation("hyokorev2014_geoid2011_h.par", &geoid),
+        ];
+        ParamCache::from_sources("TKY2JGD.par", &tky, &patches, &EarthquakeCatalogue::builtin())
+            .unwrap()
+    }
+
+    #[test]
+    fn test_round_trip() {
+        let cache = sample_cache();
+        assert_eq!(
+            cache.file_names().collect::<Vec<_>>(),
+            ["TKY2JGD.par", "hyokorev2014_geoid2011_h.par", "touhokutaiheiyouoki2011.par"]
+        );
+
+        let bytes = cache.to_bytes();
+        // ヘッダ12 + TKY2JGD (15 + 4 + 20×2) + 件数4 + ジオイド (32 + 14 + 4 + 12) + 水平補正 (31 + 14 + 4 + 20)
+        assert_eq!(bytes.len(), 206);
+        let loaded = ParamCache::from_bytes(bytes.clone()).unwrap();
+        assert_eq!(loaded.to_bytes(), bytes);
+        assert!(matches!(loaded.tky_parameters, ParamTable::Records(_)));
+        assert_eq!(loaded.tky_parameters.entries().len(), 2);
+        assert_eq!(
+            loaded.tky_parameters.get(53394611),
+            Some(Parameter::new(11.64926, -11.6547, 0.0))
+        );
+        assert_eq!(loaded.tky_parameters.get(53394613), None);
+        assert!(!loaded.tky_parameters.contains(53394610));
+
+        let geoid = &loaded.param_files[0];
+        assert_eq!((geoid.kind, geoid.earthquake_date), (StepKind::Geoid, None));
+        assert_eq!(geoid.parameters.get(53394611), Some(Parameter::new(0.0, 0.0, 0.051)));
+        let patch = &loaded.param_files[1];
+        assert_eq!(patch.kind, StepKind::PatchHorizontal);
+        assert_eq!(patch.earthquake_date, Some(SurveyStartDate::new(2011, 3, 11)));
+        assert_eq!(patch.parameters.get(53394611), Some(Parameter::new(-0.0035, 0.0509, 0.0)));
+    }
+
+    #[test]
+    fn test_cache_transforms_like_sources() {
+        // 東京駅付近の5×5メッシュ
+        let mut lines = Vec::new();
+        for i in -2..=2 {
+            for j in -2..=2 {
+                let point = jgdtrans::Point::new_unchecked(35.68 + i as f64 / 120.0, 139.77 + j as f64 / 80.0, 0.0);
+                let code = point.try_to_meshcode(&jgdtrans::mesh::MeshUnit::One).unwrap();
+                let (lat, lon) = (11.6 + 0.05 * i as f64, -11.6 + 0.03 * j as f64);
+                lines.push(format!("{} {:>9.5} {:>9.5}", code, lat, lon));
+            }
+        }
+        let tky = par(2, &lines);
+        let catalogue = EarthquakeCatalogue::builtin();
+        let parsed = JgdTransformer::from_sources("TKY2JGD.par", &tky, &[], &catalogue).unwrap();
+        let bytes = ParamCache::from_sources("TKY2JGD.par", &tky, &[], &catalogue).unwrap().to_bytes();
+        let cached = JgdTransformer::from_cache(ParamCache::from_bytes(bytes).unwrap()).unwrap();
+
+        let forward = |tf: &JgdTransformer| {
+            let r = tf.transform_on(35.68, 139.77, 0.0, "00", None).unwrap();
+            (r.latitude, r.longitude)
+        };
+        assert_eq!(forward(&cached), forward(&parsed));
+        let inverse = |tf: &JgdTransformer| {
+            let r = tf.inverse_on(35.683, 139.767, 0.0, "00", None).unwrap();
+            (r.latitude, r.longitude)
+        };
+        assert_eq!(inverse(&cached), inverse(&parsed));
+    }
+
+    #[test]
+    fn test_invalid_cache() {
+        let bytes = sample_cache().to_bytes();
+        assert!(ParamCache::from_bytes(b"NOTCACHE").is_err());
+        assert!(ParamCache::from_bytes(bytes[..bytes.len() - 1].to_vec()).is_err());
+
+        let mut wrong_version = bytes.clone();
+        wrong_version[8] = 99;
+        assert!(ParamCache::from_bytes(wrong_version).is_err());
+
+        // TKY2JGDのレコード (ヘッダ12 + ファイル名15 + 件数4 の後、20バイトずつ) の順序が崩れている
+        let first = 31..35;
+        let second = 51..55;
+        let mut unsorted = bytes.clone();
+        unsorted[first.clone()].copy_from_slice(&bytes[second.clone()]);
+        unsorted[second.clone()].copy_from_slice(&bytes[first.clone()]);
+        assert!(matches!(ParamCache::from_bytes(unsorted), Err(TransformError::Parse(_))));
+        let mut duplicated = bytes.clone();
+        duplicated[second].copy_from_slice(&bytes[first]);
+        assert!(matches!(ParamCache::from_bytes(duplicated), Err(TransformError::Parse(_))));
+
+        // カタログにない地震補正ファイル
+        let patches = [PatchSource::horizontal("noto2024.par", "")];
+        assert!(matches!(
+            ParamCache::from_sources("TKY2JGD.par", "", &patches, &EarthquakeCatalogue::builtin()),
+            Err(TransformError::UnknownPatchFile(_))
+        ));
+    }
+
+    /// キャッシュの読み込みはパラメータ表を展開せず、バイト列上のレコードを二分探索する
+    #[test]
+    fn test_load_keeps_records() {
+        let lines: Vec<String> = (0..1_000u32)
+            .map(|i| format!("{:08} {:>9} {:>9}", 30000000 + i * 3, "11.64926", "-11.65470"))
+            .collect();
+        let tky = par(2, &lines);
+        let cache = ParamCache::from_sources("TKY2JGD.par", &tky, &[], &EarthquakeCatalogue::builtin())
+            .unwrap();
+        assert!(matches!(cache.tky_parameters, ParamTable::Parsed(_)));
+
+        let loaded = ParamCache::from_bytes(cache.to_bytes()).unwrap();
+        assert!(matches!(loaded.tky_parameters, ParamTable::Records(_)));
+
+        // 先頭・末尾・途中のコードと、レコードの間のコードを引く
+        for code in (29_999_999..30_003_001).step_by(7) {
+            assert_eq!(loaded.tky_parameters.get(code), cache.tky_parameters.get(code), "{}", code);
+        }
+        for code in [30000000, 30000003, 30002997] {
+            assert!(loaded.tky_parameters.contains(code));
+        }
+        assert!(!loaded.tky_parameters.contains(30000001));
+    }
+}
@@ -111,3 +111,10 @@ pub fn extract_dtd_version(xml: &str) -> Result<String, ParseError> {
         "DTD_version was not found. Plese check your XML file.",
     )))
 }
+
+/// docs/dtd のサンプルXMLを読み込む（テスト用）
+#[cfg(test)]
+pub(crate) fn sample<T: Parse<T>>(name: &str) -> T {
+    let path = std::path::Path::new(env!("CARGO_MANIFEST_DIR")).join("../docs/dtd").join(name);
+    T::parse_from_str(&crate::transform::read_param_file(path).unwrap()).unwrap()
+}
@@ -0,0 +1,557 @@
+//! 透水試験の透水係数を正規化するモジュール
+//!
+//! 透水試験_透水係数は `1.2E-05`・`1.2×10-5`・`1.2×10⁻⁵` など表記が揺れており、
+//! 単位もv3.00以前はcm/s、v4.00ではm/sで記録されている。
+//! 本モジュールはこれらをm/sの数値に正規化し、元の単位をどの程度確からしく
+//! 判断できたかをあわせて返す。透水性の区分（地盤工学会基準）と、
+//! 透水試験_試験方法の記述による試験方法の判定も行う。透水試験_試験コードは
+//! 同梱のDTD（docs/dtd）にコード表がないため解釈せず、値だけを保持する。
+//!
+//! 単位が明記されていない値は、試験区間の土質の典型的な透水係数の範囲
+//! （[`typical_range`]）と両方の単位での読みを比べて単位を判断する。
+//! 両方の単位で範囲に入る値は判別できないものとして [`UnitCertainty::Ambiguous`] とする。
+
+use serde::Serialize;
+
+use crate::layer::{SoilClass, SoilLayer, SoilLayers, layer_at};
+use crate::types::parse_f64;
+
+/// 土質が分からない場合に透水係数として妥当とみなす範囲 (m/s)
+const PLAUSIBLE_RANGE: (f64, f64) = (1.0e-11, 1.0);
+
+/// 土質の典型的な透水係数の範囲 (m/s)
+///
+/// 地盤工学会の透水性の区分と対応する土質を基に、隣り合う区分へ1桁ずつ広げたもの。
+/// 土質が分からない場合は [`PLAUSIBLE_RANGE`] を返す。
+pub fn typical_range(soil: Option<SoilClass>) -> (f64, f64) {
+    match soil {
+        Some(SoilClass::Clay) => (1.0e-11, 1.0e-7),
+        Some(SoilClass::Organic) => (1.0e-9, 1.0e-5),
+        Some(SoilClass::Sand) => (1.0e-7, 1.0e-3),
+        Some(SoilClass::Gravel) => (1.0e-5, 1.0),
+        Some(SoilClass::Rock) => (1.0e-10, 1.0e-3),
+        None => PLAUSIBLE_RANGE,
+    }
+}
+
+/// 透水係数の単位
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+pub enum PermeabilityUnit {
+    /// cm/s（v3.00以前）
+    CentimetrePerSecond,
+    /// m/s（v4.00）
+    MetrePerSecond,
+}
+
+impl PermeabilityUnit {
+    /// m/sへの換算係数
+    pub fn to_metre_per_second(&self) -> f64 {
+        match self {
+            PermeabilityUnit::CentimetrePerSecond => 0.01,
+            PermeabilityUnit::MetrePerSecond => 1.0,
+        }
+    }
+
+    fn other(&self) -> Self {
+        match self {
+            PermeabilityUnit::CentimetrePerSecond => PermeabilityUnit::MetrePerSecond,
+            PermeabilityUnit::MetrePerSecond => PermeabilityUnit::CentimetrePerSecond,
+        }
+    }
+}
+
+/// 元の単位の確からしさ
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+pub enum UnitCertainty {
+    /// 値に単位が明記されていた
+    Explicit,
+    /// 規定単位でのみ妥当な範囲に入るため、データ形式のバージョンの規定単位とみなした
+    VersionDefault,
+    /// 規定単位では妥当な範囲を外れるため、もう一方の単位と推定した
+    Inferred,
+    /// どちらの単位でも妥当な範囲に入り判別できない（規定単位で換算）
+    Ambiguous,
+    /// どちらの単位でも妥当な範囲を外れる（規定単位で換算）
+    Implausible,
+}
+
+/// 正規化した透水係数
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+pub struct PermeabilityCoefficient {
+    /// 透水係数 (m/s)
+    pub value: f64,
+    /// 記録されていた数値
+    pub original_value: f64,
+    /// 元の単位
+    pub original_unit: PermeabilityUnit,
+    /// 元の単位の確からしさ
+    pub certainty: UnitCertainty,
+}
+
+impl PermeabilityCoefficient {
+    /// 透水係数の文字列を正規化（土質が分からない場合）
+    ///
+    /// # Arguments
+    /// * `s` - 透水係数の文字列（例: "9.30E-04", "1.2×10-5", "3.0×10⁻⁶ cm/s"）
+    /// * `default_unit` - 単位が明記されていない場合に用いる規定単位
+    pub fn parse(s: &str, default_unit: PermeabilityUnit) -> Option<Self> {
+        Self::parse_for_soil(s, default_unit, None)
+    }
+
+    /// 試験区間の土質の典型的な範囲と比べて透水係数の文字列を正規化
+    pub fn parse_for_soil(s: &str, default_unit: PermeabilityUnit, soil: Option<SoilClass>) -> Option<Self> {
+        let (original_value, explicit_unit) = parse_coefficient(s)?;
+        if original_value <= 0.0 {
+            return None;
+        }
+
+        let (original_unit, certainty) = match explicit_unit {
+            Some(unit) => (unit, UnitCertainty::Explicit),
+            None => {
+                let (min, max) = typical_range(soil);
+                let plausible = |unit: PermeabilityUnit| {
+                    let value = original_value * unit.to_metre_per_second();
+                    min <= value && value <= max
+                };
+                match (plausible(default_unit), plausible(default_unit.other())) {
+                    (true, true) => (default_unit, UnitCertainty::Ambiguous),
+                    (true, false) => (default_unit, UnitCertainty::VersionDefault),
+                    (false, true) => (default_unit.other(), UnitCertainty::Inferred),
+                    (false, false) => (default_unit, UnitCertainty::Implausible),
+                }
+            }
+        };
+
+        Some(Self {
+            value: original_value * original_unit.to_metre_per_second(),
+            original_value,
+            original_unit,
+            certainty,
+        })
+    }
+
+    /// 透水性の区分
+    pub fn class(&self) -> PermeabilityClass {
+        PermeabilityClass::from_value(self.value)
+    }
+}
+
+/// 全角英数字・各種マイナス記号・上付き数字を半角に揃え、空白を除く
+fn normalize_notation(s: &str) -> String {
+    s.chars()
+        .filter_map(|c| {
+            let c = match c {
+                '\u{FF01}'..='\u{FF5E}' => char::from_u32(c as u32 - 0xFEE0)?,
+                '−' | '‐' | '‒' | '–' | '—' | '⁻' => '-',
+                '⁺' => '+',
+                '⁰' => '0',
+                '¹' => '1',
+                '²' => '2',
+                '³' => '3',
+                '⁴'..='⁹' => char::from_u32(c as u32 - '⁴' as u32 + '4' as u32)?,
+                _ => c,
+            };
+            (!c.is_whitespace()).then_some(c)
+        })
+        .collect()
+}
+
+/// 数値と明記された単位を取り出す
+fn parse_coefficient(s: &str) -> Option<(f64, Option<PermeabilityUnit>)> {
+    let normalized = normalize_notation(s).to_ascii_lowercase();
+
+    let mut unit = None;
+    let mut body = normalized.as_str();
+    for (suffix, u) in [
+        ("cm/sec", PermeabilityUnit::CentimetrePerSecond),
+        ("cm/s", PermeabilityUnit::CentimetrePerSecond),
+        ("m/sec", PermeabilityUnit::MetrePerSecond),
+        ("m/s", PermeabilityUnit::MetrePerSecond),
+    ] {
+        if let Some(stripped) = body.strip_suffix(suffix) {
+            unit = Some(u);
+            body = stripped;
+            break;
+        }
+    }
+
+    if let Some(value) = parse_f64(Some(body)) {
+        return Some((value, unit));
+    }
+
+    // 仮数部×10^指数部 の表記
+    let (mantissa, power) = match body.find(['×', 'x', '*']) {
+        Some(pos) => {
+            let sep_len = body[pos..].chars().next()?.len_utf8();
+            (parse_f64(Some(&body[..pos]))?, &body[pos + sep_len..])
+        }
+        None => (1.0, body),
+    };
+    let exponent = power.strip_prefix("10")?;
+    let exponent = exponent
+        .strip_prefix("**")
+        .or_else(|| exponent.strip_prefix('^'))
+        .unwrap_or(exponent);
+    let exponent = exponent.strip_prefix('(').and_then(|e| e.strip_suffix(')')).unwrap_or(exponent);
+    let exponent: i32 = exponent.parse().ok()?;
+
+    let value = mantissa * 10f64.powi(exponent);
+    value.is_finite().then_some((value, unit))
+}
+
+/// 透水性の区分（地盤工学会基準）
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize)]
+pub enum PermeabilityClass {
+    /// 実質上不透水（k < 1e-9 m/s）
+    PracticallyImpervious,
+    /// 非常に低い（1e-9 ≦ k < 1e-7 m/s）
+    VeryLow,
+    /// 低い（1e-7 ≦ k < 1e-5 m/s）
+    Low,
+    /// 中位（1e-5 ≦ k < 1e-3 m/s）
+    Medium,
+    /// 高い（k ≧ 1e-3 m/s）
+    High,
+}
+
+impl PermeabilityClass {
+    /// 透水係数 (m/s) から区分を判定
+    pub fn from_value(value: f64) -> Self {
+        match value {
+            v if v < 1.0e-9 => PermeabilityClass::PracticallyImpervious,
+            v if v < 1.0e-7 => PermeabilityClass::VeryLow,
+            v if v < 1.0e-5 => PermeabilityClass::Low,
+            v if v < 1.0e-3 => PermeabilityClass::Medium,
+            _ => PermeabilityClass::High,
+        }
+    }
+
+    /// 区分名
+    pub fn label(&self) -> &'static str {
+        match self {
+            PermeabilityClass::PracticallyImpervious => "実質上不透水",
+            PermeabilityClass::VeryLow => "非常に低い",
+            PermeabilityClass::Low => "低い",
+            PermeabilityClass::Medium => "中位",
+            PermeabilityClass::High => "高い",
+        }
+    }
+}
+
+/// 透水試験の試験方法
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub enum PermeabilityTestMethod {
+    /// 単孔式透水試験（非定常法）
+    SingleHoleUnsteady,
+    /// 単孔式透水試験（定常法）
+    SingleHoleSteady,
+    /// 揚水試験
+    Pumping,
+    /// その他（試験方法の記述）
+    Other(String),
+    /// 記述がなく判定できない（試験コード）
+    Unknown(String),
+}
+
+impl PermeabilityTestMethod {
+    /// 透水試験_試験方法の記述から判定
+    ///
+    /// 記述に「揚水」「非定常」「定常」が含まれればそれに従い、それ以外の記述は
+    /// [`PermeabilityTestMethod::Other`] に保持する。記述がない場合は試験コードを
+    /// [`PermeabilityTestMethod::Unknown`] に保持する（コード表がないため解釈しない）。
+    pub fn from_description(description: Option<&str>, code: Option<&str>) -> Self {
+        let Some(description) = description.map(str::trim).filter(|d| !d.is_empty()) else {
+            return PermeabilityTestMethod::Unknown(code.unwrap_or_default().trim().to_string());
+        };
+        if description.contains("揚水") {
+            PermeabilityTestMethod::Pumping
+        } else if description.contains("非定常") {
+            PermeabilityTestMethod::SingleHoleUnsteady
+        } else if description.contains("定常") {
+            PermeabilityTestMethod::SingleHoleSteady
+        } else {
+            PermeabilityTestMethod::Other(description.to_string())
+        }
+    }
+}
+
+/// 透水試験1件分のデータ（バージョン共通）
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct PermeabilityTest {
+    /// 上端深度 (m)
+    pub top_depth: Option<f64>,
+    /// 下端深度 (m)
+    pub bottom_depth: Option<f64>,
+    /// 試験コード
+    pub test_code: Option<String>,
+    /// 試験方法
+    pub method: PermeabilityTestMethod,
+    /// 記録されていた透水係数の文字列
+    pub raw_coefficient: Option<String>,
+    /// 試験区間中央の土質（単位の判断に使用）
+    pub soil: Option<SoilClass>,
+    /// 正規化した透水係数（パースできない場合はNone）
+    pub coefficient: Option<PermeabilityCoefficient>,
+}
+
+impl PermeabilityTest {
+    /// 透水性の区分
+    pub fn class(&self) -> Option<PermeabilityClass> {
+        self.coefficient.as_ref().map(|c| c.class())
+    }
+}
+
+/// 透水試験データを取得するためのトレイト
+pub trait PermeabilityTests: SoilLayers {
+    /// 透水係数の規定単位
+    fn permeability_unit(&self) -> PermeabilityUnit {
+        PermeabilityUnit::CentimetrePerSecond
+    }
+
+    /// 透水試験を透水係数を正規化して取得
+    fn permeability_tests(&self) -> Vec<PermeabilityTest>;
+}
+
+fn permeability_test(
+    top: Option<&str>,
+    bottom: Option<&str>,
+    code: Option<&str>,
+    description: Option<&str>,
+    coefficient: Option<&str>,
+    default_unit: PermeabilityUnit,
+    layers: &[SoilLayer],
+) -> PermeabilityTest {
+    let (top_depth, bottom_depth) = (parse_f64(top), parse_f64(bottom));
+    let mid_depth = match (top_depth, bottom_depth) {
+        (Some(top), Some(bottom)) => Some((top + bottom) / 2.0),
+        (top, bottom) => top.or(bottom),
+    };
+    let soil = mid_depth.and_then(|depth| layer_at(layers, depth)).and_then(SoilClass::from_layer);
+    PermeabilityTest {
+        top_depth,
+        bottom_depth,
+        test_code: code.map(|c| c.trim().to_string()),
+        method: PermeabilityTestMethod::from_description(description, code),
+        raw_coefficient: coefficient.map(str::to_string),
+        soil,
+        coefficient: coefficient.and_then(|c| PermeabilityCoefficient::parse_for_soil(c, default_unit, soil)),
+    }
+}
+
+fn tests_210(
+    tests: &[crate::boring_structs_210::PermeabilityTest210],
+    default_unit: PermeabilityUnit,
+    layers: &[SoilLayer],
+) -> Vec<PermeabilityTest> {
+    tests
+        .iter()
+        .map(|t| {
+            permeability_test(
+                t.start_depth.as_deref(),
+                t.end_depth.as_deref(),
+                t.test_code.as_deref(),
+                t.test_method.as_deref(),
+                t.permeability_coefficient.as_deref(),
+                default_unit,
+                layers,
+            )
+        })
+        .collect()
+}
+
+impl PermeabilityTests for crate::boring_structs_400::Boring400 {
+    fn permeability_unit(&self) -> PermeabilityUnit {
+        PermeabilityUnit::MetrePerSecond
+    }
+
+    fn permeability_tests(&self) -> Vec<PermeabilityTest> {
+        tests_210(&self.core.permeability_tests, self.permeability_unit(), &self.soil_layers())
+    }
+}
+
+impl PermeabilityTests for crate::boring_structs_300::Boring300 {
+    fn permeability_tests(&self) -> Vec<PermeabilityTest> {
+        tests_210(&self.core.permeability_tests, self.permeability_unit(), &self.soil_layers())
+    }
+}
+
+impl PermeabilityTests for crate::boring_structs_210::Boring210 {
+    fn permeability_tests(&self) -> Vec<PermeabilityTest> {
+        tests_210(&self.core.permeability_tests, self.permeability_unit(), &self.soil_layers())
+    }
+}
+
+impl PermeabilityTests for crate::boring_structs_200::Boring200 {
+    fn permeability_tests(&self) -> Vec<PermeabilityTest> {
+        tests_210(&self.core.permeability_tests, self.permeability_unit(), &self.soil_layers())
+    }
+}
+
+impl PermeabilityTests for crate::boring_structs_110::Boring110 {
+    fn permeability_tests(&self) -> Vec<PermeabilityTest> {
+        let unit = self.permeability_unit();
+        let layers = self.soil_layers();
+        self.core
+            .borehole_drilled_well_test
+            .iter()
+            .map(|t| {
+                permeability_test(
+                    t.start_depth.as_deref(),
+                    t.end_depth.as_deref(),
+                    t.test_code.as_deref(),
+                    t.test_method.as_deref(),
+                    t.permeability.as_deref(),
+                    unit,
+                    &layers,
+                )
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn approx(a: f64, b: f64) -> bool {
+        (a - b).abs() <= b.abs() * 1e-9
+    }
+
+    #[test]
+    fn test_parse_notations() {
+        let cases = [
+            "1.2E-05",
+            "1.2e-5",
+            "1.2×10-5",
+            "1.2×10^-5",
+            "1.2 x 10^(-5)",
+            "1.2*10**-5",
+            "1.2×10⁻⁵",
+            "１．２×１０－５",
+            "1.2×10−5",
+        ];
+        for case in cases {
+            let (value, unit) = parse_coefficient(case).unwrap_or_else(|| panic!("{}", case));
+            assert!(approx(value, 1.2e-5), "{} -> {}", case, value);
+            assert_eq!(unit, None);
+        }
+        assert!(approx(parse_coefficient("10-4").unwrap().0, 1.0e-4));
+        assert!(parse_coefficient("不明").is_none());
+        assert!(parse_coefficient("").is_none());
+    }
+
+    #[test]
+    fn test_explicit_unit() {
+        let k = PermeabilityCoefficient::parse("3.0×10⁻⁴ cm/s", PermeabilityUnit::MetrePerSecond)
+            .unwrap();
+        assert_eq!(k.original_unit, PermeabilityUnit::CentimetrePerSecond);
+        assert_eq!(k.certainty, UnitCertainty::Explicit);
+        assert!(approx(k.value, 3.0e-6));
+
+        let k = PermeabilityCoefficient::parse("2.5E-05m/sec", PermeabilityUnit::CentimetrePerSecond)
+            .unwrap();
+        assert_eq!(k.original_unit, PermeabilityUnit::MetrePerSecond);
+        assert!(approx(k.value, 2.5e-5));
+    }
+
+    #[test]
+    fn test_version_default_and_inferred_unit() {
+        // v2.10の 9.30E-04 cm/s と v4.00の 9.30E-06 m/s は同じ値
+        let k210 =
+            PermeabilityCoefficient::parse("9.30E-04", PermeabilityUnit::CentimetrePerSecond).unwrap();
+        let k400 = PermeabilityCoefficient::parse("9.30E-06", PermeabilityUnit::MetrePerSecond).unwrap();
+        assert!(approx(k210.value, k400.value));
+        // 土質が分からない場合はどちらの単位でも妥当なため判別できない
+        assert_eq!(k210.certainty, UnitCertainty::Ambiguous);
+        // 粘土の 5.0E-06 はcm/sでのみ妥当
+        let k = PermeabilityCoefficient::parse_for_soil(
+            "5.0E-06",
+            PermeabilityUnit::CentimetrePerSecond,
+            Some(SoilClass::Clay),
+        )
+        .unwrap();
+        assert_eq!(k.certainty, UnitCertainty::VersionDefault);
+
+        // m/sとしては大きすぎる値はcm/sと推定
+        let k = PermeabilityCoefficient::parse("5.0", PermeabilityUnit::MetrePerSecond).unwrap();
+        assert_eq!(k.original_unit, PermeabilityUnit::CentimetrePerSecond);
+        assert_eq!(k.certainty, UnitCertainty::Inferred);
+
+        let k = PermeabilityCoefficient::parse("1.0E-20", PermeabilityUnit::MetrePerSecond).unwrap();
+        assert_eq!(k.certainty, UnitCertainty::Implausible);
+
+        assert!(PermeabilityCoefficient::parse("0", PermeabilityUnit::MetrePerSecond).is_none());
+    }
+
+    #[test]
+    fn test_metre_per_second_in_centimetre_file() {
+        // v3.00以前の砂層の試験に m/s の値 2.0E-06 が入力されている場合、
+        // cm/sでは2.0E-08 m/sとなり砂の典型的な範囲を外れる
+        let layers = crate::layer::layers_from_bottom_depths(vec![
+            (Some("3.0"), Some("粘土"), None),
+            (Some("8.0"), Some("細砂"), None),
+        ]);
+        let test = permeability_test(
+            Some("4.0"),
+            Some("5.0"),
+            Some("01"),
+            None,
+            Some("2.0E-06"),
+            PermeabilityUnit::CentimetrePerSecond,
+            &layers,
+        );
+        assert_eq!(test.soil, Some(SoilClass::Sand));
+        let k = test.coefficient.unwrap();
+        assert_eq!(k.original_unit, PermeabilityUnit::MetrePerSecond);
+        assert_eq!(k.certainty, UnitCertainty::Inferred);
+        assert!(approx(k.value, 2.0e-6));
+
+        // 粘土層の 2.0E-06 cm/s は規定単位のまま
+        let test = permeability_test(
+            Some("1.0"),
+            Some("2.0"),
+            Some("01"),
+            None,
+            Some("2.0E-06"),
+            PermeabilityUnit::CentimetrePerSecond,
+            &layers,
+        );
+        assert_eq!(test.coefficient.unwrap().certainty, UnitCertainty::VersionDefault);
+
+        // 砂層の 5.0E-04 はどちらの単位でも砂の範囲に入る
+        let k = PermeabilityCoefficient::parse_for_soil(
+            "5.0E-04",
+            PermeabilityUnit::CentimetrePerSecond,
+            Some(SoilClass::Sand),
+        )
+        .unwrap();
+        assert_eq!((k.original_unit, k.certainty), (PermeabilityUnit::CentimetrePerSecond, UnitCertainty::Ambiguous));
+    }
+
+    #[test]
+    fn test_class_and_method() {
+        assert_eq!(PermeabilityClass::from_value(1.0e-10), PermeabilityClass::PracticallyImpervious);
+        assert_eq!(PermeabilityClass::from_value(1.0e-8), PermeabilityClass::VeryLow);
+        assert_eq!(PermeabilityClass::from_value(9.3e-6), PermeabilityClass::Low);
+        assert_eq!(PermeabilityClass::from_value(1.0e-5), PermeabilityClass::Medium);
+        assert_eq!(PermeabilityClass::from_value(5.3e-3).label(), "高い");
+
+        assert_eq!(
+            PermeabilityTestMethod::from_description(Some("単孔式透水試験（非定常法）"), Some("02")),
+            PermeabilityTestMethod::SingleHoleUnsteady
+        );
+        assert_eq!(
+            PermeabilityTestMethod::from_description(Some("定常法"), None),
+            PermeabilityTestMethod::SingleHoleSteady
+        );
+        assert_eq!(
+            PermeabilityTestMethod::from_description(Some(" 現場透水試験 "), Some("99")),
+            PermeabilityTestMethod::Other("現場透水試験".to_string())
+        );
+        // 記述がなければコードは解釈しない
+        assert_eq!(
+            PermeabilityTestMethod::from_description(Some(""), Some(" 02 ")),
+            PermeabilityTestMethod::Unknown("02".to_string())
+        );
+    }
+}
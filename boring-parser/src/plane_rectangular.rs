@@ -0,0 +1,452 @@
+//! 平面直角座標系（I〜XIX系）への投影モジュール
+//!
+//! JGD2011の緯度経度を平面直角座標系（EPSG:6669〜6687）のX・Y座標に変換する。
+//! 国土地理院の計算式（河瀬 2011、ガウス・クリューゲル図法のn^5展開）を
+//! そのまま実装しているため、projは使用しない。
+//!
+//! X座標は北向き、Y座標は東向きを正とする（測量座標系）。
+
+use serde::Serialize;
+
+/// GRS80 長半径 (m)
+const GRS80_A: f64 = 6_378_137.0;
+/// GRS80 扁平率
+const GRS80_F: f64 = 1.0 / 298.257_222_101;
+/// 座標系原点の縮尺係数
+const SCALE_FACTOR: f64 = 0.9999;
+
+/// 各系の原点（緯度, 経度）を度で表したもの。インデックス0がI系
+const ZONE_ORIGINS: [(f64, f64); 19] = [
+    (33.0, 129.0 + 30.0 / 60.0),
+    (33.0, 131.0),
+    (36.0, 132.0 + 10.0 / 60.0),
+    (33.0, 133.0 + 30.0 / 60.0),
+    (36.0, 134.0 + 20.0 / 60.0),
+    (36.0, 136.0),
+    (36.0, 137.0 + 10.0 / 60.0),
+    (36.0, 138.0 + 30.0 / 60.0),
+    (36.0, 139.0 + 50.0 / 60.0),
+    (40.0, 140.0 + 50.0 / 60.0),
+    (44.0, 140.0 + 15.0 / 60.0),
+    (44.0, 142.0 + 15.0 / 60.0),
+    (44.0, 144.0 + 15.0 / 60.0),
+    (26.0, 142.0),
+    (26.0, 127.0 + 30.0 / 60.0),
+    (26.0, 124.0),
+    (26.0, 131.0),
+    (20.0, 136.0),
+    (26.0, 154.0),
+];
+
+/// 都道府県ごとの適用系（複数ある場合は位置で選ぶ）
+const PREFECTURE_ZONES: &[(&str, &[u8])] = &[
+    ("北海道", &[11, 12, 13]),
+    ("青森県", &[10]),
+    ("岩手県", &[10]),
+    ("宮城県", &[10]),
+    ("秋田県", &[10]),
+    ("山形県", &[10]),
+    ("福島県", &[9]),
+    ("茨城県", &[9]),
+    ("栃木県", &[9]),
+    ("群馬県", &[9]),
+    ("埼玉県", &[9]),
+    ("千葉県", &[9]),
+    ("東京都", &[9, 14, 18, 19]),
+    ("神奈川県", &[9]),
+    ("新潟県", &[8]),
+    ("富山県", &[7]),
+    ("石川県", &[7]),
+    ("福井県", &[6]),
+    ("山梨県", &[8]),
+    ("長野県", &[8]),
+    ("岐阜県", &[7]),
+    ("静岡県", &[8]),
+    ("愛知県", &[7]),
+    ("三重県", &[6]),
+    ("滋賀県", &[6]),
+    ("京都府", &[6]),
+    ("大阪府", &[6]),
+    ("兵庫県", &[5]),
+    ("奈良県", &[6]),
+    ("和歌山県", &[6]),
+    ("鳥取県", &[5]),
+    ("島根県", &[3]),
+    ("岡山県", &[5]),
+    ("広島県", &[3]),
+    ("山口県", &[3]),
+    ("徳島県", &[4]),
+    ("香川県", &[4]),
+    ("愛媛県", &[4]),
+    ("高知県", &[4]),
+    ("福岡県", &[2]),
+    ("佐賀県", &[2]),
+    ("長崎県", &[1]),
+    ("熊本県", &[2]),
+    ("大分県", &[2]),
+    ("宮崎県", &[2]),
+    ("鹿児島県", &[1, 2]),
+    ("沖縄県", &[15, 16, 17]),
+];
+
+/// 平面直角座標系の系番号（1〜19）
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize)]
+pub struct PlaneRectangularZone(u8);
+
+impl PlaneRectangularZone {
+    /// 系番号から作成（1〜19以外はNone）
+    pub fn new(number: u8) -> Option<Self> {
+        (1..=19).contains(&number).then_some(Self(number))
+    }
+
+    /// 全19系
+    pub fn all() -> impl Iterator<Item = Self> {
+        (1..=19).map(Self)
+    }
+
+    /// 系番号
+    pub fn number(&self) -> u8 {
+        self.0
+    }
+
+    /// 原点の（緯度, 経度）(度)
+    pub fn origin(&self) -> (f64, f64) {
+        ZONE_ORIGINS[self.0 as usize - 1]
+    }
+
+    /// EPSGコード（JGD2011 平面直角座標系）
+    pub fn epsg_code(&self) -> String {
+        format!("EPSG:{}", 6668 + self.0 as u32)
+    }
+
+    /// ローマ数字表記（例: "IX"）
+    pub fn roman(&self) -> &'static str {
+        const ROMAN: [&str; 19] = [
+            "I", "II", "III", "IV", "V", "VI", "VII", "VIII", "IX", "X", "XI", "XII", "XIII",
+            "XIV", "XV", "XVI", "XVII", "XVIII", "XIX",
+        ];
+        ROMAN[self.0 as usize - 1]
+    }
+
+    /// 位置から系を自動選択
+    ///
+    /// 都道府県がわかればその都道府県の適用系から、わからなければ全19系から、
+    /// 原点が最も近い系を選ぶ。系の境界は市町村・島単位で定められているため、
+    /// 都道府県なしの選択は目安である。
+    pub fn select(lat: f64, lon: f64, prefecture: Option<&str>) -> Self {
+        let candidates: Vec<Self> = prefecture
+            .and_then(zones_for_prefecture)
+            .map(|zones| zones.iter().map(|&n| Self(n)).collect())
+            .unwrap_or_else(|| Self::all().collect());
+
+        let distance = |zone: &Self| {
+            let (lat0, lon0) = zone.origin();
+            let dx = (lon - lon0) * lat.to_radians().cos();
+            let dy = lat - lat0;
+            dx * dx + dy * dy
+        };
+        candidates
+            .into_iter()
+            .min_by(|a, b| distance(a).total_cmp(&distance(b)))
+            .unwrap_or(Self(9))
+    }
+}
+
+/// 都道府県名の適用系
+pub fn zones_for_prefecture(prefecture: &str) -> Option<&'static [u8]> {
+    PREFECTURE_ZONES
+        .iter()
+        .find(|(name, _)| *name == prefecture)
+        .map(|(_, zones)| *zones)
+}
+
+/// 住所の先頭から都道府県名を取り出す
+///
+/// 「東京都」「京都府」「北海道」のほか、「県」を省略した表記（例: "茨城つくば市"）にも対応する。
+pub fn prefecture_from_address(address: &str) -> Option<&'static str> {
+    let address = address.trim_start_matches(|c: char| c.is_whitespace() || c == '　');
+    PREFECTURE_ZONES
+        .iter()
+        .map(|(name, _)| *name)
+        .find(|name| address.starts_with(name))
+        .or_else(|| {
+            PREFECTURE_ZONES.iter().map(|(name, _)| *name).find(|name| {
+                let short = name.trim_end_matches(['都', '府', '県']);
+                // 「京都市」を「京都府」と誤認しないよう、省略表記は県のみ
+                name.ends_with('県') && address.starts_with(short)
+            })
+        })
+}
+
+/// 平面直角座標
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+pub struct PlaneRectangular {
+    /// 系
+    pub zone: PlaneRectangularZone,
+    /// X座標（北向き正, m）
+    pub x: f64,
+    /// Y座標（東向き正, m）
+    pub y: f64,
+}
+
+/// ガウス・クリューゲル図法の係数
+struct Coefficients {
+    n: f64,
+    a_bar: f64,
+    big_a: [f64; 6],
+    alpha: [f64; 5],
+    beta: [f64; 5],
+    delta: [f64; 6],
+}
+
+impl Coefficients {
+    fn grs80() -> Self {
+        let n = GRS80_F / (2.0 - GRS80_F);
+        let (n2, n3, n4, n5, n6) = (n * n, n.powi(3), n.powi(4), n.powi(5), n.powi(6));
+
+        let big_a = [
+            1.0 + n2 / 4.0 + n4 / 64.0,
+            -1.5 * (n - n3 / 8.0 - n5 / 64.0),
+            15.0 / 16.0 * (n2 - n4 / 4.0),
+            -35.0 / 48.0 * (n3 - 5.0 / 16.0 * n5),
+            315.0 / 512.0 * n4,
+            -693.0 / 1280.0 * n5,
+        ];
+        let alpha = [
+            n / 2.0 - 2.0 / 3.0 * n2 + 5.0 / 16.0 * n3 + 41.0 / 180.0 * n4 - 127.0 / 288.0 * n5,
+            13.0 / 48.0 * n2 - 3.0 / 5.0 * n3 + 557.0 / 1440.0 * n4 + 281.0 / 630.0 * n5,
+            61.0 / 240.0 * n3 - 103.0 / 140.0 * n4 + 15061.0 / 26880.0 * n5,
+            49561.0 / 161280.0 * n4 - 179.0 / 168.0 * n5,
+            34729.0 / 80640.0 * n5,
+        ];
+        let beta = [
+            n / 2.0 - 2.0 / 3.0 * n2 + 37.0 / 96.0 * n3 - n4 / 360.0 - 81.0 / 512.0 * n5,
+            n2 / 48.0 + n3 / 15.0 - 437.0 / 1440.0 * n4 + 46.0 / 105.0 * n5,
+            17.0 / 480.0 * n3 - 37.0 / 840.0 * n4 - 209.0 / 4480.0 * n5,
+            4397.0 / 161280.0 * n4 - 11.0 / 504.0 * n5,
+            4583.0 / 161280.0 * n5,
+        ];
+        let delta = [
+            2.0 * n - 2.0 / 3.0 * n2 - 2.0 * n3 + 116.0 / 45.0 * n4 + 26.0 / 45.0 * n5
+                - 2854.0 / 675.0 * n6,
+            7.0 / 3.0 * n2 - 8.0 / 5.0 * n3 - 227.0 / 45.0 * n4 + 2704.0 / 315.0 * n5
+                + 2323.0 / 945.0 * n6,
+            56.0 / 15.0 * n3 - 136.0 / 35.0 * n4 - 1262.0 / 105.0 * n5 + 73814.0 / 2835.0 * n6,
+            4279.0 / 630.0 * n4 - 332.0 / 35.0 * n5 - 399572.0 / 14175.0 * n6,
+            4174.0 / 315.0 * n5 - 144838.0 / 6237.0 * n6,
+            601676.0 / 22275.0 * n6,
+        ];
+
+        Self {
+            n,
+            a_bar: SCALE_FACTOR * GRS80_A / (1.0 + n) * big_a[0],
+            big_a,
+            alpha,
+            beta,
+            delta,
+        }
+    }
+
+    /// 原点緯度までの子午線弧長（縮尺係数込み）
+    fn meridian_arc(&self, phi0: f64) -> f64 {
+        let series: f64 = (1..=5).map(|j| self.big_a[j] * (2.0 * j as f64 * phi0).sin()).sum();
+        SCALE_FACTOR * GRS80_A / (1.0 + self.n) * (self.big_a[0] * phi0 + series)
+    }
+}
+
+impl PlaneRectangular {
+    /// JGD2011の緯度経度（度）を指定した系の平面直角座標に変換
+    pub fn from_geographic(lat: f64, lon: f64, zone: PlaneRectangularZone) -> Self {
+        let c = Coefficients::grs80();
+        let (lat0, lon0) = zone.origin();
+        let phi = lat.to_radians();
+        let dl = (lon - lon0).to_radians();
+
+        let k = 2.0 * c.n.sqrt() / (1.0 + c.n);
+        let t = (phi.sin().atanh() - k * (k * phi.sin()).atanh()).sinh();
+        let t_bar = (1.0 + t * t).sqrt();
+        let xi = t.atan2(dl.cos());
+        let eta = (dl.sin() / t_bar).atanh();
+
+        let mut x = xi;
+        let mut y = eta;
+        for (j, alpha) in c.alpha.iter().enumerate() {
+            let m = 2.0 * (j + 1) as f64;
+            x += alpha * (m * xi).sin() * (m * eta).cosh();
+            y += alpha * (m * xi).cos() * (m * eta).sinh();
+        }
+
+        Self {
+            zone,
+            x: c.a_bar * x - c.meridian_arc(lat0.to_radians()),
+            y: c.a_bar * y,
+        }
+    }
+
+    /// JGD2011の緯度経度（度）に逆変換し、(緯度, 経度) を返す
+    pub fn to_geographic(&self) -> (f64, f64) {
+        let c = Coefficients::grs80();
+        let (lat0, lon0) = self.zone.origin();
+
+        let xi = (self.x + c.meridian_arc(lat0.to_radians())) / c.a_bar;
+        let eta = self.y / c.a_bar;
+
+        let mut xi2 = xi;
+        let mut eta2 = eta;
+        for (j, beta) in c.beta.iter().enumerate() {
+            let m = 2.0 * (j + 1) as f64;
+            xi2 -= beta * (m * xi).sin() * (m * eta).cosh();
+            eta2 -= beta * (m * xi).cos() * (m * eta).sinh();
+        }
+
+        let chi = (xi2.sin() / eta2.cosh()).asin();
+        let phi = chi
+            + c.delta
+                .iter()
+                .enumerate()
+                .map(|(j, delta)| delta * (2.0 * (j + 1) as f64 * chi).sin())
+                .sum::<f64>();
+        let lon = lon0 + eta2.sinh().atan2(xi2.cos()).to_degrees();
+
+        (phi.to_degrees(), lon)
+    }
+}
+
+/// 調査位置住所から都道府県を取得するためのトレイト
+pub trait SurveyPrefecture {
+    /// 調査位置住所
+    fn survey_address(&self) -> Option<&str>;
+
+    /// 調査位置住所から求めた都道府県名
+    fn prefecture(&self) -> Option<&'static str> {
+        prefecture_from_address(self.survey_address()?)
+    }
+}
+
+impl SurveyPrefecture for crate::boring_structs_400::Boring400 {
+    fn survey_address(&self) -> Option<&str> {
+        self.title.survey_position.address.as_deref()
+    }
+}
+
+impl SurveyPrefecture for crate::boring_structs_300::Boring300 {
+    fn survey_address(&self) -> Option<&str> {
+        self.title.survey_position.address.as_deref()
+    }
+}
+
+impl SurveyPrefecture for crate::boring_structs_210::Boring210 {
+    fn survey_address(&self) -> Option<&str> {
+        self.title.survey_position.address.as_deref()
+    }
+}
+
+impl SurveyPrefecture for crate::boring_structs_200::Boring200 {
+    fn survey_address(&self) -> Option<&str> {
+        self.title.survey_position.address.as_deref()
+    }
+}
+
+impl SurveyPrefecture for crate::boring_structs_110::Boring110 {
+    fn survey_address(&self) -> Option<&str> {
+        self.title.survey_position.address.as_deref()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn zone(n: u8) -> PlaneRectangularZone {
+        PlaneRectangularZone::new(n).unwrap()
+    }
+
+    #[test]
+    fn test_origin_maps_to_zero() {
+        for z in PlaneRectangularZone::all() {
+            let (lat0, lon0) = z.origin();
+            let p = PlaneRectangular::from_geographic(lat0, lon0, z);
+            assert!(p.x.abs() < 1e-6 && p.y.abs() < 1e-6, "{:?}", p);
+        }
+    }
+
+    #[test]
+    fn test_central_meridian_arc() {
+        // 中央子午線上ではX = m0 × 子午線弧長（数値積分による値 55476.2738m）
+        let p = PlaneRectangular::from_geographic(36.5, 139.0 + 50.0 / 60.0, zone(9));
+        assert!((p.x - 55476.2738).abs() < 1e-3, "{}", p.x);
+        assert!(p.y.abs() < 1e-6);
+    }
+
+    #[test]
+    fn test_off_meridian_against_reference() {
+        // Snyderの級数式による参照値（中央子午線から0.25°程度ならmm精度で一致する）
+        let p = PlaneRectangular::from_geographic(36.10343, 140.08643, zone(9));
+        assert!((p.x - 11505.0987).abs() < 0.01, "{}", p.x);
+        assert!((p.y - 22788.0371).abs() < 0.01, "{}", p.y);
+
+        let p = PlaneRectangular::from_geographic(35.68, 139.77, zone(9));
+        assert!((p.x - -35500.5337).abs() < 0.01, "{}", p.x);
+        assert!((p.y - -5732.7742).abs() < 0.01, "{}", p.y);
+    }
+
+    #[test]
+    fn test_round_trip() {
+        for (lat, lon, n) in [(35.68, 139.77, 9), (43.06, 141.35, 12), (26.21, 127.68, 15), (33.59, 130.40, 2)] {
+            let p = PlaneRectangular::from_geographic(lat, lon, zone(n));
+            let (lat2, lon2) = p.to_geographic();
+            assert!((lat - lat2).abs() < 1e-9 && (lon - lon2).abs() < 1e-9);
+        }
+    }
+
+    /// PROJのEPSGデータベース(proj.db)との比較（proj機能が有効な場合は常に実行）
+    #[cfg(feature = "proj")]
+    #[test]
+    fn test_agrees_with_proj() {
+        for (lat, lon, n) in [(35.68, 139.77, 9), (43.06, 141.35, 12), (26.21, 127.68, 15), (33.59, 130.40, 2)] {
+            let z = zone(n);
+            let proj = proj::Proj::new_known_crs("EPSG:6668", &z.epsg_code(), None).unwrap();
+            // PROJは (東方向, 北方向) = (Y, X) を返す
+            let (y, x) = proj.convert((lon, lat)).unwrap();
+            let p = PlaneRectangular::from_geographic(lat, lon, z);
+            assert!((p.x - x).abs() < 1e-3 && (p.y - y).abs() < 1e-3, "{:?} / ({}, {})", p, x, y);
+        }
+    }
+
+    #[test]
+    fn test_zone_metadata() {
+        assert_eq!(zone(1).epsg_code(), "EPSG:6669");
+        assert_eq!(zone(19).epsg_code(), "EPSG:6687");
+        assert_eq!(zone(9).roman(), "IX");
+        assert!(PlaneRectangularZone::new(0).is_none());
+        assert!(PlaneRectangularZone::new(20).is_none());
+    }
+
+    #[test]
+    fn test_zone_selection() {
+        // 札幌（北海道: XI/XII/XIII）→ XII
+        assert_eq!(PlaneRectangularZone::select(43.06, 141.35, Some("北海道")).number(), 12);
+        // 函館 → XI
+        assert_eq!(PlaneRectangularZone::select(41.77, 140.73, Some("北海道")).number(), 11);
+        // 那覇 → XV、石垣 → XVI
+        assert_eq!(PlaneRectangularZone::select(26.21, 127.68, Some("沖縄県")).number(), 15);
+        assert_eq!(PlaneRectangularZone::select(24.34, 124.16, Some("沖縄県")).number(), 16);
+        // 東京（本土）→ IX、父島 → XIV
+        assert_eq!(PlaneRectangularZone::select(35.68, 139.77, Some("東京都")).number(), 9);
+        assert_eq!(PlaneRectangularZone::select(27.09, 142.19, Some("東京都")).number(), 14);
+        // 都道府県がわかれば位置より優先（下関は原点がII系に近いが山口県はIII系）
+        assert_eq!(PlaneRectangularZone::select(33.96, 130.94, Some("山口県")).number(), 3);
+        assert_eq!(PlaneRectangularZone::select(33.96, 130.94, None).number(), 2);
+        // 都道府県不明 → 最寄りの原点
+        assert_eq!(PlaneRectangularZone::select(35.68, 139.77, None).number(), 9);
+    }
+
+    #[test]
+    fn test_prefecture_from_address() {
+        assert_eq!(prefecture_from_address("東京都千代田区霞が関"), Some("東京都"));
+        assert_eq!(prefecture_from_address("　京都府京都市"), Some("京都府"));
+        assert_eq!(prefecture_from_address("茨城つくば市北郷"), Some("茨城県"));
+        assert_eq!(prefecture_from_address("京都市左京区"), None);
+        assert_eq!(prefecture_from_address("○○県○○郡○○町字○○"), None);
+    }
+}
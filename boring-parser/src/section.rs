@@ -0,0 +1,917 @@
+//! 断面図モジュール
+//!
+//! 並べたボーリング（または指定した折れ線）を断面線とし、各ボーリングの孔口と孔跡を
+//! 断面線に投影して、土質・岩種区分と地層岩体区分の層、標準貫入試験のN値、孔内水位を
+//! 距離程と標高で配置した断面モデル（[`Section`]）を作る。[`to_svg`] で断面図を描く。
+//!
+//! 断面線は平面直角座標の折れ線とし、距離程は始点から折れ線に沿った距離、
+//! 離れは断面線の進行方向に向かって右側を正とする。始点より手前・終点より先に投影される
+//! ボーリングは、最初・最後の区間を延長して距離程を求める（負や全長超えになる）。
+//! 斜めボーリングは孔跡上の各点を投影するため、断面線に沿った成分だけが距離程に現れる。
+//!
+//! 孔口標高がないボーリングは孔口を0m（[`crate::local_coordinate::HeightDatum::GroundLevel`]）として配置する。
+//! 基準面の異なるボーリングが混ざっているかは [`Section::mixed_datums`] で確認できる。
+//! 標高軸は1つしかないため、混ざっている断面は [`to_svg`] で描かない。
+
+use serde::Serialize;
+use thiserror::Error;
+
+use crate::elevation::{datum_label, CollarElevation, DepthElevations};
+use crate::groundwater::{WaterLevelReading, WaterLevelSeries};
+use crate::layer::{SoilClass, SoilLayer, StratumLayers};
+use crate::plane_rectangular::{PlaneRectangular, PlaneRectangularZone};
+use crate::spt::SptRecord;
+use crate::trajectory::Trajectory;
+use crate::transform::JgdTransformer;
+use crate::types::BoringHeader;
+
+/// 断面作成のエラー
+#[derive(Error, Debug, Clone, PartialEq)]
+pub enum SectionError {
+    #[error("断面線には異なる2点以上が必要です（{0}点）")]
+    TooFewPoints(usize),
+    #[error("描画条件 {0} が不正です（{1}）")]
+    InvalidOption(&'static str, f64),
+    #[error("基準面の異なるボーリングが混ざっています（{0}）")]
+    MixedDatums(String),
+}
+
+/// 断面線（平面直角座標の折れ線）
+///
+/// 異なる2点以上の頂点を持つことを保証するため、[`SectionLine::new`] などでのみ作成できる。
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct SectionLine {
+    /// 系
+    zone: PlaneRectangularZone,
+    /// 頂点（始点から順）
+    vertices: Vec<PlaneRectangular>,
+}
+
+/// 断面線への投影結果
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+pub struct SectionProjection {
+    /// 距離程（始点から断面線に沿った距離、m）
+    pub chainage: f64,
+    /// 断面線からの離れ（進行方向右側が正、m）
+    pub offset: f64,
+}
+
+impl SectionLine {
+    /// 頂点から作成
+    ///
+    /// 最初の頂点の系にそろえ、同じ位置が続く頂点は1つにまとめる。
+    pub fn new(vertices: Vec<PlaneRectangular>) -> Result<Self, SectionError> {
+        let count = vertices.len();
+        let zone = vertices.first().ok_or(SectionError::TooFewPoints(0))?.zone;
+        let mut merged: Vec<PlaneRectangular> = Vec::with_capacity(count);
+        for v in vertices {
+            let v = if v.zone == zone {
+                v
+            } else {
+                let (lat, lng) = v.to_geographic();
+                PlaneRectangular::from_geographic(lat, lng, zone)
+            };
+            if merged.last().is_none_or(|last| (last.x - v.x).hypot(last.y - v.y) > 1e-6) {
+                merged.push(v);
+            }
+        }
+        if merged.len() < 2 {
+            return Err(SectionError::TooFewPoints(merged.len()));
+        }
+        Ok(Self { zone, vertices: merged })
+    }
+
+    /// 緯度経度（JGD2011、度）の折れ線から作成
+    pub fn from_geographic(points: &[(f64, f64)], zone: PlaneRectangularZone) -> Result<Self, SectionError> {
+        Self::new(
+            points
+                .iter()
+                .map(|&(lat, lng)| PlaneRectangular::from_geographic(lat, lng, zone))
+                .collect(),
+        )
+    }
+
+    /// 系
+    pub fn zone(&self) -> PlaneRectangularZone {
+        self.zone
+    }
+
+    /// 頂点（始点から順）
+    pub fn vertices(&self) -> &[PlaneRectangular] {
+        &self.vertices
+    }
+
+    /// 全長 (m)
+    pub fn length(&self) -> f64 {
+        self.vertex_chainages().last().copied().unwrap_or(0.0)
+    }
+
+    /// 各頂点の距離程 (m)
+    pub fn vertex_chainages(&self) -> Vec<f64> {
+        let mut chainage = 0.0;
+        let mut chainages = vec![0.0];
+        for w in self.vertices.windows(2) {
+            chainage += (w[1].x - w[0].x).hypot(w[1].y - w[0].y);
+            chainages.push(chainage);
+        }
+        chainages
+    }
+
+    /// 点を断面線に投影
+    ///
+    /// 最も近い区間に垂線を下ろす。最初・最後の区間は外側に延長する。
+    ///
+    /// # Example
+    /// ```ignore
+    /// let p = line.project(&collar);
+    /// println!("距離程 {:.1}m 離れ {:.1}m", p.chainage, p.offset);
+    /// ```
+    pub fn project(&self, point: &PlaneRectangular) -> SectionProjection {
+        let point = if point.zone == self.zone {
+            *point
+        } else {
+            let (lat, lng) = point.to_geographic();
+            PlaneRectangular::from_geographic(lat, lng, self.zone)
+        };
+        let last = self.vertices.len() - 2;
+        let mut start = 0.0;
+        let mut best: Option<(f64, SectionProjection)> = None;
+
+        for (i, w) in self.vertices.windows(2).enumerate() {
+            // 東・北の成分
+            let (de, dn) = (w[1].y - w[0].y, w[1].x - w[0].x);
+            let (pe, pn) = (point.y - w[0].y, point.x - w[0].x);
+            let length = de.hypot(dn);
+            let mut t = (pe * de + pn * dn) / (length * length);
+            if i > 0 {
+                t = t.max(0.0);
+            }
+            if i < last {
+                t = t.min(1.0);
+            }
+            let distance = (pe - t * de).hypot(pn - t * dn);
+            if best.is_none_or(|(d, _)| distance < d) {
+                best = Some((
+                    distance,
+                    SectionProjection {
+                        chainage: start + t * length,
+                        offset: (dn * pe - de * pn) / length,
+                    },
+                ));
+            }
+            start += length;
+        }
+
+        // 頂点は2つ以上あるため必ず求まる
+        best.map(|(_, p)| p).unwrap_or(SectionProjection { chainage: 0.0, offset: 0.0 })
+    }
+}
+
+/// 断面を作るためのボーリングの記録
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct BoreholeLog {
+    /// ボーリング名
+    pub name: Option<String>,
+    /// 孔口標高
+    pub collar: CollarElevation,
+    /// 孔跡（孔口の平面直角座標がない場合は断面に配置しない）
+    pub trajectory: Trajectory,
+    /// 土質・岩種区分
+    pub soil_layers: Vec<SoilLayer>,
+    /// 地層岩体区分
+    pub stratum_layers: Vec<SoilLayer>,
+    /// 標準貫入試験
+    pub spt: Vec<SptRecord>,
+    /// 孔内水位
+    pub water_levels: WaterLevelSeries,
+}
+
+impl BoreholeLog {
+    /// ボーリングファイルから作成
+    ///
+    /// 孔口の経度緯度を指定した系に投影する。孔口標高がない場合は孔口を0mとする。
+    pub fn from_document<T>(document: &T, transformer: &JgdTransformer, zone: PlaneRectangularZone) -> Self
+    where
+        T: DepthElevations + StratumLayers + BoringHeader,
+    {
+        let collar = document.elevation_profile_or_ground_level().collar;
+        let mut trajectory = document.trajectory_on(transformer, zone);
+        trajectory.collar_elevation = Some(collar.value);
+        Self {
+            name: document.boring_name().map(str::to_string),
+            collar,
+            trajectory,
+            soil_layers: document.soil_layers(),
+            stratum_layers: document.stratum_layers(),
+            spt: document.spt_records(),
+            water_levels: document.water_level_series(),
+        }
+    }
+}
+
+/// 断面上の位置
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+pub struct SectionPoint {
+    /// 距離程 (m)
+    pub chainage: f64,
+    /// 標高 (m)
+    pub elevation: f64,
+}
+
+/// 断面上に配置した層
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct SectionInterval {
+    /// 層
+    pub layer: SoilLayer,
+    /// 上端
+    pub top: SectionPoint,
+    /// 下端
+    pub bottom: SectionPoint,
+}
+
+/// 断面上に配置した標準貫入試験
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+pub struct SectionSpt {
+    /// 標準貫入試験
+    pub record: SptRecord,
+    /// N値（換算）
+    pub n_value: f64,
+    /// 試験区間中央の位置
+    pub point: SectionPoint,
+}
+
+/// 断面上に配置した孔内水位
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct SectionWaterLevel {
+    /// 測定
+    pub reading: WaterLevelReading,
+    /// 水位の位置
+    pub point: SectionPoint,
+}
+
+/// 断面上に配置したボーリング
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct SectionBorehole {
+    /// 入力順の番号
+    pub index: usize,
+    /// ボーリング名
+    pub name: Option<String>,
+    /// 孔口の投影位置
+    pub projection: SectionProjection,
+    /// 孔口標高
+    pub collar: CollarElevation,
+    /// 土質・岩種区分
+    pub soil_layers: Vec<SectionInterval>,
+    /// 地層岩体区分
+    pub stratum_layers: Vec<SectionInterval>,
+    /// 標準貫入試験
+    pub spt: Vec<SectionSpt>,
+    /// 孔内水位（水位のある測定のみ）
+    pub water_levels: Vec<SectionWaterLevel>,
+    /// 安定水位
+    pub stabilised_water_level: Option<SectionWaterLevel>,
+}
+
+impl SectionBorehole {
+    /// 孔口の位置
+    pub fn collar_point(&self) -> SectionPoint {
+        SectionPoint {
+            chainage: self.projection.chainage,
+            elevation: self.collar.value,
+        }
+    }
+}
+
+/// 断面に配置しなかった理由
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+pub enum SkipReason {
+    /// 孔口の平面直角座標がない
+    NoLocation,
+    /// 断面線からの離れが上限を超える（離れ, m）
+    TooFarFromLine(f64),
+}
+
+/// 断面に配置しなかったボーリング
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct SkippedBorehole {
+    /// 入力順の番号
+    pub index: usize,
+    /// ボーリング名
+    pub name: Option<String>,
+    /// 理由
+    pub reason: SkipReason,
+}
+
+/// 断面
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct Section {
+    /// 断面線
+    pub line: SectionLine,
+    /// 距離程順のボーリング
+    pub boreholes: Vec<SectionBorehole>,
+    /// 配置しなかったボーリング
+    pub skipped: Vec<SkippedBorehole>,
+}
+
+impl Section {
+    /// 距離程の範囲 (m)。断面線の全長と配置したボーリングを含む
+    pub fn chainage_range(&self) -> (f64, f64) {
+        self.points()
+            .map(|p| p.chainage)
+            .fold((0.0, self.line.length()), |(min, max), c| (min.min(c), max.max(c)))
+    }
+
+    /// 標高の範囲 (m)。ボーリングがない場合はNone
+    pub fn elevation_range(&self) -> Option<(f64, f64)> {
+        self.points().map(|p| p.elevation).fold(None, |range, e| match range {
+            None => Some((e, e)),
+            Some((min, max)) => Some((e.min(min), e.max(max))),
+        })
+    }
+
+    /// 基準面の異なるボーリングが混ざっているか
+    pub fn mixed_datums(&self) -> bool {
+        self.boreholes.windows(2).any(|w| w[0].collar.datum != w[1].collar.datum)
+    }
+
+    /// 孔口・層・試験・水位のすべての位置
+    fn points(&self) -> impl Iterator<Item = SectionPoint> + '_ {
+        self.boreholes.iter().flat_map(|b| {
+            std::iter::once(b.collar_point())
+                .chain(b.soil_layers.iter().chain(&b.stratum_layers).flat_map(|l| [l.top, l.bottom]))
+                .chain(b.spt.iter().map(|s| s.point))
+                .chain(b.water_levels.iter().map(|w| w.point))
+        })
+    }
+}
+
+/// 断面の作成条件
+#[derive(Debug, Clone, PartialEq)]
+pub struct SectionBuilder {
+    /// 孔口を投影する平面直角座標系
+    pub zone: PlaneRectangularZone,
+    /// 断面線。Noneの場合は位置のわかるボーリングの孔口を入力順に結ぶ
+    pub line: Option<SectionLine>,
+    /// 断面線からの離れの上限 (m)。Noneは制限なし
+    pub max_offset: Option<f64>,
+    /// 安定水位の判定に使う許容値 (m)
+    pub water_level_tolerance: f64,
+}
+
+impl SectionBuilder {
+    /// 孔口を入力順に結ぶ断面
+    pub fn new(zone: PlaneRectangularZone) -> Self {
+        Self {
+            zone,
+            line: None,
+            max_offset: None,
+            water_level_tolerance: 0.1,
+        }
+    }
+
+    /// 指定した断面線に沿った断面
+    pub fn along(line: SectionLine) -> Self {
+        Self {
+            line: Some(line.clone()),
+            ..Self::new(line.zone)
+        }
+    }
+
+    /// ボーリングファイルから断面を作成
+    ///
+    /// # Example
+    /// ```ignore
+    /// let zone = PlaneRectangularZone::new(9).unwrap();
+    /// let section = SectionBuilder::new(zone).build(&borings, &transformer)?;
+    /// std::fs::write("section.svg", to_svg(&section, &SvgOptions::default())?)?;
+    /// ```
+    pub fn build<T>(&self, documents: &[T], transformer: &JgdTransformer) -> Result<Section, SectionError>
+    where
+        T: DepthElevations + StratumLayers + BoringHeader,
+    {
+        let logs: Vec<BoreholeLog> = documents
+            .iter()
+            .map(|d| BoreholeLog::from_document(d, transformer, self.zone))
+            .collect();
+        self.build_logs(&logs)
+    }
+
+    /// ボーリングの記録から断面を作成
+    pub fn build_logs(&self, logs: &[BoreholeLog]) -> Result<Section, SectionError> {
+        let line = match &self.line {
+            Some(line) => line.clone(),
+            None => SectionLine::new(logs.iter().filter_map(|l| l.trajectory.collar).collect())?,
+        };
+
+        let mut boreholes = Vec::new();
+        let mut skipped = Vec::new();
+        for (index, log) in logs.iter().enumerate() {
+            let skip = |reason| SkippedBorehole {
+                index,
+                name: log.name.clone(),
+                reason,
+            };
+            let Some(collar) = log.trajectory.collar else {
+                skipped.push(skip(SkipReason::NoLocation));
+                continue;
+            };
+            let projection = line.project(&collar);
+            if self.max_offset.is_some_and(|max| projection.offset.abs() > max) {
+                skipped.push(skip(SkipReason::TooFarFromLine(projection.offset)));
+                continue;
+            }
+            boreholes.push(self.place(index, log, &line, projection));
+        }
+        boreholes.sort_by(|a, b| a.projection.chainage.total_cmp(&b.projection.chainage));
+
+        Ok(Section { line, boreholes, skipped })
+    }
+
+    /// 1本のボーリングを断面上に配置
+    fn place(&self, index: usize, log: &BoreholeLog, line: &SectionLine, projection: SectionProjection) -> SectionBorehole {
+        let point_at = |depth: f64| {
+            let p = log.trajectory.point_at(depth);
+            SectionPoint {
+                chainage: p.plane.map_or(projection.chainage, |plane| line.project(&plane).chainage),
+                elevation: log.collar.value - p.vertical_depth,
+            }
+        };
+        let intervals = |layers: &[SoilLayer]| {
+            layers
+                .iter()
+                .map(|layer| SectionInterval {
+                    layer: layer.clone(),
+                    top: point_at(layer.top_depth),
+                    bottom: point_at(layer.bottom_depth),
+                })
+                .collect()
+        };
+        let water_level = |reading: &WaterLevelReading| {
+            reading.depth.map(|d| SectionWaterLevel {
+                reading: reading.clone(),
+                point: point_at(d),
+            })
+        };
+
+        SectionBorehole {
+            index,
+            name: log.name.clone(),
+            projection,
+            collar: log.collar.clone(),
+            soil_layers: intervals(&log.soil_layers),
+            stratum_layers: intervals(&log.stratum_layers),
+            spt: log
+                .spt
+                .iter()
+                .map(|r| SectionSpt {
+                    record: *r,
+                    n_value: r.n_value(),
+                    point: point_at(r.mid_depth()),
+                })
+                .collect(),
+            water_levels: log.water_levels.readings.iter().filter_map(water_level).collect(),
+            stabilised_water_level: log
+                .water_levels
+                .stabilised(self.water_level_tolerance)
+                .and_then(|s| water_level(&s.reading)),
+        }
+    }
+}
+
+/// 断面図の描画条件
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct SvgOptions {
+    /// 水平方向の1mあたりのピクセル数
+    pub horizontal_scale: f64,
+    /// 鉛直方向の1mあたりのピクセル数
+    pub vertical_scale: f64,
+    /// 柱状図の幅 (px)
+    pub column_width: f64,
+    /// N値1あたりの長さ (px)
+    pub n_value_scale: f64,
+    /// N値の表示上限
+    pub max_n_value: f64,
+    /// 余白 (px)
+    pub margin: f64,
+}
+
+impl Default for SvgOptions {
+    fn default() -> Self {
+        Self {
+            horizontal_scale: 2.0,
+            vertical_scale: 10.0,
+            column_width: 16.0,
+            n_value_scale: 1.0,
+            max_n_value: 50.0,
+            margin: 60.0,
+        }
+    }
+}
+
+impl SvgOptions {
+    /// 縮尺が正の有限値、幅・余白が0以上の有限値であることを確認
+    pub fn validate(&self) -> Result<(), SectionError> {
+        for (name, value) in [("horizontal_scale", self.horizontal_scale), ("vertical_scale", self.vertical_scale)] {
+            if !(value.is_finite() && value > 0.0) {
+                return Err(SectionError::InvalidOption(name, value));
+            }
+        }
+        for (name, value) in [
+            ("column_width", self.column_width),
+            ("n_value_scale", self.n_value_scale),
+            ("max_n_value", self.max_n_value),
+            ("margin", self.margin),
+        ] {
+            if !(value.is_finite() && value >= 0.0) {
+                return Err(SectionError::InvalidOption(name, value));
+            }
+        }
+        Ok(())
+    }
+}
+
+/// 断面図をSVG文字列に変換
+///
+/// 土質・岩種区分を柱状に、地層岩体区分をその左に細い柱で描き、N値を柱の右に折れ線で、
+/// 安定水位を▽で示す。層の名称は各図形の `<title>` に入れる。
+/// 描画条件が不正な場合（[`SvgOptions::validate`]）と、基準面の異なるボーリングが
+/// 混ざっている場合（[`Section::mixed_datums`]）はエラーを返す。
+pub fn to_svg(section: &Section, options: &SvgOptions) -> Result<String, SectionError> {
+    options.validate()?;
+    if section.mixed_datums() {
+        let mut labels: Vec<String> = Vec::new();
+        for borehole in &section.boreholes {
+            let label = datum_label(&borehole.collar.datum);
+            if !labels.contains(&label) {
+                labels.push(label);
+            }
+        }
+        return Err(SectionError::MixedDatums(labels.join(", ")));
+    }
+    let (min_chainage, max_chainage) = section.chainage_range();
+    let (min_elevation, max_elevation) = section
+        .elevation_range()
+        .map_or((-10.0, 0.0), |(min, max)| ((min - 1.0).floor(), (max + 1.0).ceil()));
+    let column = options.column_width;
+    let n_width = options.max_n_value * options.n_value_scale;
+    let width = (max_chainage - min_chainage) * options.horizontal_scale + 2.0 * options.margin + column + n_width;
+    let height = (max_elevation - min_elevation) * options.vertical_scale + 2.0 * options.margin;
+    let x = |chainage: f64| options.margin + column + (chainage - min_chainage) * options.horizontal_scale;
+    let y = |elevation: f64| options.margin + (max_elevation - elevation) * options.vertical_scale;
+
+    let mut svg = format!(
+        "<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"{w:.0}\" height=\"{h:.0}\" viewBox=\"0 0 {w:.0} {h:.0}\" font-family=\"sans-serif\" font-size=\"10\">\n",
+        w = width,
+        h = height
+    );
+    svg.push_str(&format!("<rect width=\"{width:.0}\" height=\"{height:.0}\" fill=\"white\"/>\n"));
+
+    // 標高の目盛
+    let datum = section
+        .boreholes
+        .first()
+        .map_or_else(|| "T.P.".to_string(), |b| datum_label(&b.collar.datum));
+    let step = tick_step(options.vertical_scale, 30.0);
+    let mut elevation = (min_elevation / step).ceil() * step;
+    while elevation <= max_elevation {
+        let ty = y(elevation);
+        svg.push_str(&format!(
+            "<line x1=\"{:.1}\" y1=\"{ty:.1}\" x2=\"{:.1}\" y2=\"{ty:.1}\" stroke=\"#e0e0e0\"/>\n",
+            options.margin,
+            width - options.margin
+        ));
+        svg.push_str(&format!(
+            "<text x=\"{:.1}\" y=\"{:.1}\" text-anchor=\"end\">{elevation:.0}</text>\n",
+            options.margin - 4.0,
+            ty + 3.0
+        ));
+        elevation += step;
+    }
+    svg.push_str(&format!(
+        "<text x=\"{:.1}\" y=\"{:.1}\" text-anchor=\"end\">{} (m)</text>\n",
+        options.margin - 4.0,
+        options.margin - 8.0,
+        xml_escape(&datum)
+    ));
+
+    // 距離程の目盛
+    let bottom = height - options.margin;
+    let step = tick_step(options.horizontal_scale, 60.0);
+    let mut chainage = (min_chainage / step).ceil() * step;
+    while chainage <= max_chainage {
+        let tx = x(chainage);
+        svg.push_str(&format!(
+            "<line x1=\"{tx:.1}\" y1=\"{bottom:.1}\" x2=\"{tx:.1}\" y2=\"{:.1}\" stroke=\"black\"/>\n",
+            bottom + 4.0
+        ));
+        svg.push_str(&format!(
+            "<text x=\"{tx:.1}\" y=\"{:.1}\" text-anchor=\"middle\">{chainage:.0}</text>\n",
+            bottom + 16.0
+        ));
+        chainage += step;
+    }
+    svg.push_str(&format!(
+        "<line x1=\"{:.1}\" y1=\"{bottom:.1}\" x2=\"{:.1}\" y2=\"{bottom:.1}\" stroke=\"black\"/>\n",
+        x(min_chainage),
+        x(max_chainage)
+    ));
+    svg.push_str(&format!(
+        "<text x=\"{:.1}\" y=\"{:.1}\" text-anchor=\"end\">距離程 (m)</text>\n",
+        x(max_chainage),
+        bottom + 32.0
+    ));
+
+    // 断面線の折れ点
+    let vertices = section.line.vertex_chainages();
+    for &c in &vertices[1..vertices.len() - 1] {
+        svg.push_str(&format!(
+            "<line x1=\"{:.1}\" y1=\"{:.1}\" x2=\"{:.1}\" y2=\"{bottom:.1}\" stroke=\"#999999\" stroke-dasharray=\"4 4\"/>\n",
+            x(c),
+            options.margin,
+            x(c)
+        ));
+    }
+
+    // 地表線
+    if section.boreholes.len() > 1 {
+        let points: Vec<String> = section
+            .boreholes
+            .iter()
+            .map(|b| format!("{:.1},{:.1}", x(b.projection.chainage), y(b.collar.value)))
+            .collect();
+        svg.push_str(&format!(
+            "<polyline points=\"{}\" fill=\"none\" stroke=\"#8b5a2b\" stroke-width=\"1.5\"/>\n",
+            points.join(" ")
+        ));
+    }
+
+    for borehole in &section.boreholes {
+        svg.push_str("<g>\n");
+        let collar = borehole.collar_point();
+        if let Some(name) = &borehole.name {
+            svg.push_str(&format!(
+                "<text x=\"{:.1}\" y=\"{:.1}\" text-anchor=\"middle\">{}</text>\n",
+                x(collar.chainage),
+                y(collar.elevation) - 6.0,
+                xml_escape(name)
+            ));
+        }
+
+        for l in &borehole.stratum_layers {
+            let fill = l.layer.name.as_deref().map_or("#eeeeee", stratum_fill);
+            svg.push_str(&column_polygon(l, -column * 0.75, column / 2.0, fill, &x, &y));
+        }
+        for l in &borehole.soil_layers {
+            svg.push_str(&column_polygon(l, 0.0, column, soil_fill(&l.layer), &x, &y));
+        }
+
+        if !borehole.spt.is_empty() {
+            let points: Vec<(f64, f64)> = borehole
+                .spt
+                .iter()
+                .map(|s| {
+                    let n = s.n_value.min(options.max_n_value);
+                    (x(s.point.chainage) + column / 2.0 + n * options.n_value_scale, y(s.point.elevation))
+                })
+                .collect();
+            let joined: Vec<String> = points.iter().map(|(px, py)| format!("{px:.1},{py:.1}")).collect();
+            svg.push_str(&format!(
+                "<polyline points=\"{}\" fill=\"none\" stroke=\"#d62728\"/>\n",
+                joined.join(" ")
+            ));
+            for ((px, py), s) in points.iter().zip(&borehole.spt) {
+                svg.push_str(&format!(
+                    "<circle cx=\"{px:.1}\" cy=\"{py:.1}\" r=\"1.5\" fill=\"#d62728\"><title>N={:.0}</title></circle>\n",
+                    s.n_value
+                ));
+            }
+        }
+
+        if let Some(water) = &borehole.stabilised_water_level {
+            let (wx, wy) = (x(water.point.chainage) - column / 2.0 - 6.0, y(water.point.elevation));
+            svg.push_str(&format!(
+                "<path d=\"M {:.1} {:.1} L {:.1} {:.1} L {wx:.1} {wy:.1} Z\" fill=\"#1f77b4\"><title>{} {:.2}m</title></path>\n",
+                wx - 4.0,
+                wy - 7.0,
+                wx + 4.0,
+                wy - 7.0,
+                xml_escape(&datum_label(&borehole.collar.datum)),
+                water.point.elevation
+            ));
+        }
+        svg.push_str("</g>\n");
+    }
+
+    svg.push_str("</svg>\n");
+    Ok(svg)
+}
+
+/// 層の柱を描く多角形（柱の中心からのずれ `shift` と幅 `width`, px）
+fn column_polygon(
+    interval: &SectionInterval,
+    shift: f64,
+    width: f64,
+    fill: &str,
+    x: &impl Fn(f64) -> f64,
+    y: &impl Fn(f64) -> f64,
+) -> String {
+    let (left, right) = (shift - width / 2.0, shift + width / 2.0);
+    let (top, bottom) = (interval.top, interval.bottom);
+    let title = match (&interval.layer.name, &interval.layer.symbol) {
+        (Some(name), Some(symbol)) => format!("{name} ({symbol})"),
+        (Some(name), None) => name.clone(),
+        (None, Some(symbol)) => symbol.clone(),
+        (None, None) => String::new(),
+    };
+    format!(
+        "<polygon points=\"{:.1},{:.1} {:.1},{:.1} {:.1},{:.1} {:.1},{:.1}\" fill=\"{fill}\" stroke=\"black\" stroke-width=\"0.5\"><title>{}</title></polygon>\n",
+        x(top.chainage) + left,
+        y(top.elevation),
+        x(top.chainage) + right,
+        y(top.elevation),
+        x(bottom.chainage) + right,
+        y(bottom.elevation),
+        x(bottom.chainage) + left,
+        y(bottom.elevation),
+        xml_escape(&title)
+    )
+}
+
+/// 目盛の間隔 (m)。1目盛が `min_pixels` 以上になる最小の1・2・5系列の値
+///
+/// `scale` は正の有限値であること（[`SvgOptions::validate`] で確認済み）
+fn tick_step(scale: f64, min_pixels: f64) -> f64 {
+    debug_assert!(scale.is_finite() && scale > 0.0);
+    let mut magnitude = 1.0;
+    loop {
+        for base in [1.0, 2.0, 5.0] {
+            let step = base * magnitude;
+            if step * scale >= min_pixels {
+                return step;
+            }
+        }
+        magnitude *= 10.0;
+    }
+}
+
+/// 土質・岩種区分の塗り色
+///
+/// 土質の大分類（[`SoilClass::from_layer`]）で決める。分類できない層（埋土など）は灰色。
+fn soil_fill(layer: &SoilLayer) -> &'static str {
+    match SoilClass::from_layer(layer) {
+        Some(SoilClass::Gravel) => "#f2c14e",
+        Some(SoilClass::Sand) => "#f7e59a",
+        Some(SoilClass::Clay) => "#a9c08a",
+        Some(SoilClass::Organic) => "#7a6652",
+        Some(SoilClass::Rock) => "#b0b0b0",
+        None => "#dddddd",
+    }
+}
+
+/// 地層岩体区分の塗り色（同じ名称は同じ色）
+fn stratum_fill(name: &str) -> &'static str {
+    const PALETTE: [&str; 8] = [
+        "#8dd3c7", "#bebada", "#fb8072", "#80b1d3", "#fdb462", "#b3de69", "#fccde5", "#bc80bd",
+    ];
+    let hash = name.chars().fold(0u32, |h, c| h.wrapping_mul(31).wrapping_add(c as u32));
+    PALETTE[hash as usize % PALETTE.len()]
+}
+
+/// XMLの文字参照に置き換える
+fn xml_escape(value: &str) -> String {
+    value
+        .replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::groundwater::{AquiferType, ReadingStage};
+    use crate::local_coordinate::HeightDatum;
+
+    fn zone() -> PlaneRectangularZone {
+        PlaneRectangularZone::new(9).unwrap()
+    }
+
+    fn at(x: f64, y: f64) -> PlaneRectangular {
+        PlaneRectangular { zone: zone(), x, y }
+    }
+
+    fn log(name: &str, collar: Option<PlaneRectangular>, elevation: f64, inclination: f64) -> BoreholeLog {
+        let mut trajectory = Trajectory::new(inclination, 90.0, Some(elevation));
+        if let Some(c) = collar {
+            // 原点付近のため子午線収差は無視できる
+            trajectory = trajectory.with_collar(c);
+        }
+        let layer = |top: f64, bottom: f64, name: &str| SoilLayer {
+            top_depth: top,
+            bottom_depth: bottom,
+            name: Some(name.to_string()),
+            symbol: None,
+        };
+        let reading = WaterLevelReading {
+            date: None,
+            drilling_depth: Some(10.0),
+            depth: Some(1.5),
+            elevation: None,
+            status_code: None,
+            aquifer_code: None,
+            stage: ReadingStage::AfterCompletion,
+            aquifer: AquiferType::Unconfined,
+            remarks: None,
+        };
+        BoreholeLog {
+            name: Some(name.to_string()),
+            collar: CollarElevation { datum: HeightDatum::TokyoPeil, value: elevation },
+            trajectory,
+            soil_layers: vec![layer(0.0, 3.0, "シルト質砂"), layer(3.0, 10.0, "粘土")],
+            stratum_layers: vec![layer(0.0, 10.0, "Ac1")],
+            spt: vec![SptRecord { start_depth: 1.0, blows: 10.0, penetration: Some(30.0) }],
+            water_levels: WaterLevelSeries::new(Some(elevation), vec![reading]),
+        }
+    }
+
+    #[test]
+    fn test_project_polyline() {
+        // 東へ100m進み、北へ100m進む折れ線
+        let line = SectionLine::new(vec![at(0.0, 0.0), at(0.0, 0.0), at(0.0, 100.0), at(100.0, 100.0)]).unwrap();
+        assert_eq!(line.vertices().len(), 3);
+        assert_eq!(line.length(), 200.0);
+
+        // 最初の区間の南側（進行方向右）
+        let p = line.project(&at(-5.0, 40.0));
+        assert!((p.chainage - 40.0).abs() < 1e-9 && (p.offset - 5.0).abs() < 1e-9);
+        // 2つめの区間の西側（進行方向左）
+        let p = line.project(&at(50.0, 90.0));
+        assert!((p.chainage - 150.0).abs() < 1e-9 && (p.offset + 10.0).abs() < 1e-9);
+        // 始点より手前は延長
+        let p = line.project(&at(0.0, -20.0));
+        assert!((p.chainage + 20.0).abs() < 1e-9 && p.offset.abs() < 1e-9);
+
+        assert_eq!(SectionLine::new(vec![at(1.0, 1.0), at(1.0, 1.0)]), Err(SectionError::TooFewPoints(1)));
+    }
+
+    #[test]
+    fn test_build_logs() {
+        let logs = vec![
+            log("B-2", Some(at(0.0, 50.0)), 8.0, 30.0),
+            log("B-1", Some(at(-3.0, 0.0)), 10.0, 0.0),
+            log("B-X", None, 5.0, 0.0),
+            log("B-3", Some(at(40.0, 80.0)), 6.0, 0.0),
+        ];
+        let line = SectionLine::new(vec![at(0.0, 0.0), at(0.0, 100.0)]).unwrap();
+        let mut builder = SectionBuilder::along(line);
+        builder.max_offset = Some(20.0);
+        let section = builder.build_logs(&logs).unwrap();
+
+        let names: Vec<_> = section.boreholes.iter().map(|b| b.name.as_deref().unwrap()).collect();
+        assert_eq!(names, vec!["B-1", "B-2"]);
+        assert_eq!(section.skipped.len(), 2);
+        assert_eq!(section.skipped[0].reason, SkipReason::NoLocation);
+        assert!(matches!(section.skipped[1].reason, SkipReason::TooFarFromLine(o) if (o + 40.0).abs() < 1e-6));
+
+        // 鉛直孔は孔口の距離程のまま深度を標高に換算
+        let b1 = &section.boreholes[0];
+        assert!((b1.projection.offset - 3.0).abs() < 1e-9);
+        assert!((b1.soil_layers[1].bottom.elevation - 0.0).abs() < 1e-9);
+        assert!((b1.spt[0].n_value - 10.0).abs() < 1e-9);
+        assert!((b1.stabilised_water_level.as_ref().unwrap().point.elevation - 8.5).abs() < 1e-9);
+
+        // 東へ30°傾いた孔は深くなるほど距離程が進む
+        let b2 = &section.boreholes[1];
+        let bottom = b2.soil_layers[1].bottom;
+        assert!((bottom.chainage - 55.0).abs() < 1e-3);
+        assert!((bottom.elevation - (8.0 - 10.0 * 30f64.to_radians().cos())).abs() < 1e-9);
+        assert_eq!(section.elevation_range().map(|(_, max)| max), Some(10.0));
+        assert!(!section.mixed_datums());
+    }
+
+    #[test]
+    fn test_to_svg() {
+        let mut logs = vec![log("B-1", Some(at(0.0, 0.0)), 10.0, 0.0), log("B<2>", Some(at(0.0, 60.0)), 9.0, 0.0)];
+        logs[1].collar.datum = HeightDatum::GroundLevel;
+        let section = SectionBuilder::new(zone()).build_logs(&logs).unwrap();
+        assert!(section.mixed_datums());
+        // T.P.とG.L.を同じ標高軸には描けない
+        assert_eq!(
+            to_svg(&section, &SvgOptions::default()),
+            Err(SectionError::MixedDatums("T.P., G.L.".to_string()))
+        );
+
+        logs[1].collar.datum = HeightDatum::TokyoPeil;
+        let section = SectionBuilder::new(zone()).build_logs(&logs).unwrap();
+        let svg = to_svg(&section, &SvgOptions::default()).unwrap();
+        assert!(svg.starts_with("<svg") && svg.ends_with("</svg>\n"));
+        assert_eq!(svg.matches("<polygon").count(), 6);
+        assert!(svg.contains("B&lt;2&gt;") && !svg.contains("B<2>"));
+        assert!(svg.contains("fill=\"#f7e59a\""));
+
+        for scale in [0.0, -1.0, f64::NAN, f64::INFINITY] {
+            let options = SvgOptions { vertical_scale: scale, ..SvgOptions::default() };
+            assert!(matches!(to_svg(&section, &options), Err(SectionError::InvalidOption("vertical_scale", _))));
+        }
+    }
+}
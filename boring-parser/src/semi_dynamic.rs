@@ -0,0 +1,275 @@
+//! セミ・ダイナミック補正モジュール
+//!
+//! 国土地理院のSemiDynaEXEパラメータを使い、地殻変動による座標のずれを
+//! 元期（測地成果2011の基準日）と今期（観測日）の間で補正する。
+//!
+//! # パラメータの適用期間
+//! パラメータファイルは年度ごとに公開され（例: `SemiDyna2023.par`）、
+//! その年の4月1日から翌年3月31日までの観測に適用する。
+//!
+//! # 補正の向き
+//! - 順方向: 元期 → 今期
+//! - 逆方向: 今期 → 元期
+//!
+//! 今期A → 今期B の補正は、Aの年度のパラメータで元期に戻したあと、
+//! Bの年度のパラメータで今期Bに進める。
+
+use jgdtrans::{Format, ParData, Point, Transformer};
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::Path;
+
+use crate::transform::{SurveyStartDate, TransformError, read_param_file};
+
+/// パラメータの年度が切り替わる月
+const FISCAL_YEAR_START_MONTH: u32 = 4;
+
+/// 座標の元期・今期
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Epoch {
+    /// 元期（測地成果2011）
+    Reference,
+    /// 今期（観測日）
+    At(SurveyStartDate),
+}
+
+impl Epoch {
+    /// パラメータの年度（元期はNone）
+    pub fn fiscal_year(&self) -> Option<i32> {
+        match self {
+            Epoch::Reference => None,
+            Epoch::At(date) if date.month < FISCAL_YEAR_START_MONTH => Some(date.year - 1),
+            Epoch::At(date) => Some(date.year),
+        }
+    }
+}
+
+/// 年度ごとのパラメータ
+struct YearParameter {
+    /// ファイル名
+    file_name: String,
+    /// 変換器
+    tf: Transformer<ParData>,
+}
+
+/// セミ・ダイナミック補正
+///
+/// # Example
+/// ```ignore
+/// let semi = SemiDynamic::load("params/semidyna")?;
+/// let from = Epoch::At(SurveyStartDate::new(2018, 6, 1));
+/// let to = Epoch::At(SurveyStartDate::new(2024, 6, 1));
+/// let (lat, lon, alt) = semi.correct(36.1, 140.1, 30.0, from, to)?;
+/// ```
+pub struct SemiDynamic {
+    /// 年度 → パラメータ
+    years: BTreeMap<i32, YearParameter>,
+}
+
+impl Default for SemiDynamic {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl SemiDynamic {
+    /// パラメータなしで作成（[`SemiDynamic::insert`] で追加する）
+    pub fn new() -> Self {
+        Self { years: BTreeMap::new() }
+    }
+
+    /// ディレクトリ内のSemiDynaEXEパラメータファイルを読み込む
+    ///
+    /// ファイル名が `SemiDyna` で始まり、年度(4桁)を含む `.par` ファイルを対象とする。
+    /// (例: `SemiDyna2023.par`, `SemiDynaEXE2023.par`)
+    pub fn load<P: AsRef<Path>>(dir: P) -> Result<Self, TransformError> {
+        let dir = dir.as_ref();
+        if !dir.exists() {
+            return Err(TransformError::DirectoryNotFound(dir.display().to_string()));
+        }
+
+        let mut semi = Self::new();
+        for entry in fs::read_dir(dir)? {
+            let path = entry?.path();
+            if path.extension().and_then(|s| s.to_str()) != Some("par") {
+                continue;
+            }
+            let Some(file_name) = path.file_name().and_then(|s| s.to_str()) else {
+                continue;
+            };
+            let Some(year) = parameter_year(file_name) else {
+                continue;
+            };
+
+            let content = read_param_file(&path)?;
+            let file_name = file_name.to_string();
+            semi.insert_str(year, &content, file_name)?;
+        }
+        Ok(semi)
+    }
+
+    /// パラメータファイルの内容を年度を指定して追加
+    pub fn insert_str(
+        &mut self,
+        year: i32,
+        content: &str,
+        file_name: String,
+    ) -> Result<(), TransformError> {
+        let tf = Transformer::from_str(content, Format::SemiDynaEXE)
+            .map_err(|e| TransformError::Parse(format!("{}: {}", file_name, e)))?;
+        self.insert(year, tf, file_name);
+        Ok(())
+    }
+
+    /// 読み込み済みのパラメータを年度を指定して追加
+    pub fn insert(&mut self, year: i32, tf: Transformer<ParData>, file_name: String) {
+        self.years.insert(year, YearParameter { file_name, tf });
+    }
+
+    /// 読み込んだパラメータの年度
+    pub fn years(&self) -> impl Iterator<Item = i32> + '_ {
+        self.years.keys().copied()
+    }
+
+    /// 今期の座標を元期に補正
+    pub fn to_reference(&self, point: &Point, epoch: Epoch) -> Result<Point, TransformError> {
+        match self.parameter(epoch)? {
+            None => Ok(point.clone()),
+            Some(param) => param
+                .tf
+                .backward(point)
+                .map_err(|e| TransformError::OutOfCoverage(format!("{}: {}", param.file_name, e))),
+        }
+    }
+
+    /// 元期の座標を今期に補正
+    pub fn from_reference(&self, point: &Point, epoch: Epoch) -> Result<Point, TransformError> {
+        match self.parameter(epoch)? {
+            None => Ok(point.clone()),
+            Some(param) => param
+                .tf
+                .forward(point)
+                .map_err(|e| TransformError::OutOfCoverage(format!("{}: {}", param.file_name, e))),
+        }
+    }
+
+    /// 座標を `from` の元期・今期から `to` の元期・今期に補正
+    ///
+    /// # Returns
+    /// 補正後の (緯度, 経度, 標高)
+    pub fn correct(
+        &self,
+        lat: f64,
+        lon: f64,
+        alt: f64,
+        from: Epoch,
+        to: Epoch,
+    ) -> Result<(f64, f64, f64), TransformError> {
+        let point = Point::new_unchecked(lat, lon, alt);
+        let result = if from.fiscal_year() == to.fiscal_year() {
+            point
+        } else {
+            let reference = self.to_reference(&point, from)?;
+            self.from_reference(&reference, to)?
+        };
+        Ok((result.latitude, result.longitude, result.altitude))
+    }
+
+    /// 元期・今期に対応するパラメータのファイル名（元期はNone）
+    pub fn file_name(&self, epoch: Epoch) -> Result<Option<&str>, TransformError> {
+        Ok(self.parameter(epoch)?.map(|p| p.file_name.as_str()))
+    }
+
+    /// 元期・今期に対応するパラメータ（元期はNone）
+    fn parameter(&self, epoch: Epoch) -> Result<Option<&YearParameter>, TransformError> {
+        match epoch.fiscal_year() {
+            None => Ok(None),
+            Some(year) => self
+                .years
+                .get(&year)
+                .map(Some)
+                .ok_or(TransformError::MissingSemiDynamic(year)),
+        }
+    }
+}
+
+/// ファイル名からパラメータの年度を取得
+fn parameter_year(file_name: &str) -> Option<i32> {
+    if !file_name.to_ascii_lowercase().starts_with("semidyna") {
+        return None;
+    }
+    let bytes = file_name.as_bytes();
+    bytes
+        .windows(4)
+        .position(|w| w.iter().all(u8::is_ascii_digit))
+        .and_then(|i| file_name[i..i + 4].parse().ok())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use jgdtrans::Parameter;
+    use std::collections::HashMap;
+
+    /// SemiDynaEXE2023.par の一部 (つくば付近)
+    fn tsukuba(scale: f64) -> Transformer<ParData> {
+        let params = [
+            (54401005, Parameter::new(-0.00622, 0.01516, 0.0946)),
+            (54401055, Parameter::new(-0.0062, 0.01529, 0.08972)),
+            (54401100, Parameter::new(-0.00663, 0.01492, 0.10374)),
+            (54401150, Parameter::new(-0.00664, 0.01506, 0.10087)),
+        ]
+        .into_iter()
+        .map(|(code, p)| {
+            (code, Parameter::new(p.latitude * scale, p.longitude * scale, p.altitude * scale))
+        })
+        .collect::<HashMap<_, _>>();
+        Transformer::new(ParData::new(Format::SemiDynaEXE, params))
+    }
+
+    #[test]
+    fn test_fiscal_year_and_file_name() {
+        assert_eq!(Epoch::At(SurveyStartDate::new(2021, 3, 31)).fiscal_year(), Some(2020));
+        assert_eq!(Epoch::At(SurveyStartDate::new(2021, 4, 1)).fiscal_year(), Some(2021));
+        assert_eq!(Epoch::Reference.fiscal_year(), None);
+
+        assert_eq!(parameter_year("SemiDyna2023.par"), Some(2023));
+        assert_eq!(parameter_year("SemiDynaEXE2009.par"), Some(2009));
+        assert_eq!(parameter_year("kumamoto2016.par"), None);
+    }
+
+    #[test]
+    fn test_correct_between_epochs() {
+        let mut semi = SemiDynamic::new();
+        semi.insert(2020, tsukuba(0.5), "SemiDyna2020.par".to_string());
+        semi.insert(2023, tsukuba(1.0), "SemiDyna2023.par".to_string());
+
+        let (lat, lon) = (36.10377479, 140.087855041);
+        let e2020 = Epoch::At(SurveyStartDate::new(2020, 6, 1));
+        let e2023 = Epoch::At(SurveyStartDate::new(2023, 6, 1));
+
+        // 元期 → 2023年度: jgdtrans の例と同じ値
+        let (lat23, lon23, alt23) = semi.correct(lat, lon, 2.34, Epoch::Reference, e2023).unwrap();
+        assert!((lat23 - 36.103773017086695).abs() < 1e-12);
+        assert!((lon23 - 140.08785924333452).abs() < 1e-12);
+        assert!((alt23 - 2.4363138578103).abs() < 1e-9);
+
+        // 2023年度 → 元期 で戻る
+        let (back_lat, back_lon, _) = semi.correct(lat23, lon23, alt23, e2023, Epoch::Reference).unwrap();
+        assert!((back_lat - lat).abs() < 1e-10 && (back_lon - lon).abs() < 1e-10);
+
+        // 2020年度 → 2023年度 は差分の変動 (半分) だけ動く
+        let (lat20, lon20, alt20) = semi.correct(lat, lon, 2.34, Epoch::Reference, e2020).unwrap();
+        assert!((lat20 - lat).abs() > 1e-10);
+        let (lat20_23, lon20_23, _) = semi.correct(lat20, lon20, alt20, e2020, e2023).unwrap();
+        assert!((lat20_23 - lat23).abs() < 1e-10 && (lon20_23 - lon23).abs() < 1e-10);
+
+        // 同じ年度は補正なし、パラメータのない年度はエラー
+        assert_eq!(semi.correct(lat, lon, 0.0, e2023, e2023).unwrap(), (lat, lon, 0.0));
+        let e2015 = Epoch::At(SurveyStartDate::new(2015, 6, 1));
+        assert!(matches!(
+            semi.correct(lat, lon, 0.0, e2015, e2023),
+            Err(TransformError::MissingSemiDynamic(2015))
+        ));
+    }
+}
@@ -0,0 +1,167 @@
+//! 標準貫入試験のN値を取得するモジュール
+//!
+//! 各バージョンの標準貫入試験データを、開始深度・打撃回数・貫入量を持つ
+//! レコードのリストとして統一的に取得できます。
+//! v4.00では貫入量の単位がmmに変更されているため、cmに換算して扱う。
+
+use serde::Serialize;
+
+use crate::types::parse_f64;
+
+/// 標準貫入試験の規定貫入量 (cm)
+pub const STANDARD_PENETRATION: f64 = 30.0;
+
+/// 標準貫入試験1回分
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+pub struct SptRecord {
+    /// 開始深度 (m)
+    pub start_depth: f64,
+    /// 合計打撃回数
+    pub blows: f64,
+    /// 合計貫入量 (cm)
+    pub penetration: Option<f64>,
+}
+
+impl SptRecord {
+    /// N値
+    ///
+    /// 打撃回数が打ち切られ、貫入量が30cmに満たない場合は30cm相当に換算する。
+    pub fn n_value(&self) -> f64 {
+        match self.penetration {
+            Some(p) if p > 0.0 && p < STANDARD_PENETRATION => {
+                self.blows * STANDARD_PENETRATION / p
+            }
+            _ => self.blows,
+        }
+    }
+
+    /// 試験区間の中央深度 (m)
+    pub fn mid_depth(&self) -> f64 {
+        let penetration = self.penetration.unwrap_or(STANDARD_PENETRATION);
+        self.start_depth + penetration / 100.0 / 2.0
+    }
+}
+
+/// 指定深度に最も近い標準貫入試験を取得
+pub fn nearest_spt(records: &[SptRecord], depth: f64) -> Option<&SptRecord> {
+    records.iter().min_by(|a, b| {
+        let da = (a.mid_depth() - depth).abs();
+        let db = (b.mid_depth() - depth).abs();
+        da.total_cmp(&db)
+    })
+}
+
+fn spt_records<'a, I>(rows: I, penetration_scale: f64) -> Vec<SptRecord>
+where
+    I: IntoIterator<Item = (Option<&'a str>, Option<&'a str>, Option<&'a str>)>,
+{
+    rows.into_iter()
+        .filter_map(|(depth, blows, penetration)| {
+            Some(SptRecord {
+                start_depth: parse_f64(depth)?,
+                blows: parse_f64(blows)?,
+                penetration: parse_f64(penetration).map(|p| p * penetration_scale),
+            })
+        })
+        .collect()
+}
+
+/// 標準貫入試験データを取得するためのトレイト
+pub trait StandardPenetrationTests {
+    /// 標準貫入試験を開始深度・打撃回数・貫入量(cm)のレコードとして取得
+    fn spt_records(&self) -> Vec<SptRecord>;
+}
+
+impl StandardPenetrationTests for crate::boring_structs_400::Boring400 {
+    fn spt_records(&self) -> Vec<SptRecord> {
+        // v4.00は貫入量がmm単位
+        spt_records(
+            self.core.standard_penetration_tests.iter().map(|t| {
+                (
+                    t.start_depth.as_deref(),
+                    t.total_hit_count.as_deref(),
+                    t.total_penetration.as_deref(),
+                )
+            }),
+            0.1,
+        )
+    }
+}
+
+impl StandardPenetrationTests for crate::boring_structs_300::Boring300 {
+    fn spt_records(&self) -> Vec<SptRecord> {
+        spt_records(
+            self.core.standard_penetration_tests.iter().map(|t| {
+                (t.start_depth.as_deref(), t.total_hits.as_deref(), t.total_penetration.as_deref())
+            }),
+            1.0,
+        )
+    }
+}
+
+impl StandardPenetrationTests for crate::boring_structs_210::Boring210 {
+    fn spt_records(&self) -> Vec<SptRecord> {
+        spt_records(
+            self.core.standard_penetration_tests.iter().map(|t| {
+                (t.start_depth.as_deref(), t.total_hits.as_deref(), t.total_penetration.as_deref())
+            }),
+            1.0,
+        )
+    }
+}
+
+impl StandardPenetrationTests for crate::boring_structs_200::Boring200 {
+    fn spt_records(&self) -> Vec<SptRecord> {
+        spt_records(
+            self.core.standard_penetration_tests.iter().map(|t| {
+                (t.start_depth.as_deref(), t.total_hits.as_deref(), t.total_penetration.as_deref())
+            }),
+            1.0,
+        )
+    }
+}
+
+impl StandardPenetrationTests for crate::boring_structs_110::Boring110 {
+    fn spt_records(&self) -> Vec<SptRecord> {
+        spt_records(
+            self.core.standard_penetration_test.iter().map(|t| {
+                (t.start_depth.as_deref(), t.total_hits.as_deref(), t.total_penetration.as_deref())
+            }),
+            1.0,
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_n_value_conversion() {
+        let full = SptRecord { start_depth: 1.15, blows: 17.0, penetration: Some(30.0) };
+        assert_eq!(full.n_value(), 17.0);
+
+        // 50回で15cmの打ち切り → 100
+        let refusal = SptRecord { start_depth: 10.15, blows: 50.0, penetration: Some(15.0) };
+        assert_eq!(refusal.n_value(), 100.0);
+
+        // 自沈等で30cmを超える場合は打撃回数をそのまま使う
+        let soft = SptRecord { start_depth: 2.15, blows: 3.0, penetration: Some(45.0) };
+        assert_eq!(soft.n_value(), 3.0);
+    }
+
+    #[test]
+    fn test_spt_records_and_nearest() {
+        let rows = vec![
+            (Some("1.15"), Some("3"), Some("450")),
+            (Some("2.15"), Some("4"), Some("300")),
+            (Some("3.15"), Some(""), Some("300")),
+        ];
+        let records = spt_records(rows, 0.1);
+
+        assert_eq!(records.len(), 2);
+        assert_eq!(records[0].penetration, Some(45.0));
+        assert!((records[1].mid_depth() - 2.3).abs() < 1e-9);
+        assert_eq!(nearest_spt(&records, 2.6).unwrap().blows, 4.0);
+    }
+}
@@ -0,0 +1,295 @@
+//! 斜めボーリングの孔跡モジュール
+//!
+//! ボーリング基本情報の掘進角度（v4.00: 角度）と掘進方向（v1.10: 掘進方位、v4.00: 方位）から
+//! 孔跡を直線としてモデル化し、孔口からの掘進長（XMLの深度）を鉛直深度・標高・
+//! 水平方向の離れに変換する。
+//!
+//! # 角度の定義（柱状図の表題欄による）
+//! - 掘進角度: 鉛直下向きを0°、水平を90°、鉛直上向きを180°とする
+//! - 掘進方向: 真北を0°とし、時計回り（東を90°）とする
+//!
+//! 平面直角座標の孔口位置を与えると、子午線収差を補正して座標北基準の方向角に直し、
+//! 各深度の平面直角座標を求める。角度の記載がない場合は鉛直孔とする。
+
+use serde::Serialize;
+
+use crate::coordinate::GeoCoordinate3D;
+use crate::elevation::CollarElevation;
+use crate::layer::SoilLayer;
+use crate::plane_rectangular::{PlaneRectangular, PlaneRectangularZone};
+use crate::spt::SptRecord;
+use crate::transform::JgdTransformer;
+use crate::types::parse_f64;
+
+/// 孔跡（直線）
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+pub struct Trajectory {
+    /// 掘進角度（鉛直下向きから、度）
+    pub inclination: f64,
+    /// 掘進方向（真北から時計回り、度）
+    pub azimuth: f64,
+    /// 孔口標高 (m)
+    pub collar_elevation: Option<f64>,
+    /// 孔口の平面直角座標
+    pub collar: Option<PlaneRectangular>,
+    /// 孔口の子午線収差（真北の座標北からの角度、東側で正、度）
+    pub convergence: f64,
+}
+
+/// 孔跡上の位置
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+pub struct TrajectoryPoint {
+    /// 掘進長（XMLの深度、m）
+    pub depth: f64,
+    /// 鉛直深度 (m)
+    pub vertical_depth: f64,
+    /// 標高 (m)。孔口標高がない場合はNone
+    pub elevation: Option<f64>,
+    /// 孔口から北方向（座標北）への離れ (m)
+    pub north: f64,
+    /// 孔口から東方向への離れ (m)
+    pub east: f64,
+    /// 平面直角座標。孔口位置がない場合はNone
+    pub plane: Option<PlaneRectangular>,
+}
+
+impl TrajectoryPoint {
+    /// 孔口からの水平距離 (m)
+    pub fn horizontal_distance(&self) -> f64 {
+        self.north.hypot(self.east)
+    }
+}
+
+/// 孔跡上に配置した層
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct PositionedLayer {
+    /// 層
+    pub layer: SoilLayer,
+    /// 上端
+    pub top: TrajectoryPoint,
+    /// 下端
+    pub bottom: TrajectoryPoint,
+}
+
+impl Trajectory {
+    /// 掘進角度・掘進方向（度）から作成
+    pub fn new(inclination: f64, azimuth: f64, collar_elevation: Option<f64>) -> Self {
+        Self {
+            inclination: inclination.clamp(0.0, 180.0),
+            azimuth: azimuth.rem_euclid(360.0),
+            collar_elevation,
+            collar: None,
+            convergence: 0.0,
+        }
+    }
+
+    /// 鉛直孔
+    pub fn vertical(collar_elevation: Option<f64>) -> Self {
+        Self::new(0.0, 0.0, collar_elevation)
+    }
+
+    /// 孔口の平面直角座標を指定（子午線収差も求める）
+    pub fn with_collar(mut self, collar: PlaneRectangular) -> Self {
+        let (lat, lng) = collar.to_geographic();
+        let (_, lng0) = collar.zone.origin();
+        self.convergence = ((lng - lng0).to_radians().tan() * lat.to_radians().sin())
+            .atan()
+            .to_degrees();
+        self.collar = Some(collar);
+        self
+    }
+
+    /// 鉛直孔か
+    pub fn is_vertical(&self) -> bool {
+        self.inclination == 0.0
+    }
+
+    /// 座標北基準の方向角（度）
+    pub fn grid_azimuth(&self) -> f64 {
+        (self.azimuth - self.convergence).rem_euclid(360.0)
+    }
+
+    /// 掘進長の位置
+    ///
+    /// # Example
+    /// ```ignore
+    /// let p = boring.trajectory().point_at(10.0);
+    /// println!("鉛直深度 {:.2}m 標高 {:?}m", p.vertical_depth, p.elevation);
+    /// ```
+    pub fn point_at(&self, depth: f64) -> TrajectoryPoint {
+        let (sin_i, cos_i) = self.inclination.to_radians().sin_cos();
+        let (sin_a, cos_a) = self.grid_azimuth().to_radians().sin_cos();
+        let vertical_depth = depth * cos_i;
+        let horizontal = depth * sin_i;
+        let north = horizontal * cos_a;
+        let east = horizontal * sin_a;
+
+        TrajectoryPoint {
+            depth,
+            vertical_depth,
+            elevation: self.collar_elevation.map(|e| e - vertical_depth),
+            north,
+            east,
+            plane: self.collar.map(|c| PlaneRectangular {
+                zone: c.zone,
+                x: c.x + north,
+                y: c.y + east,
+            }),
+        }
+    }
+
+    /// 複数の掘進長の位置
+    pub fn points<I>(&self, depths: I) -> Vec<TrajectoryPoint>
+    where
+        I: IntoIterator<Item = f64>,
+    {
+        depths.into_iter().map(|d| self.point_at(d)).collect()
+    }
+
+    /// 鉛直深度に達する掘進長 (m)。水平・上向きの孔で達しない場合はNone
+    pub fn depth_at_vertical_depth(&self, vertical_depth: f64) -> Option<f64> {
+        let cos_i = self.inclination.to_radians().cos();
+        (cos_i > 1e-9).then(|| vertical_depth / cos_i)
+    }
+
+    /// 層の上端・下端の位置
+    pub fn layers(&self, layers: &[SoilLayer]) -> Vec<PositionedLayer> {
+        layers
+            .iter()
+            .map(|layer| PositionedLayer {
+                layer: layer.clone(),
+                top: self.point_at(layer.top_depth),
+                bottom: self.point_at(layer.bottom_depth),
+            })
+            .collect()
+    }
+
+    /// 標準貫入試験の試験区間中央の位置
+    pub fn spt(&self, records: &[SptRecord]) -> Vec<(SptRecord, TrajectoryPoint)> {
+        records
+            .iter()
+            .map(|r| (*r, self.point_at(r.mid_depth())))
+            .collect()
+    }
+}
+
+/// 孔跡を取得するためのトレイト
+pub trait BoreholeTrajectory: GeoCoordinate3D {
+    /// 掘進角度と掘進方向の文字列
+    fn drilling_angles(&self) -> (Option<&str>, Option<&str>);
+
+    /// 孔跡を取得（孔口位置なし）
+    ///
+    /// 掘進角度がない場合は鉛直孔、掘進方向がない場合は北向きとする。
+    fn trajectory(&self) -> Trajectory {
+        let (angle, direction) = self.drilling_angles();
+        let elevation = CollarElevation::parse(self.collar_elevation().as_deref()).ok().map(|c| c.value);
+        match parse_f64(angle) {
+            Some(inclination) => Trajectory::new(inclination, parse_f64(direction).unwrap_or(0.0), elevation),
+            None => Trajectory::vertical(elevation),
+        }
+    }
+
+    /// 孔口の平面直角座標を含む孔跡を取得
+    ///
+    /// 孔口の経度緯度をJGD2011に変換し、指定した系に投影する。変換できない場合は孔口位置なし。
+    ///
+    /// # Example
+    /// ```ignore
+    /// let zone = PlaneRectangularZone::new(6).unwrap();
+    /// for layer in boring.trajectory_on(&transformer, zone).layers(&boring.soil_layers()) {
+    ///     println!("{:?} {:?}", layer.bottom.plane, layer.bottom.elevation);
+    /// }
+    /// ```
+    fn trajectory_on(&self, transformer: &JgdTransformer, zone: PlaneRectangularZone) -> Trajectory {
+        let trajectory = self.trajectory();
+        match self.geo_location().to_plane_rectangular(transformer, zone) {
+            Some(collar) => trajectory.with_collar(collar),
+            None => trajectory,
+        }
+    }
+}
+
+impl BoreholeTrajectory for crate::boring_structs_400::Boring400 {
+    fn drilling_angles(&self) -> (Option<&str>, Option<&str>) {
+        let info = &self.title.boring_basic_info;
+        (info.angle.as_deref(), info.azimuth.as_deref())
+    }
+}
+
+impl BoreholeTrajectory for crate::boring_structs_300::Boring300 {
+    fn drilling_angles(&self) -> (Option<&str>, Option<&str>) {
+        let info = &self.title.boring_basic_info;
+        (info.drilling_angle.as_deref(), info.drilling_direction.as_deref())
+    }
+}
+
+impl BoreholeTrajectory for crate::boring_structs_210::Boring210 {
+    fn drilling_angles(&self) -> (Option<&str>, Option<&str>) {
+        let info = &self.title.boring_basic_info;
+        (info.drilling_angle.as_deref(), info.drilling_direction.as_deref())
+    }
+}
+
+impl BoreholeTrajectory for crate::boring_structs_200::Boring200 {
+    fn drilling_angles(&self) -> (Option<&str>, Option<&str>) {
+        let info = &self.title.boring_basic_info;
+        (info.drilling_angle.as_deref(), info.drilling_direction.as_deref())
+    }
+}
+
+impl BoreholeTrajectory for crate::boring_structs_110::Boring110 {
+    fn drilling_angles(&self) -> (Option<&str>, Option<&str>) {
+        let info = &self.title.boring_basic_info;
+        (info.drilling_angle.as_deref(), info.drilling_direction.as_deref())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_point_at() {
+        // 鉛直孔
+        let p = Trajectory::vertical(Some(50.0)).point_at(10.0);
+        assert_eq!((p.vertical_depth, p.elevation, p.horizontal_distance()), (10.0, Some(40.0), 0.0));
+
+        // 鉛直から30°、東向き
+        let p = Trajectory::new(30.0, 90.0, Some(50.0)).point_at(10.0);
+        assert!((p.vertical_depth - 8.660254).abs() < 1e-6);
+        assert!((p.elevation.unwrap() - 41.339746).abs() < 1e-6);
+        assert!(p.north.abs() < 1e-9 && (p.east - 5.0).abs() < 1e-9);
+
+        // 上向き（180°）は鉛直深度が負
+        let t = Trajectory::new(180.0, 0.0, None);
+        assert!((t.point_at(5.0).vertical_depth + 5.0).abs() < 1e-9);
+        assert_eq!(Trajectory::new(90.0, 0.0, None).depth_at_vertical_depth(1.0), None);
+        assert!((Trajectory::new(60.0, 0.0, None).depth_at_vertical_depth(5.0).unwrap() - 10.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_with_collar_applies_convergence() {
+        // IX系原点の真上は子午線収差0
+        let zone = PlaneRectangularZone::new(9).unwrap();
+        let t = Trajectory::new(90.0, 0.0, Some(0.0)).with_collar(PlaneRectangular { zone, x: 0.0, y: 0.0 });
+        assert!(t.convergence.abs() < 1e-9);
+        let p = t.point_at(10.0).plane.unwrap();
+        assert!((p.x - 10.0).abs() < 1e-9 && p.y.abs() < 1e-9);
+
+        // 原点から東では真北が座標北より西に傾くため、真北向きの孔は座標上やや西へ向かう
+        let t = Trajectory::new(90.0, 0.0, None).with_collar(PlaneRectangular { zone, x: 0.0, y: 50_000.0 });
+        assert!(t.convergence > 0.2 && t.convergence < 0.4);
+        let p = t.point_at(100.0);
+        assert!(p.east < 0.0 && (p.horizontal_distance() - 100.0).abs() < 1e-9);
+        assert!((p.plane.unwrap().y - (50_000.0 + p.east)).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_layers() {
+        let layers = vec![SoilLayer { top_depth: 0.0, bottom_depth: 2.0, name: None, symbol: None }];
+        let positioned = Trajectory::new(60.0, 180.0, Some(10.0)).layers(&layers);
+        assert!((positioned[0].bottom.elevation.unwrap() - 9.0).abs() < 1e-9);
+        assert!((positioned[0].bottom.north + 3f64.sqrt()).abs() < 1e-9);
+    }
+}
@@ -1,20 +1,41 @@
 //! 測地系変換モジュール
 //!
-//! 日本測地系(Tokyo Datum)およびJGD2000からJGD2011への座標変換を提供する。
+//! 日本測地系(Tokyo Datum)およびJGD2000からJGD2011への座標変換と、その逆変換を提供する。
 //! 国土地理院のパラメータファイル(TKY2JGD, PatchJGD)を使用。
 //!
 //! # 地震補正の適用ルール
 //! 地震による地殻変動の補正は、**調査日が地震発生日より前**の場合のみ適用される。
 //! 調査日が地震後の場合、測量時点で既に地殻変動後の位置が記録されているため、
 //! 補正を適用すると誤った位置になる。
+//!
+//! パラメータファイルは調査日によらずすべて一度だけ読み込み、変換のたびに
+//! 調査日から適用するファイルを選ぶ。1つの変換器で調査日の異なる多数の
+//! ファイルを変換できる（[`JgdTransformer::transform_on`]、
+//! [`JgdTransformer::transform_document`]）。
+//!
+//! # 地震補正パラメータのカタログ
+//! 地震補正ファイルとその地震の発生日の対応は [`EarthquakeCatalogue`] で管理する。
+//! 組み込みの一覧に加え、パッチディレクトリの `earthquakes.csv` から追加・上書きできる。
+//! カタログにない地震補正ファイルがある場合、読み込み時にエラーとする。
+//!
+//! # 変換の来歴
+//! [`JgdTransformer::transform_with_provenance`] は、実行した変換ステップ、
+//! 調査日により適用しなかった地震補正、パラメータ範囲外だったステップを
+//! [`TransformResult`] として返す。
 
 use encoding_rs::SHIFT_JIS;
-use jgdtrans::{Format, ParData, Parameter, Point, Transformer};
-use std::collections::HashMap;
+use jgdtrans::mesh::MeshCell;
+use jgdtrans::{Correction, Format, MeshUnit, ParData, Parameter, Point};
+use serde::Serialize;
 use std::fs;
 use std::path::Path;
 use thiserror::Error;
 
+use crate::coordinate::GeoCoordinate;
+use crate::param_cache::{ParamCache, ParamTable, PatchSource};
+use crate::semi_dynamic::{Epoch, SemiDynamic};
+use crate::wgs84;
+
 /// 変換エラー
 #[derive(Error, Debug)]
 pub enum TransformError {
@@ -29,12 +50,216 @@ pub enum TransformError {
 
     #[error("パラメータディレクトリが存在しません: {0}")]
     DirectoryNotFound(String),
+
+    #[error("パラメータの範囲外です: {0}")]
+    OutOfCoverage(String),
+
+    #[error("地震カタログに登録されていないパッチファイルです: {0}")]
+    UnknownPatchFile(String),
+
+    #[error("セミ・ダイナミック補正パラメータがありません: {0}年度")]
+    MissingSemiDynamic(i32),
+
+    #[error("未知の測地系コードです: {0}")]
+    UnknownGeodeticSystem(String),
+}
+
+/// 変換ステップの種別
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+pub enum StepKind {
+    /// Tokyo Datum → JGD2000 (TKY2JGD)
+    Tky2Jgd,
+    /// 地震による水平補正 (PatchJGD)
+    PatchHorizontal,
+    /// 地震による標高補正 (PatchJGD_H)
+    PatchElevation,
+    /// ジオイドモデル改定による標高補正 (HyokoRev)
+    Geoid,
+    /// 地殻変動のセミ・ダイナミック補正 (SemiDynaEXE)
+    SemiDynamic,
+    /// JGD2011 → WGS84 (proj)
+    Wgs84,
+}
+
+/// 変換ステップの状態
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+pub enum StepStatus {
+    /// 補正を適用した
+    Applied,
+    /// 地点がパラメータの範囲外のため補正されなかった
+    OutOfCoverage,
+    /// 調査日が地震発生日より後のため適用しなかった
+    SkippedSurveyDate,
+}
+
+/// 変換ステップ1件の来歴
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct TransformStep {
+    /// ステップの種別
+    pub kind: StepKind,
+    /// 使用したパラメータファイル名
+    pub file_name: Option<String>,
+    /// 状態
+    pub status: StepStatus,
+}
+
+/// 来歴付きの変換結果
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct TransformResult {
+    /// 緯度 (度, 10進数)
+    pub latitude: f64,
+    /// 経度 (度, 10進数)
+    pub longitude: f64,
+    /// 標高 (m)
+    pub altitude: f64,
+    /// 変換元の測地系コード
+    pub system_code: String,
+    /// 地震補正の適用判定に使った調査日
+    pub survey_date: Option<SurveyStartDate>,
+    /// 実行・スキップしたステップ
+    pub steps: Vec<TransformStep>,
+    /// 適用対象のPatchJGD系パラメータのいずれも地点を含まず、JGD2000の座標をそのまま返した
+    pub patch_fallback: bool,
+}
+
+impl TransformResult {
+    /// 適用したステップ
+    pub fn applied_steps(&self) -> impl Iterator<Item = &TransformStep> {
+        self.steps.iter().filter(|s| s.status == StepStatus::Applied)
+    }
+
+    /// 調査日により適用しなかったステップ
+    pub fn skipped_steps(&self) -> impl Iterator<Item = &TransformStep> {
+        self.steps.iter().filter(|s| s.status == StepStatus::SkippedSurveyDate)
+    }
+
+    /// パラメータ範囲外による未補正があるか
+    ///
+    /// 地震補正パラメータは被災地域のみを対象とするため、個々の地震補正が
+    /// 範囲外であることは異常ではない。全国を対象とするジオイド補正が範囲外の場合と、
+    /// PatchJGD系パラメータが1つも地点を含まなかった場合を範囲外とみなす。
+    pub fn is_out_of_coverage(&self) -> bool {
+        self.patch_fallback
+            || self
+                .steps
+                .iter()
+                .any(|s| s.kind == StepKind::Geoid && s.status == StepStatus::OutOfCoverage)
+    }
+}
+
+/// 読み込んだPatchJGD系パラメータファイル
+pub(crate) struct ParamFile {
+    /// ファイル名
+    pub(crate) file_name: String,
+    /// 種別
+    pub(crate) kind: StepKind,
+    /// 対応する地震の発生日（地震補正でない場合はNone）
+    pub(crate) earthquake_date: Option<SurveyStartDate>,
+    /// メッシュコードごとのパラメータ
+    pub(crate) parameters: ParamTable,
+}
+
+impl ParamFile {
+    /// 調査日に対してこのファイルを適用するか
+    fn applies(&self, survey_date: Option<SurveyStartDate>) -> bool {
+        earthquake_applies(self.earthquake_date, survey_date)
+    }
+
+    /// 地点を含むメッシュの四隅すべてにパラメータがあるか
+    fn covers(&self, cell: Option<&MeshCell>) -> bool {
+        cell.is_some_and(|cell| {
+            cell_corners(cell)
+                .iter()
+                .all(|code| self.parameters.contains(*code))
+        })
+    }
+
+    /// 地点に対するステップの来歴
+    fn step(&self, cell: Option<&MeshCell>, survey_date: Option<SurveyStartDate>) -> TransformStep {
+        let status = if !self.applies(survey_date) {
+            StepStatus::SkippedSurveyDate
+        } else if self.covers(cell) {
+            StepStatus::Applied
+        } else {
+            StepStatus::OutOfCoverage
+        };
+        TransformStep {
+            kind: self.kind,
+            file_name: Some(self.file_name.clone()),
+            status,
+        }
+    }
+}
+
+/// PatchJGD系パラメータファイルの内容をパース
+///
+/// ファイル名でフォーマットを判別する。ジオイド補正以外のファイルは
+/// 地震カタログに登録されている必要がある。
+///
+/// # Arguments
+/// * `file_name` - ファイル名
+/// * `content` - ファイルの内容
+/// * `elevation` - 標高補正ファイルか（`elevation/` ディレクトリのファイル）
+/// * `catalogue` - 地震カタログ
+pub(crate) fn parse_param_file(
+    file_name: &str,
+    content: &str,
+    elevation: bool,
+    catalogue: &EarthquakeCatalogue,
+) -> Result<ParamFile, TransformError> {
+    let (format, kind) = if !elevation {
+        (Format::PatchJGD, StepKind::PatchHorizontal)
+    } else if file_name.contains("hyoko") {
+        (Format::HyokoRev, StepKind::Geoid) // ジオイドモデル改定
+    } else {
+        (Format::PatchJGD_H, StepKind::PatchElevation) // 地震（標高）
+    };
+
+    // ジオイド補正以外は地震補正ファイル → カタログに必須
+    let earthquake_date = match kind {
+        StepKind::Geoid => None,
+        _ => Some(
+            catalogue
+                .find(file_name)
+                .ok_or_else(|| TransformError::UnknownPatchFile(file_name.to_string()))?
+                .date,
+        ),
+    };
+
+    let par_data = ParData::from_str(content, format)
+        .map_err(|e| TransformError::Parse(format!("{}: {}", file_name, e)))?;
+
+    Ok(ParamFile {
+        file_name: file_name.to_string(),
+        kind,
+        earthquake_date,
+        parameters: ParamTable::Parsed(par_data.parameter),
+    })
+}
+
+/// メッシュの四隅 (南西, 南東, 北西, 北東) のメッシュコード
+fn cell_corners(cell: &MeshCell) -> [u32; 4] {
+    [
+        cell.south_west().to_meshcode(),
+        cell.south_east().to_meshcode(),
+        cell.north_west().to_meshcode(),
+        cell.north_east().to_meshcode(),
+    ]
+}
+
+/// パスからファイル名を取得
+pub(crate) fn file_name_of(path: &Path) -> String {
+    path.file_name()
+        .and_then(|s| s.to_str())
+        .unwrap_or_default()
+        .to_string()
 }
 
 /// 調査開始日（年月日）
 ///
 /// 地震補正の適用判定に使用。調査開始日が地震発生日より前の場合のみ補正を適用する。
-#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+/// 孔内水位の測定年月日など、ボーリングデータの年月日にも使う。
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize)]
 pub struct SurveyStartDate {
     pub year: i32,
     pub month: u32,
@@ -57,17 +282,80 @@ impl SurveyStartDate {
         Some(Self { year, month, day })
     }
 
+    /// "YYYY-MM-DD"・"YYYY/MM/DD"・"YYYYMMDD" 形式の文字列からパース
+    ///
+    /// 月・日が範囲外の場合はNoneを返す
+    pub fn parse(value: Option<&str>) -> Option<Self> {
+        let trimmed = value?.trim();
+        let parts: Vec<&str> = trimmed.split(['-', '/']).collect();
+        let (year, month, day) = match parts.as_slice() {
+            [y, m, d] => (*y, *m, *d),
+            [s] if s.len() == 8 && s.is_ascii() => (&s[0..4], &s[4..6], &s[6..8]),
+            _ => return None,
+        };
+        Self::from_parts(Some(year), Some(month), Some(day))
+    }
+
     /// 年月日を個別にパース
+    ///
+    /// 前後の空白は除く。月・日が範囲外の場合はNoneを返す
     pub fn from_parts(year: Option<&str>, month: Option<&str>, day: Option<&str>) -> Option<Self> {
-        Some(Self {
-            year: year?.parse().ok()?,
-            month: month?.parse().ok()?,
-            day: day?.parse().ok()?,
-        })
+        let date = Self {
+            year: year?.trim().parse().ok()?,
+            month: month?.trim().parse().ok()?,
+            day: day?.trim().parse().ok()?,
+        };
+        ((1..=12).contains(&date.month) && (1..=31).contains(&date.day)).then_some(date)
     }
 }
 
-/// 地震情報
+/// 調査期間の開始日を取得するためのトレイト
+pub trait SurveyDate {
+    /// 調査期間の開始日
+    ///
+    /// # Example
+    /// ```ignore
+    /// let result = transformer.transform_on(lat, lng, 0.0, "01", boring.survey_start_date())?;
+    /// ```
+    fn survey_start_date(&self) -> Option<SurveyStartDate>;
+}
+
+impl SurveyDate for crate::boring_structs_400::Boring400 {
+    fn survey_start_date(&self) -> Option<SurveyStartDate> {
+        SurveyStartDate::parse(self.title.survey_period.start_date.as_deref())
+    }
+}
+
+impl SurveyDate for crate::boring_structs_300::Boring300 {
+    fn survey_start_date(&self) -> Option<SurveyStartDate> {
+        SurveyStartDate::parse(self.title.survey_period.start_date.as_deref())
+    }
+}
+
+impl SurveyDate for crate::boring_structs_210::Boring210 {
+    fn survey_start_date(&self) -> Option<SurveyStartDate> {
+        SurveyStartDate::parse(self.title.survey_period.start_date.as_deref())
+    }
+}
+
+impl SurveyDate for crate::boring_structs_200::Boring200 {
+    fn survey_start_date(&self) -> Option<SurveyStartDate> {
+        SurveyStartDate::parse(self.title.survey_period.start_date.as_deref())
+    }
+}
+
+impl SurveyDate for crate::boring_structs_110::Boring110 {
+    fn survey_start_date(&self) -> Option<SurveyStartDate> {
+        let period = &self.title.survey_period;
+        SurveyStartDate::from_parts(
+            period.start_year.as_deref(),
+            period.start_month.as_deref(),
+            period.start_day.as_deref(),
+        )
+    }
+}
+
+/// 組み込みの地震情報
 struct EarthquakeInfo {
     /// ファイル名に含まれるパターン
     file_pattern: &'static str,
@@ -75,7 +363,7 @@ struct EarthquakeInfo {
     date: SurveyStartDate,
 }
 
-/// 地震補正パラメータの一覧（発生日順）
+/// 組み込みの地震補正パラメータの一覧（発生日順）
 /// 調査日がこの日付より前の場合のみ、対応するパラメータを適用する
 const EARTHQUAKES: &[EarthquakeInfo] = &[
     EarthquakeInfo {
@@ -108,16 +396,286 @@ const EARTHQUAKES: &[EarthquakeInfo] = &[
     },
 ];
 
-/// ファイル名が地震補正パラメータかどうかを判定し、適用すべきかを返す
-fn should_apply_earthquake_correction(file_name: &str, survey_date: Option<SurveyStartDate>) -> bool {
-    // 地震補正ファイルでない場合は常に適用（ジオイド補正など）
-    let earthquake = EARTHQUAKES.iter().find(|eq| file_name.contains(eq.file_pattern));
+/// 地震カタログのマニフェストファイル名（パッチディレクトリ直下）
+pub const EARTHQUAKE_MANIFEST: &str = "earthquakes.csv";
+
+/// 地震情報
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct Earthquake {
+    /// ファイル名に含まれるパターン
+    pub file_pattern: String,
+    /// 地震発生日
+    pub date: SurveyStartDate,
+    /// 地震名
+    pub name: Option<String>,
+}
+
+/// 地震補正パラメータのカタログ
+///
+/// パッチファイル名と地震発生日の対応を持つ。
+/// マニフェストは1行1地震のCSVで、`#` 以降はコメント。
+///
+/// ```text
+/// # ファイル名パターン,発生日,地震名(任意)
+/// noto2024,2024-01-01,令和6年能登半島地震
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct EarthquakeCatalogue {
+    earthquakes: Vec<Earthquake>,
+}
+
+impl Default for EarthquakeCatalogue {
+    fn default() -> Self {
+        Self::builtin()
+    }
+}
+
+impl EarthquakeCatalogue {
+    /// 組み込みの地震一覧
+    pub fn builtin() -> Self {
+        Self {
+            earthquakes: EARTHQUAKES
+                .iter()
+                .map(|eq| Earthquake {
+                    file_pattern: eq.file_pattern.to_string(),
+                    date: eq.date,
+                    name: None,
+                })
+                .collect(),
+        }
+    }
+
+    /// マニフェストの内容をパース
+    pub fn parse(content: &str) -> Result<Self, TransformError> {
+        let mut earthquakes = Vec::new();
+        for (index, line) in content.lines().enumerate() {
+            let line = line.split('#').next().unwrap_or_default().trim();
+            if line.is_empty() {
+                continue;
+            }
+            let fields: Vec<&str> = line.split(',').map(str::trim).collect();
+            let (pattern, date) = match fields.as_slice() {
+                [pattern, date, ..] if !pattern.is_empty() => (*pattern, *date),
+                _ => {
+                    return Err(TransformError::Parse(format!(
+                        "{} {}行目: ファイル名パターンと発生日が必要です",
+                        EARTHQUAKE_MANIFEST,
+                        index + 1
+                    )));
+                }
+            };
+            let date = SurveyStartDate::parse(Some(date)).ok_or_else(|| {
+                TransformError::Parse(format!(
+                    "{} {}行目: 発生日が不正です: {}",
+                    EARTHQUAKE_MANIFEST,
+                    index + 1,
+                    date
+                ))
+            })?;
+            earthquakes.push(Earthquake {
+                file_pattern: pattern.to_string(),
+                date,
+                name: fields.get(2).filter(|s| !s.is_empty()).map(|s| s.to_string()),
+            });
+        }
+        Ok(Self { earthquakes })
+    }
+
+    /// マニフェストファイルを読み込む
+    pub fn load<P: AsRef<Path>>(path: P) -> Result<Self, TransformError> {
+        Self::parse(&read_param_file(path)?)
+    }
+
+    /// 組み込みの一覧に、パッチディレクトリのマニフェスト（存在する場合）を追加
+    pub fn for_patch_dir<P: AsRef<Path>>(patch_dir: P) -> Result<Self, TransformError> {
+        let mut catalogue = Self::builtin();
+        let manifest = patch_dir.as_ref().join(EARTHQUAKE_MANIFEST);
+        if manifest.exists() {
+            catalogue.merge(Self::load(manifest)?);
+        }
+        Ok(catalogue)
+    }
+
+    /// 別のカタログを追加（同じパターンは上書き）
+    pub fn merge(&mut self, other: EarthquakeCatalogue) {
+        for eq in other.earthquakes {
+            match self
+                .earthquakes
+                .iter_mut()
+                .find(|e| e.file_pattern == eq.file_pattern)
+            {
+                Some(existing) => *existing = eq,
+                None => self.earthquakes.push(eq),
+            }
+        }
+    }
+
+    /// 地震を追加
+    pub fn push(&mut self, earthquake: Earthquake) {
+        self.merge(Self { earthquakes: vec![earthquake] });
+    }
+
+    /// 登録されている地震
+    pub fn earthquakes(&self) -> &[Earthquake] {
+        &self.earthquakes
+    }
+
+    /// ファイル名に対応する地震（複数一致する場合は最も長いパターン）
+    pub fn find(&self, file_name: &str) -> Option<&Earthquake> {
+        self.earthquakes
+            .iter()
+            .filter(|eq| file_name.contains(eq.file_pattern.as_str()))
+            .max_by_key(|eq| eq.file_pattern.len())
+    }
+}
+
+/// GRS80 長半径 (m)
+const GRS80_A: f64 = 6_378_137.0;
+
+/// GRS80 第一離心率の2乗
+const GRS80_E2: f64 = 0.006_694_380_022_900_79;
+
+/// 逆変換結果
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct InverseTransform {
+    /// 緯度 (度, 10進数)
+    pub latitude: f64,
+    /// 経度 (度, 10進数)
+    pub longitude: f64,
+    /// 標高 (m)
+    pub altitude: f64,
+    /// 逆変換結果を再度JGD2011へ順変換したときの水平残差 (m)
+    pub residual: f64,
+    /// 同じく標高の残差 (m)
+    pub altitude_residual: f64,
+}
+
+/// 逆変換の反復回数の上限
+const BACKWARD_MAX_ITERATION: usize = 8;
+
+/// 逆変換の収束判定に使う誤差の上限 (度)
+const BACKWARD_MAX_ERROR: f64 = 5e-14;
+
+/// 緯度・経度のパラメータの単位 (秒) から度への換算
+const SECONDS_PER_DEGREE: f64 = 3600.0;
 
-    match (earthquake, survey_date) {
+/// 3次メッシュ (1kmメッシュ) の緯度方向・経度方向の大きさ (度)
+const CELL_HEIGHT: f64 = 1.0 / 120.0;
+const CELL_WIDTH: f64 = 1.0 / 80.0;
+
+/// 地点を含む3次メッシュの四隅で、パラメータ表のパラメータを合算する
+///
+/// 四隅のいずれかにどの表のパラメータもない場合、または地点がメッシュの範囲外の場合はNone。
+fn corner_parameters(tables: &[&ParamTable], point: &Point) -> Option<(MeshCell, [Parameter; 4])> {
+    let cell = MeshCell::try_from_point(point, MeshUnit::One)?;
+    let mut sums = std::array::from_fn(|_| Parameter::new(0.0, 0.0, 0.0));
+    for (sum, code) in sums.iter_mut().zip(cell_corners(&cell)) {
+        let mut found = false;
+        for param in tables.iter().filter_map(|t| t.get(code)) {
+            sum.latitude += param.latitude;
+            sum.longitude += param.longitude;
+            sum.altitude += param.altitude;
+            found = true;
+        }
+        if !found {
+            return None;
+        }
+    }
+    Some((cell, sums))
+}
+
+/// パラメータ表の補正量を、メッシュ四隅のパラメータから双一次補間する
+///
+/// 複数の表を渡した場合は四隅ごとにパラメータを合算してから補間する。
+/// 四隅のいずれかにパラメータがない場合はNone。
+fn interpolate(tables: &[&ParamTable], point: &Point) -> Option<Correction> {
+    let (cell, [sw, se, nw, ne]) = corner_parameters(tables, point)?;
+    // y: 緯度方向, x: 経度方向
+    let (y, x) = cell.position(point);
+    Some(Correction {
+        latitude: bilinear(sw.latitude, se.latitude, nw.latitude, ne.latitude, y, x) / SECONDS_PER_DEGREE,
+        longitude: bilinear(sw.longitude, se.longitude, nw.longitude, ne.longitude, y, x)
+            / SECONDS_PER_DEGREE,
+        altitude: bilinear(sw.altitude, se.altitude, nw.altitude, ne.altitude, y, x),
+    })
+}
+
+/// パラメータ表の補正を逆にたどる
+///
+/// 変換先 `q` に対して `p + 補正量(p) = q` となる `p` を、双一次補間の偏微分を
+/// ヤコビ行列とするニュートン法で求める。標高は求めた `p` の補正量を差し引く。
+/// 反復中にパラメータの範囲外に出た場合は `TransformError::OutOfCoverage`、
+/// 収束しない場合は `TransformError::Transform`。
+fn backward(tables: &[&ParamTable], point: &Point, file_name: &str) -> Result<Point, TransformError> {
+    let out_of_coverage = || TransformError::OutOfCoverage(format!("{}: パラメータの範囲外です", file_name));
+
+    let (mut lat, mut lon) = (point.latitude, point.longitude);
+    for _ in 0..BACKWARD_MAX_ITERATION {
+        let current = Point::new_unchecked(lat, lon, 0.0);
+        let (cell, [sw, se, nw, ne]) = corner_parameters(tables, &current).ok_or_else(out_of_coverage)?;
+        let (y, x) = cell.position(&current);
+
+        let corr_lat = bilinear(sw.latitude, se.latitude, nw.latitude, ne.latitude, y, x) / SECONDS_PER_DEGREE;
+        let corr_lon = bilinear(sw.longitude, se.longitude, nw.longitude, ne.longitude, y, x) / SECONDS_PER_DEGREE;
+
+        // f(p) = q - p - 補正量(p)
+        let f_lat = point.latitude - (lat + corr_lat);
+        let f_lon = point.longitude - (lon + corr_lon);
+        if f_lat.abs() < BACKWARD_MAX_ERROR && f_lon.abs() < BACKWARD_MAX_ERROR {
+            let altitude = bilinear(sw.altitude, se.altitude, nw.altitude, ne.altitude, y, x);
+            return Ok(Point::new_unchecked(lat, lon, point.altitude - altitude));
+        }
+
+        // ヤコビ行列 (補正量の偏微分は位置 0〜1 あたりの値を度あたりに換算する)
+        let (lat_y, lat_x) = bilinear_gradient(sw.latitude, se.latitude, nw.latitude, ne.latitude, y, x);
+        let (lon_y, lon_x) = bilinear_gradient(sw.longitude, se.longitude, nw.longitude, ne.longitude, y, x);
+        let a = -1.0 - lat_y / SECONDS_PER_DEGREE / CELL_HEIGHT;
+        let b = -lat_x / SECONDS_PER_DEGREE / CELL_WIDTH;
+        let c = -lon_y / SECONDS_PER_DEGREE / CELL_HEIGHT;
+        let d = -1.0 - lon_x / SECONDS_PER_DEGREE / CELL_WIDTH;
+        let det = a * d - b * c;
+        if !det.is_normal() {
+            break;
+        }
+
+        lat -= (d * f_lat - b * f_lon) / det;
+        lon -= (a * f_lon - c * f_lat) / det;
+    }
+    Err(TransformError::Transform("逆変換が収束しません".to_string()))
+}
+
+/// 近接する2点間の水平距離 (m)
+fn horizontal_distance(lat1: f64, lon1: f64, lat2: f64, lon2: f64) -> f64 {
+    let phi = ((lat1 + lat2) / 2.0).to_radians();
+    let w = (1.0 - GRS80_E2 * phi.sin().powi(2)).sqrt();
+    // 子午線曲率半径と卯酉線曲率半径
+    let m = GRS80_A * (1.0 - GRS80_E2) / w.powi(3);
+    let n = GRS80_A / w;
+    let dy = (lat2 - lat1).to_radians() * m;
+    let dx = (lon2 - lon1).to_radians() * n * phi.cos();
+    dx.hypot(dy)
+}
+
+/// 双一次補間 (y: 緯度方向, x: 経度方向の位置 0〜1)
+fn bilinear(sw: f64, se: f64, nw: f64, ne: f64, y: f64, x: f64) -> f64 {
+    sw * (1.0 - x) * (1.0 - y) + se * x * (1.0 - y) + nw * (1.0 - x) * y + ne * x * y
+}
+
+/// 双一次補間の偏微分 (y方向, x方向)
+fn bilinear_gradient(sw: f64, se: f64, nw: f64, ne: f64, y: f64, x: f64) -> (f64, f64) {
+    ((nw - sw) * (1.0 - x) + (ne - se) * x, (se - sw) * (1.0 - y) + (ne - nw) * y)
+}
+
+/// 地震の発生日と調査日から、補正を適用すべきかを返す
+fn earthquake_applies(
+    earthquake_date: Option<SurveyStartDate>,
+    survey_date: Option<SurveyStartDate>,
+) -> bool {
+    match (earthquake_date, survey_date) {
         // 地震ファイルで、調査日が指定されている場合
-        (Some(eq), Some(date)) => {
+        (Some(eq_date), Some(date)) => {
             // 調査日が地震発生日より前の場合のみ適用
-            date < eq.date
+            date < eq_date
         }
         // 地震ファイルだが調査日が不明 → 安全のため適用しない
         (Some(_), None) => false,
@@ -145,19 +703,32 @@ pub fn read_param_file<P: AsRef<Path>>(path: P) -> Result<String, std::io::Error
 /// Tokyo Datum/JGD2000からJGD2011への変換を行う。
 /// - Step 1: Tokyo Datum → JGD2000 (TKY2JGD)
 /// - Step 2: JGD2000 → JGD2011 (PatchJGD統合)
-/// - Step 3: JGD2011 → WGS84 (proj)
+/// - Step 3: JGD2011 → WGS84 (proj、または組み込みの変換)
+///
+/// Step 2 のパラメータファイルは調査日によらずすべて保持し、変換のたびに
+/// 調査日から適用するファイルを選んで補正量を合算する。
+///
+/// 構築後は読み取り専用のため、`Send + Sync` として複数スレッドで共有できる。
 pub struct JgdTransformer {
-    /// Tokyo Datum → JGD2000 変換器
-    tky_tf: Transformer<ParData>,
-    /// JGD2000 → JGD2011 統合変換器 (水平+垂直)
-    unified_tf: Transformer<ParData>,
-    /// JGD2011 → WGS84 変換器 (キャッシュ)
-    jgd2011_to_wgs84: proj::Proj,
+    /// Tokyo Datum → JGD2000 のパラメータ
+    tky_parameters: ParamTable,
+    /// JGD2000 → JGD2011 パラメータファイル (ファイル名順)
+    param_files: Vec<ParamFile>,
+    /// TKY2JGDパラメータファイル名
+    tky_file_name: String,
+    /// 調査日を指定しない変換で使う調査日
+    survey_date: Option<SurveyStartDate>,
+    /// セミ・ダイナミック補正 (任意)
+    semi_dynamic: Option<SemiDynamic>,
 }
 
 impl JgdTransformer {
     /// パラメータファイルを読み込んで変換器を初期化（調査日指定あり）
     ///
+    /// 指定した調査日は、調査日を引数に取らないメソッドの既定値として使われる。
+    /// 調査日の異なる多数のファイルを変換する場合は [`JgdTransformer::new`] で
+    /// 初期化し、[`JgdTransformer::transform_on`] などで調査日を指定する。
+    ///
     /// # Arguments
     /// * `tky_path` - TKY2JGD.par のパス
     /// * `patch_dir` - PatchJGD系ファイルが入ったディレクトリのパス
@@ -182,61 +753,121 @@ impl JgdTransformer {
         patch_dir: P,
         survey_date: SurveyStartDate,
     ) -> Result<Self, TransformError> {
-        let tky_content = read_param_file(&tky_path)?;
-        let tky_tf = Transformer::from_str(&tky_content, Format::TKY2JGD)
-            .map_err(|e| TransformError::Parse(format!("TKY2JGD parse error: {}", e)))?;
-
-        let unified_tf = Self::create_unified_transformer(patch_dir, Some(survey_date))?;
-
-        let jgd2011_to_wgs84 = proj::Proj::new_known_crs("EPSG:6668", "EPSG:4326", None)
-            .map_err(|e| TransformError::Transform(format!("proj init error: {}", e)))?;
-
-        Ok(Self { tky_tf, unified_tf, jgd2011_to_wgs84 })
+        let mut transformer = Self::new(tky_path, patch_dir)?;
+        transformer.survey_date = Some(survey_date);
+        Ok(transformer)
     }
 
     /// パラメータファイルを読み込んで変換器を初期化（調査日なし）
     ///
-    /// **注意**: 調査日が不明な場合、地震補正は適用されません。
+    /// **注意**: 調査日を指定しない変換では、地震補正は適用されません。
     /// ジオイド補正のみが適用されます。
     ///
     /// # Arguments
     /// * `tky_path` - TKY2JGD.par のパス
     /// * `patch_dir` - PatchJGD系ファイルが入ったディレクトリのパス
+    ///
+    /// # Errors
+    /// 地震カタログに登録されていない地震補正ファイルがある場合は
+    /// `TransformError::UnknownPatchFile`
     pub fn new<P: AsRef<Path>>(tky_path: P, patch_dir: P) -> Result<Self, TransformError> {
-        let tky_content = read_param_file(&tky_path)?;
-        let tky_tf = Transformer::from_str(&tky_content, Format::TKY2JGD)
-            .map_err(|e| TransformError::Parse(format!("TKY2JGD parse error: {}", e)))?;
+        let catalogue = EarthquakeCatalogue::for_patch_dir(&patch_dir)?;
+        Self::with_catalogue(tky_path, patch_dir, &catalogue)
+    }
 
-        // 調査日なしの場合、地震補正は適用しない（ジオイド補正のみ）
-        let unified_tf = Self::create_unified_transformer(patch_dir, None)?;
+    /// 地震カタログを指定して変換器を初期化（調査日なし）
+    ///
+    /// パッチディレクトリのマニフェストは読まず、指定したカタログだけを使う。
+    ///
+    /// # Example
+    /// ```ignore
+    /// let mut catalogue = EarthquakeCatalogue::builtin();
+    /// catalogue.merge(EarthquakeCatalogue::load("config/earthquakes.csv")?);
+    /// let transformer = JgdTransformer::with_catalogue("params/TKY2JGD.par", "params/patch_files", &catalogue)?;
+    /// ```
+    pub fn with_catalogue<P: AsRef<Path>>(
+        tky_path: P,
+        patch_dir: P,
+        catalogue: &EarthquakeCatalogue,
+    ) -> Result<Self, TransformError> {
+        let mut cache = ParamCache::load_tky(tky_path)?;
+        cache.param_files = Self::load_patch_files(patch_dir, catalogue)?;
+        Self::from_cache(cache)
+    }
 
-        let jgd2011_to_wgs84 = proj::Proj::new_known_crs("EPSG:6668", "EPSG:4326", None)
-            .map_err(|e| TransformError::Transform(format!("proj init error: {}", e)))?;
+    /// メモリ上のパラメータファイルの内容から変換器を初期化（調査日なし）
+    ///
+    /// ファイルシステムを使わないため、`include_str!` で埋め込んだパラメータや
+    /// ネットワークから取得したパラメータを使える。
+    ///
+    /// # Example
+    /// ```ignore
+    /// let patches = [PatchSource::horizontal("touhokutaiheiyouoki2011.par", TOUHOKU)];
+    /// let transformer = JgdTransformer::from_sources(
+    ///     "TKY2JGD.par",
+    ///     include_str!("../params/TKY2JGD.par"),
+    ///     &patches,
+    ///     &EarthquakeCatalogue::builtin(),
+    /// )?;
+    /// ```
+    pub fn from_sources(
+        tky_file_name: &str,
+        tky_content: &str,
+        patches: &[PatchSource<'_>],
+        catalogue: &EarthquakeCatalogue,
+    ) -> Result<Self, TransformError> {
+        Self::from_cache(ParamCache::from_sources(tky_file_name, tky_content, patches, catalogue)?)
+    }
 
-        Ok(Self { tky_tf, unified_tf, jgd2011_to_wgs84 })
+    /// パラメータキャッシュから変換器を初期化（調査日なし）
+    ///
+    /// パラメータファイルのパースを省略できるため、起動が速い。
+    ///
+    /// # Example
+    /// ```ignore
+    /// // 一度だけ作成
+    /// ParamCache::from_dir("params/TKY2JGD.par", "params/patch_files")?.write("params/jgd.cache")?;
+    ///
+    /// // 以降はキャッシュから起動
+    /// let transformer = JgdTransformer::from_cache(ParamCache::load("params/jgd.cache")?)?;
+    /// ```
+    pub fn from_cache(cache: ParamCache) -> Result<Self, TransformError> {
+        // WGS84への変換が使えることを構築時に確認する
+        wgs84::ensure_available()?;
+
+        Ok(Self {
+            tky_parameters: cache.tky_parameters,
+            param_files: cache.param_files,
+            tky_file_name: cache.tky_file_name,
+            survey_date: None,
+            semi_dynamic: None,
+        })
     }
 
     /// TKY2JGDのみで変換器を初期化 (PatchJGDファイルなし)
     ///
     /// JGD2000→JGD2011変換が不要な場合に使用
     pub fn new_tky_only<P: AsRef<Path>>(tky_path: P) -> Result<Self, TransformError> {
-        let tky_content = read_param_file(&tky_path)?;
-        let tky_tf = Transformer::from_str(&tky_content, Format::TKY2JGD)
-            .map_err(|e| TransformError::Parse(format!("TKY2JGD parse error: {}", e)))?;
-
-        // 空の統合変換器を作成
-        let empty_params: HashMap<u32, Parameter> = HashMap::new();
-        let empty_data =
-            ParData::with_description(Format::PatchJGD_HV, empty_params, "Empty".to_string());
-        let unified_tf = Transformer::new(empty_data);
+        Self::from_cache(ParamCache::load_tky(tky_path)?)
+    }
 
-        let jgd2011_to_wgs84 = proj::Proj::new_known_crs("EPSG:6668", "EPSG:4326", None)
-            .map_err(|e| TransformError::Transform(format!("proj init error: {}", e)))?;
+    /// 調査日を指定しない変換で使う調査日
+    pub fn survey_date(&self) -> Option<SurveyStartDate> {
+        self.survey_date
+    }
 
-        Ok(Self { tky_tf, unified_tf, jgd2011_to_wgs84 })
+    /// セミ・ダイナミック補正パラメータを設定
+    ///
+    /// # Example
+    /// ```ignore
+    /// let mut transformer = JgdTransformer::new("params/TKY2JGD.par", "params/patch_files")?;
+    /// transformer.set_semi_dynamic(SemiDynamic::load("params/semidyna")?);
+    /// ```
+    pub fn set_semi_dynamic(&mut self, semi_dynamic: SemiDynamic) {
+        self.semi_dynamic = Some(semi_dynamic);
     }
 
-    /// ディレクトリ内のパラメータファイルを加算マージして1つのTransformerを作る
+    /// ディレクトリ内のパラメータファイルをすべて読み込む
     ///
     /// # Directory Structure
     /// ```text
@@ -250,84 +881,50 @@ impl JgdTransformer {
     ///     └── ...
     /// ```
     ///
+    /// 地震補正の適用判定は変換時に行うため、ここでは調査日によらず読み込む。
+    /// ジオイド補正以外のファイルは、地震カタログに登録されている必要がある。
+    ///
     /// # Arguments
     /// * `dir_path` - パラメータファイルのルートディレクトリ
-    /// * `survey_date` - 調査日（Noneの場合、地震補正は適用しない）
-    fn create_unified_transformer<P: AsRef<Path>>(
+    /// * `catalogue` - 地震カタログ
+    pub(crate) fn load_patch_files<P: AsRef<Path>>(
         dir_path: P,
-        survey_date: Option<SurveyStartDate>,
-    ) -> Result<Transformer<ParData>, TransformError> {
+        catalogue: &EarthquakeCatalogue,
+    ) -> Result<Vec<ParamFile>, TransformError> {
         let dir = dir_path.as_ref();
 
-        // ディレクトリが存在しない場合は空のTransformerを返す
+        // ディレクトリが存在しない場合はパラメータなし
         if !dir.exists() {
-            let empty_params: HashMap<u32, Parameter> = HashMap::new();
-            let empty_data = ParData::with_description(
-                Format::PatchJGD_HV,
-                empty_params,
-                "No patch files".to_string(),
-            );
-            return Ok(Transformer::new(empty_data));
+            return Ok(Vec::new());
         }
 
-        let mut unified_params: HashMap<u32, Parameter> = HashMap::new();
-        let mut description = String::from("Unified Parameter Set:\n");
-        if let Some(date) = survey_date {
-            description.push_str(&format!(
-                "Survey date: {}/{}/{}\n",
-                date.year, date.month, date.day
-            ));
-        } else {
-            description.push_str("Survey date: unknown (earthquake corrections disabled)\n");
-        }
-        let mut loaded_count = 0;
-
-        // horizontal/ と elevation/ サブディレクトリをスキャン
-        let horizontal_dir = dir.join("horizontal");
-        let elevation_dir = dir.join("elevation");
+        let mut param_files: Vec<ParamFile> = Vec::new();
 
         // 水平補正ファイルを読み込み
+        let horizontal_dir = dir.join("horizontal");
         if horizontal_dir.exists() {
-            description.push_str("\n[Horizontal corrections]\n");
-            loaded_count += Self::load_param_files(
-                &horizontal_dir,
-                Format::PatchJGD,
-                survey_date,
-                &mut unified_params,
-                &mut description,
-            )?;
+            Self::load_param_files(&horizontal_dir, false, catalogue, &mut param_files)?;
         }
 
         // 標高補正ファイルを読み込み
+        let elevation_dir = dir.join("elevation");
         if elevation_dir.exists() {
-            description.push_str("\n[Elevation corrections]\n");
-            loaded_count += Self::load_param_files_elevation(
-                &elevation_dir,
-                survey_date,
-                &mut unified_params,
-                &mut description,
-            )?;
+            Self::load_param_files(&elevation_dir, true, catalogue, &mut param_files)?;
         }
 
-        if loaded_count == 0 {
-            description = "No patch files loaded".to_string();
-        }
+        // 来歴の出力順を安定させる
+        param_files.sort_by(|a, b| a.file_name.cmp(&b.file_name));
 
-        let unified_data =
-            ParData::with_description(Format::PatchJGD_HV, unified_params, description);
-        Ok(Transformer::new(unified_data))
+        Ok(param_files)
     }
 
-    /// 指定ディレクトリからパラメータファイルを読み込む（水平用）
+    /// 指定ディレクトリからパラメータファイルを読み込む
     fn load_param_files(
         dir: &Path,
-        format: Format,
-        survey_date: Option<SurveyStartDate>,
-        unified_params: &mut HashMap<u32, Parameter>,
-        description: &mut String,
-    ) -> Result<usize, TransformError> {
-        let mut loaded_count = 0;
-
+        elevation: bool,
+        catalogue: &EarthquakeCatalogue,
+        param_files: &mut Vec<ParamFile>,
+    ) -> Result<(), TransformError> {
         for entry in fs::read_dir(dir)? {
             let entry = entry?;
             let path = entry.path();
@@ -341,89 +938,11 @@ impl JgdTransformer {
                 None => continue,
             };
 
-            // 地震補正の適用判定
-            if !should_apply_earthquake_correction(&file_name, survey_date) {
-                description.push_str(&format!("  - {} (skipped: survey after earthquake)\n", file_name));
-                continue;
-            }
-
             let content = read_param_file(&path)?;
-            let par_data = ParData::from_str(&content, format)
-                .map_err(|e| TransformError::Parse(format!("{}: {}", file_name, e)))?;
-
-            for (mesh_code, param) in par_data.parameter {
-                unified_params
-                    .entry(mesh_code)
-                    .and_modify(|existing| {
-                        existing.latitude += param.latitude;
-                        existing.longitude += param.longitude;
-                        existing.altitude += param.altitude;
-                    })
-                    .or_insert(param);
-            }
-
-            description.push_str(&format!("  - {} (applied)\n", file_name));
-            loaded_count += 1;
+            param_files.push(parse_param_file(&file_name, &content, elevation, catalogue)?);
         }
 
-        Ok(loaded_count)
-    }
-
-    /// 指定ディレクトリからパラメータファイルを読み込む（標高用）
-    fn load_param_files_elevation(
-        dir: &Path,
-        survey_date: Option<SurveyStartDate>,
-        unified_params: &mut HashMap<u32, Parameter>,
-        description: &mut String,
-    ) -> Result<usize, TransformError> {
-        let mut loaded_count = 0;
-
-        for entry in fs::read_dir(dir)? {
-            let entry = entry?;
-            let path = entry.path();
-
-            if path.extension().and_then(|s| s.to_str()) != Some("par") {
-                continue;
-            }
-
-            let file_name = match path.file_name().and_then(|s| s.to_str()) {
-                Some(name) => name.to_string(),
-                None => continue,
-            };
-
-            // 地震補正の適用判定
-            if !should_apply_earthquake_correction(&file_name, survey_date) {
-                description.push_str(&format!("  - {} (skipped: survey after earthquake)\n", file_name));
-                continue;
-            }
-
-            // ファイル名でフォーマットを判別
-            let format = if file_name.contains("hyoko") {
-                Format::HyokoRev // ジオイドモデル改定
-            } else {
-                Format::PatchJGD_H // 地震（標高）
-            };
-
-            let content = read_param_file(&path)?;
-            let par_data = ParData::from_str(&content, format)
-                .map_err(|e| TransformError::Parse(format!("{}: {}", file_name, e)))?;
-
-            for (mesh_code, param) in par_data.parameter {
-                unified_params
-                    .entry(mesh_code)
-                    .and_modify(|existing| {
-                        existing.latitude += param.latitude;
-                        existing.longitude += param.longitude;
-                        existing.altitude += param.altitude;
-                    })
-                    .or_insert(param);
-            }
-
-            description.push_str(&format!("  - {} (applied)\n", file_name));
-            loaded_count += 1;
-        }
-
-        Ok(loaded_count)
+        Ok(())
     }
 
     /// 座標をJGD2011に変換 (水平座標のみ)
@@ -486,59 +1005,463 @@ impl JgdTransformer {
         // Step 1-2: Tokyo/JGD2000 → JGD2011
         let (lat_jgd2011, lng_jgd2011) = self.transform_horizontal(lat, lon, system_code)?;
 
-        // Step 3: JGD2011 → WGS84 (キャッシュ済みprojを使用)
-        self.jgd2011_to_wgs84.convert((lng_jgd2011, lat_jgd2011)).ok()
+        // Step 3: JGD2011 → WGS84
+        wgs84::jgd2011_to_wgs84(lng_jgd2011, lat_jgd2011).ok()
     }
 
-    /// 内部変換処理
-    fn transform_point(
+    /// JGD2011の座標を指定の測地系に逆変換 (水平座標のみ)
+    ///
+    /// JGD2011 → JGD2000 (PatchJGD統合) → Tokyo Datum (TKY2JGD) の順に、
+    /// 順変換と同じパラメータの双一次補間をニュートン法で逆にたどって求める。
+    /// パッチの範囲外の地点はJGD2000と同じとみなし、逆変換が収束しない場合はエラーとする。
+    ///
+    /// # Arguments
+    /// * `lat` - JGD2011の緯度 (度, 10進数)
+    /// * `lon` - JGD2011の経度 (度, 10進数)
+    /// * `system_code` - 変換先の測地系コード ("00"=Tokyo, "01"=JGD2000, "02"=JGD2011)
+    ///
+    /// # Returns
+    /// 変換後の座標と往復残差。変換失敗時にNone
+    ///
+    /// # Example
+    /// ```ignore
+    /// let inv = transformer.inverse_horizontal(35.6712, 139.7640, "00")?;
+    /// println!("Tokyo: ({}, {}) 残差 {:.4}m", inv.latitude, inv.longitude, inv.residual);
+    /// ```
+    pub fn inverse_horizontal(
+        &self,
+        lat: f64,
+        lon: f64,
+        system_code: &str,
+    ) -> Option<InverseTransform> {
+        self.inverse_full(lat, lon, 0.0, system_code).ok()
+    }
+
+    /// JGD2011の座標を指定の測地系に逆変換 (水平+垂直)
+    ///
+    /// # Arguments
+    /// * `lat` - JGD2011の緯度 (度, 10進数)
+    /// * `lon` - JGD2011の経度 (度, 10進数)
+    /// * `alt` - JGD2011の標高 (m)
+    /// * `system_code` - 変換先の測地系コード ("00"=Tokyo, "01"=JGD2000, "02"=JGD2011)
+    ///
+    /// # Returns
+    /// 変換後の座標と、それを順変換してJGD2011に戻したときの残差
+    pub fn inverse_full(
+        &self,
+        lat: f64,
+        lon: f64,
+        alt: f64,
+        system_code: &str,
+    ) -> Result<InverseTransform, TransformError> {
+        self.inverse_on(lat, lon, alt, system_code, self.survey_date)
+    }
+
+    /// 調査日を指定してJGD2011の座標を逆変換 (水平+垂直)
+    ///
+    /// 調査日は地震補正の適用判定に使う。
+    pub fn inverse_on(
         &self,
         lat: f64,
         lon: f64,
         alt: f64,
         system_code: &str,
+        survey_date: Option<SurveyStartDate>,
+    ) -> Result<InverseTransform, TransformError> {
+        let result = self.inverse_point(lat, lon, alt, system_code, survey_date)?;
+
+        // 順変換で戻して往復残差を求める
+        let round_trip = self.transform_on(
+            result.latitude,
+            result.longitude,
+            result.altitude,
+            system_code,
+            survey_date,
+        )?;
+
+        Ok(InverseTransform {
+            latitude: result.latitude,
+            longitude: result.longitude,
+            altitude: result.altitude,
+            residual: horizontal_distance(lat, lon, round_trip.latitude, round_trip.longitude),
+            altitude_residual: (round_trip.altitude - alt).abs(),
+        })
+    }
+
+    /// 内部逆変換処理
+    fn inverse_point(
+        &self,
+        lat: f64,
+        lon: f64,
+        alt: f64,
+        system_code: &str,
+        survey_date: Option<SurveyStartDate>,
     ) -> Result<Point, TransformError> {
         let input_point = Point::new_unchecked(lat, lon, alt);
 
         // コードの正規化 (先頭のゼロ除去)
         let code = system_code.trim().trim_start_matches('0');
 
+        // JGD2011 → JGD2000 (パッチ範囲外の場合はそのまま、収束しない場合はエラー)
+        let to_2000 = |p: Point| match self.patch_backward(&p, survey_date) {
+            Err(TransformError::OutOfCoverage(_)) => Ok(p),
+            result => result,
+        };
+
         match code {
+            // JGD2011 → Tokyo Datum: Step 2 の逆 + Step 1 の逆
+            "" | "0" => {
+                let p_2000 = to_2000(input_point)?;
+
+                // JGD2000 → Tokyo
+                backward(&[&self.tky_parameters], &p_2000, &self.tky_file_name)
+            }
+
+            // JGD2011 → JGD2000: Step 2 の逆のみ
+            "1" => to_2000(input_point),
+
+            // JGD2011 → 変換不要
+            "2" => Ok(input_point),
+
+            _ => Err(TransformError::UnknownGeodeticSystem(system_code.trim().to_string())),
+        }
+    }
+
+    /// 座標をJGD2011に変換し、変換の来歴を返す (水平+垂直)
+    ///
+    /// 実行したステップ、調査日により適用しなかった地震補正、
+    /// パラメータ範囲外だったステップを記録する。
+    ///
+    /// # Arguments
+    /// * `lat` - 緯度 (度, 10進数)
+    /// * `lon` - 経度 (度, 10進数)
+    /// * `alt` - 標高 (m)
+    /// * `system_code` - 測地系コード ("00"=Tokyo, "01"=JGD2000, "02"=JGD2011)
+    ///
+    /// # Errors
+    /// Tokyo Datumの座標がTKY2JGDの範囲外の場合は `TransformError::OutOfCoverage`、
+    /// 測地系コードが "00"・"01"・"02" のいずれでもない場合は `TransformError::UnknownGeodeticSystem`
+    ///
+    /// # Example
+    /// ```ignore
+    /// let result = transformer.transform_with_provenance(38.2601, 140.8824, 15.0, "01")?;
+    /// for step in &result.steps {
+    ///     println!("{:?} {:?} {:?}", step.kind, step.file_name, step.status);
+    /// }
+    /// if result.is_out_of_coverage() {
+    ///     eprintln!("補正パラメータの範囲外です");
+    /// }
+    /// ```
+    pub fn transform_with_provenance(
+        &self,
+        lat: f64,
+        lon: f64,
+        alt: f64,
+        system_code: &str,
+    ) -> Result<TransformResult, TransformError> {
+        self.transform_on(lat, lon, alt, system_code, self.survey_date)
+    }
+
+    /// 調査日を指定して座標をJGD2011に変換し、変換の来歴を返す (水平+垂直)
+    ///
+    /// 調査日は地震補正の適用判定に使う。パラメータは読み込み済みのものを使うため、
+    /// 調査日の異なる多数の座標を1つの変換器で変換できる。
+    ///
+    /// # Arguments
+    /// * `lat` - 緯度 (度, 10進数)
+    /// * `lon` - 経度 (度, 10進数)
+    /// * `alt` - 標高 (m)
+    /// * `system_code` - 測地系コード ("00"=Tokyo, "01"=JGD2000, "02"=JGD2011)
+    /// * `survey_date` - 調査日（Noneの場合、地震補正は適用しない）
+    ///
+    /// # Example
+    /// ```ignore
+    /// let transformer = JgdTransformer::new("params/TKY2JGD.par", "params/patch_files")?;
+    /// for boring in &borings {
+    ///     let result = transformer.transform_on(lat, lng, 0.0, "01", boring.survey_start_date())?;
+    /// }
+    /// ```
+    pub fn transform_on(
+        &self,
+        lat: f64,
+        lon: f64,
+        alt: f64,
+        system_code: &str,
+        survey_date: Option<SurveyStartDate>,
+    ) -> Result<TransformResult, TransformError> {
+        let input_point = Point::new_unchecked(lat, lon, alt);
+
+        // コードの正規化 (先頭のゼロ除去)
+        let code = system_code.trim().trim_start_matches('0');
+
+        let mut steps = Vec::new();
+        let mut patch_fallback = false;
+
+        let point = match code {
             // Tokyo Datum (日本測地系) → Step 1 + Step 2
             // code "00" の場合、trim_start_matches('0') で "" になる
             "" | "0" => {
                 // Step 1: Tokyo → JGD2000
-                let corr_tky = self
-                    .tky_tf
-                    .forward_corr(&input_point)
-                    .map_err(|e| TransformError::Transform(format!("TKY2JGD: {}", e)))?;
+                let corr_tky = self.tky_forward_corr(&input_point).ok_or_else(|| {
+                    TransformError::OutOfCoverage(format!("{}: パラメータの範囲外です", self.tky_file_name))
+                })?;
+                steps.push(TransformStep {
+                    kind: StepKind::Tky2Jgd,
+                    file_name: Some(self.tky_file_name.clone()),
+                    status: StepStatus::Applied,
+                });
                 let p_2000 = &input_point + corr_tky;
 
                 // Step 2: JGD2000 → JGD2011
-                match self.unified_tf.forward_corr(&p_2000) {
-                    Ok(corr_unified) => Ok(&p_2000 + corr_unified),
-                    Err(_) => Ok(p_2000), // パッチ範囲外の場合はJGD2000のまま
-                }
+                self.apply_patch(p_2000, survey_date, &mut steps, &mut patch_fallback)
             }
 
             // JGD2000 (世界測地系2000) → Step 2のみ
-            "1" => match self.unified_tf.forward_corr(&input_point) {
-                Ok(corr_unified) => Ok(&input_point + corr_unified),
-                Err(_) => Ok(input_point), // パッチ範囲外の場合はそのまま
-            },
+            "1" => self.apply_patch(input_point, survey_date, &mut steps, &mut patch_fallback),
 
             // JGD2011 → 変換不要
-            "2" => Ok(input_point),
+            "2" => input_point,
+
+            _ => return Err(TransformError::UnknownGeodeticSystem(system_code.trim().to_string())),
+        };
+
+        Ok(TransformResult {
+            latitude: point.latitude,
+            longitude: point.longitude,
+            altitude: point.altitude,
+            system_code: system_code.trim().to_string(),
+            survey_date,
+            steps,
+            patch_fallback,
+        })
+    }
+
+    /// 座標をWGS84に変換し、変換の来歴を返す (水平座標のみ)
+    ///
+    /// 結果の緯度・経度はWGS84座標。
+    pub fn to_wgs84_with_provenance(
+        &self,
+        lat: f64,
+        lon: f64,
+        system_code: &str,
+    ) -> Result<TransformResult, TransformError> {
+        self.to_wgs84_on(lat, lon, system_code, self.survey_date)
+    }
+
+    /// 調査日を指定して座標をWGS84に変換し、変換の来歴を返す (水平座標のみ)
+    pub fn to_wgs84_on(
+        &self,
+        lat: f64,
+        lon: f64,
+        system_code: &str,
+        survey_date: Option<SurveyStartDate>,
+    ) -> Result<TransformResult, TransformError> {
+        let result = self.transform_on(lat, lon, 0.0, system_code, survey_date)?;
+        self.jgd2011_result_to_wgs84(result)
+    }
 
-            // 未知のコード → そのまま返す
-            _ => Ok(input_point),
+    /// ボーリングデータの緯度経度をJGD2011に変換
+    ///
+    /// 地震補正の適用判定には、文書の調査期間の開始日を使う。
+    ///
+    /// # Example
+    /// ```ignore
+    /// let transformer = JgdTransformer::new("params/TKY2JGD.par", "params/patch_files")?;
+    /// for boring in &borings {
+    ///     let result = transformer.transform_document(boring)?;
+    ///     println!("{} {} ({:?})", result.latitude, result.longitude, result.survey_date);
+    /// }
+    /// ```
+    pub fn transform_document<T>(&self, document: &T) -> Result<TransformResult, TransformError>
+    where
+        T: GeoCoordinate + SurveyDate,
+    {
+        let location = document.geo_location();
+        let (lng, lat) = location
+            .to_decimal()
+            .ok_or_else(|| TransformError::Transform("緯度経度がありません".to_string()))?;
+        let system_code = location.geodetic_system.as_deref().unwrap_or("02");
+        self.transform_on(lat, lng, 0.0, system_code, document.survey_start_date())
+    }
+
+    /// ボーリングデータの緯度経度をWGS84に変換
+    ///
+    /// 地震補正の適用判定には、文書の調査期間の開始日を使う。
+    pub fn document_to_wgs84<T>(&self, document: &T) -> Result<TransformResult, TransformError>
+    where
+        T: GeoCoordinate + SurveyDate,
+    {
+        let result = self.transform_document(document)?;
+        self.jgd2011_result_to_wgs84(result)
+    }
+
+    /// 座標をJGD2011に変換し、セミ・ダイナミック補正で目標の元期・今期に合わせる
+    ///
+    /// JGD2011 ("02") の座標は調査日の今期に観測された値とみなし、調査日の年度の
+    /// パラメータで元期に戻してから目標に補正する。Tokyo Datum/JGD2000 からの変換結果は
+    /// 元期の値なので、そのまま目標に補正する。
+    ///
+    /// # Arguments
+    /// * `lat` - 緯度 (度, 10進数)
+    /// * `lon` - 経度 (度, 10進数)
+    /// * `alt` - 標高 (m)
+    /// * `system_code` - 測地系コード ("00"=Tokyo, "01"=JGD2000, "02"=JGD2011)
+    /// * `survey_date` - 調査日（地震補正の判定と、JGD2011座標の観測時期に使用）
+    /// * `target` - 補正先の元期・今期
+    ///
+    /// # Errors
+    /// セミ・ダイナミック補正パラメータが設定されていない場合、必要な年度のパラメータが
+    /// ない場合、JGD2011の座標で調査日が不明な場合
+    pub fn transform_to_epoch(
+        &self,
+        lat: f64,
+        lon: f64,
+        alt: f64,
+        system_code: &str,
+        survey_date: Option<SurveyStartDate>,
+        target: Epoch,
+    ) -> Result<TransformResult, TransformError> {
+        let semi = self.semi_dynamic.as_ref().ok_or_else(|| {
+            TransformError::Transform("セミ・ダイナミック補正パラメータが設定されていません".to_string())
+        })?;
+
+        let mut result = self.transform_on(lat, lon, alt, system_code, survey_date)?;
+
+        let from = match system_code.trim().trim_start_matches('0') {
+            "2" => Epoch::At(survey_date.ok_or_else(|| {
+                TransformError::Transform("JGD2011座標の観測時期(調査日)が不明です".to_string())
+            })?),
+            _ => Epoch::Reference,
+        };
+        if from.fiscal_year() == target.fiscal_year() {
+            return Ok(result);
         }
+
+        let (lat, lon, alt) =
+            semi.correct(result.latitude, result.longitude, result.altitude, from, target)?;
+        result.latitude = lat;
+        result.longitude = lon;
+        result.altitude = alt;
+        for epoch in [from, target] {
+            if let Some(file_name) = semi.file_name(epoch)? {
+                result.steps.push(TransformStep {
+                    kind: StepKind::SemiDynamic,
+                    file_name: Some(file_name.to_string()),
+                    status: StepStatus::Applied,
+                });
+            }
+        }
+        Ok(result)
+    }
+
+    /// ボーリングデータの緯度経度をJGD2011に変換し、目標の元期・今期に補正
+    ///
+    /// 調査日には文書の調査期間の開始日を使う。
+    pub fn document_to_epoch<T>(
+        &self,
+        document: &T,
+        target: Epoch,
+    ) -> Result<TransformResult, TransformError>
+    where
+        T: GeoCoordinate + SurveyDate,
+    {
+        let location = document.geo_location();
+        let (lng, lat) = location
+            .to_decimal()
+            .ok_or_else(|| TransformError::Transform("緯度経度がありません".to_string()))?;
+        let system_code = location.geodetic_system.as_deref().unwrap_or("02");
+        self.transform_to_epoch(lat, lng, 0.0, system_code, document.survey_start_date(), target)
+    }
+
+    /// JGD2011の変換結果をWGS84に変換し、ステップを追加する
+    pub(crate) fn jgd2011_result_to_wgs84(
+        &self,
+        mut result: TransformResult,
+    ) -> Result<TransformResult, TransformError> {
+        let (lng, lat) = wgs84::jgd2011_to_wgs84(result.longitude, result.latitude)?;
+        result.latitude = lat;
+        result.longitude = lng;
+        result.steps.push(TransformStep {
+            kind: StepKind::Wgs84,
+            file_name: None,
+            status: StepStatus::Applied,
+        });
+        Ok(result)
+    }
+
+    /// Tokyo Datum → JGD2000 の補正量
+    ///
+    /// 読み込み済みのパラメータからメッシュ四隅を引いて双一次補間する。
+    /// 四隅のいずれかにパラメータがない場合はNone。
+    fn tky_forward_corr(&self, point: &Point) -> Option<Correction> {
+        interpolate(&[&self.tky_parameters], point)
+    }
+
+    /// JGD2000 → JGD2011 の補正量
+    ///
+    /// 調査日から適用するファイルを選び、メッシュ四隅のパラメータを合算して
+    /// 双一次補間する。四隅のいずれかにパラメータがない場合はNone。
+    fn patch_corr(&self, point: &Point, survey_date: Option<SurveyStartDate>) -> Option<Correction> {
+        interpolate(&self.patch_tables(survey_date), point)
+    }
+
+    /// 調査日に適用するパッチファイルのパラメータ表
+    fn patch_tables(&self, survey_date: Option<SurveyStartDate>) -> Vec<&ParamTable> {
+        self.param_files
+            .iter()
+            .filter(|f| f.applies(survey_date))
+            .map(|f| &f.parameters)
+            .collect()
+    }
+
+    /// JGD2000 → JGD2011 の補正を逆にたどる
+    ///
+    /// 調査日に適用するパッチファイルのパラメータを四隅ごとに合算し、ニュートン法で求める。
+    /// 適用するパラメータがない場合は `TransformError::OutOfCoverage`。
+    fn patch_backward(&self, point: &Point, survey_date: Option<SurveyStartDate>) -> Result<Point, TransformError> {
+        backward(&self.patch_tables(survey_date), point, "PatchJGD")
+    }
+
+    /// JGD2000 → JGD2011 の補正を適用し、ファイルごとの来歴を記録する
+    fn apply_patch(
+        &self,
+        p_2000: Point,
+        survey_date: Option<SurveyStartDate>,
+        steps: &mut Vec<TransformStep>,
+        patch_fallback: &mut bool,
+    ) -> Point {
+        let cell = MeshCell::try_from_point(&p_2000, MeshUnit::One);
+        steps.extend(self.param_files.iter().map(|f| f.step(cell.as_ref(), survey_date)));
+
+        match self.patch_corr(&p_2000, survey_date) {
+            Some(corr) => &p_2000 + corr,
+            None => {
+                // パッチ範囲外の場合はJGD2000のまま
+                *patch_fallback = self.param_files.iter().any(|f| f.applies(survey_date));
+                p_2000
+            }
+        }
+    }
+
+    /// 内部変換処理
+    fn transform_point(
+        &self,
+        lat: f64,
+        lon: f64,
+        alt: f64,
+        system_code: &str,
+    ) -> Result<Point, TransformError> {
+        let result = self.transform_with_provenance(lat, lon, alt, system_code)?;
+        Ok(Point::new_unchecked(result.latitude, result.longitude, result.altitude))
     }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::parser::sample;
+    use jgdtrans::Transformer;
+    use std::collections::HashMap;
 
     #[test]
     fn test_code_normalization() {
@@ -668,6 +1591,429 @@ mod tests {
         // 地震補正は適用されない（ジオイド補正のみ）
     }
 
+    /// 東京駅付近に、TKY2JGD相当の補正量(約-11.6秒, +11.6秒)を持つ1kmメッシュを敷いたパラメータ
+    fn synthetic_tky_parameters() -> ParamTable {
+        let mut params: HashMap<u32, Parameter> = HashMap::new();
+        for i in -3..=3 {
+            for j in -3..=3 {
+                let point = Point::new_unchecked(
+                    35.68 + i as f64 / 120.0,
+                    139.77 + j as f64 / 80.0,
+                    0.0,
+                );
+                let code = point.try_to_meshcode(&jgdtrans::mesh::MeshUnit::One).unwrap();
+                // 位置によってわずかに変化する補正量 (秒)
+                let lat_corr = 11.6 + 0.05 * i as f64 - 0.02 * j as f64;
+                let lon_corr = -11.6 + 0.03 * j as f64 + 0.01 * i as f64;
+                params.insert(code, Parameter::new(lat_corr, lon_corr, 0.0));
+            }
+        }
+        ParamTable::Parsed(params)
+    }
+
+    #[test]
+    fn test_backward_inverts_forward() {
+        let tky = synthetic_tky_parameters();
+        let origin = Point::new_unchecked(35.675, 139.765, 0.0);
+        let corr = synthetic_transformer(Vec::new()).tky_forward_corr(&origin).unwrap();
+        let forward = &origin + corr.clone();
+
+        // 四隅の双一次補間はjgdtransの順変換と一致する
+        let ParamTable::Parsed(params) = &tky else { unreachable!() };
+        let expected = Transformer::new(ParData::new(Format::TKY2JGD, params.clone())).forward_corr(&origin).unwrap();
+        assert!((corr.latitude - expected.latitude).abs() < 1e-15);
+        assert!((corr.longitude - expected.longitude).abs() < 1e-15);
+
+        // 約11秒(約300m)の補正を戻せること
+        assert!(horizontal_distance(35.675, 139.765, forward.latitude, forward.longitude) > 300.0);
+
+        let back = backward(&[&tky], &forward, "TKY2JGD.par").unwrap();
+        assert!(horizontal_distance(origin.latitude, origin.longitude, back.latitude, back.longitude) < 1e-4);
+
+        // 範囲外は OutOfCoverage (呼び出し側で扱いを決める)
+        let outside = Point::new_unchecked(43.0, 141.3, 0.0);
+        let err = backward(&[&tky], &outside, "TKY2JGD.par").unwrap_err();
+        assert!(matches!(err, TransformError::OutOfCoverage(_)));
+    }
+
+    /// 合成TKY2JGDと、指定したパッチファイル群を持つ変換器
+    fn synthetic_transformer(param_files: Vec<ParamFile>) -> JgdTransformer {
+        JgdTransformer {
+            tky_parameters: synthetic_tky_parameters(),
+            param_files,
+            tky_file_name: "TKY2JGD.par".to_string(),
+            survey_date: None,
+            semi_dynamic: None,
+        }
+    }
+
+    /// 中心点の周囲 ±n メッシュに一様なパラメータを持つパッチファイル
+    fn patch_file(name: &str, kind: StepKind, center: (f64, f64), n: i32, param: Parameter) -> ParamFile {
+        let mut parameters = HashMap::new();
+        for i in -n..=n {
+            for j in -n..=n {
+                let point = Point::new_unchecked(
+                    center.0 + i as f64 / 120.0,
+                    center.1 + j as f64 / 80.0,
+                    0.0,
+                );
+                parameters.insert(point.try_to_meshcode(&MeshUnit::One).unwrap(), param.clone());
+            }
+        }
+        ParamFile {
+            file_name: name.to_string(),
+            kind,
+            earthquake_date: EarthquakeCatalogue::builtin().find(name).map(|eq| eq.date),
+            parameters: ParamTable::Parsed(parameters),
+        }
+    }
+
+    #[test]
+    fn test_inverse_full_round_trip() {
+        let transformer = synthetic_transformer(Vec::new());
+
+        let (lat, lon) = transformer.transform_horizontal(35.675, 139.765, "00").unwrap();
+        let inv = transformer.inverse_full(lat, lon, 12.0, "00").unwrap();
+
+        assert!((inv.latitude - 35.675).abs() < 1e-9);
+        assert!((inv.longitude - 139.765).abs() < 1e-9);
+        assert_eq!(inv.altitude, 12.0);
+        assert!(inv.residual < 1e-3);
+        assert!(inv.altitude_residual < 1e-6);
+
+        // JGD2011 → JGD2011 は変換なし
+        let same = transformer.inverse_horizontal(lat, lon, "02").unwrap();
+        assert_eq!((same.latitude, same.longitude), (lat, lon));
+        assert_eq!(same.residual, 0.0);
+    }
+
+    #[test]
+    fn test_transform_provenance() {
+        let mut transformer = synthetic_transformer(vec![
+            patch_file("touhokutaiheiyouoki2011.par", StepKind::PatchHorizontal, (35.68, 139.77), 3, Parameter::new(-0.5, 0.8, 0.0)),
+            patch_file("kumamoto2016.par", StepKind::PatchHorizontal, (32.8, 130.7), 1, Parameter::new(0.3, -0.3, 0.0)),
+            patch_file("noto2007.par", StepKind::PatchHorizontal, (35.68, 139.77), 1, Parameter::new(9.0, 9.0, 0.0)),
+            patch_file("hyokorev2014_geoid2011_h.par", StepKind::Geoid, (35.68, 139.77), 3, Parameter::new(0.0, 0.0, 0.05)),
+        ]);
+        // 2010年の調査: 能登(2007)は調査後の地震のため適用しない
+        transformer.survey_date = Some(SurveyStartDate::new(2010, 5, 15));
+
+        let result = transformer.transform_with_provenance(35.675, 139.765, 10.0, "00").unwrap();
+        let statuses: Vec<(StepKind, Option<&str>, StepStatus)> = result
+            .steps
+            .iter()
+            .map(|s| (s.kind, s.file_name.as_deref(), s.status))
+            .collect();
+        assert_eq!(statuses[0], (StepKind::Tky2Jgd, Some("TKY2JGD.par"), StepStatus::Applied));
+        assert!(statuses.contains(&(
+            StepKind::PatchHorizontal,
+            Some("touhokutaiheiyouoki2011.par"),
+            StepStatus::Applied
+        )));
+        assert!(statuses.contains(&(
+            StepKind::PatchHorizontal,
+            Some("kumamoto2016.par"),
+            StepStatus::OutOfCoverage
+        )));
+        assert_eq!(result.skipped_steps().count(), 1);
+        assert!(!result.patch_fallback);
+        assert!(!result.is_out_of_coverage());
+        assert!((result.altitude - 10.05).abs() < 1e-9);
+
+        // 来歴付きと来歴なしの結果は一致する
+        let (lat, lon, _) = transformer.transform_full(35.675, 139.765, 10.0, "00").unwrap();
+        assert_eq!((lat, lon), (result.latitude, result.longitude));
+
+        // WGS84変換ステップが追加される
+        let wgs = transformer.to_wgs84_with_provenance(35.675, 139.765, "00").unwrap();
+        assert_eq!(wgs.steps.last().unwrap().kind, StepKind::Wgs84);
+    }
+
+    #[test]
+    fn test_transform_on_per_call_date() {
+        let transformer = synthetic_transformer(vec![
+            patch_file("touhokutaiheiyouoki2011.par", StepKind::PatchHorizontal, (38.26, 140.88), 2, Parameter::new(-0.5, 0.8, 0.0)),
+            patch_file("touhokutaiheiyouoki2011_h.par", StepKind::PatchElevation, (38.26, 140.88), 2, Parameter::new(0.0, 0.0, -0.3)),
+            patch_file("hyokorev2014_geoid2011_h.par", StepKind::Geoid, (38.26, 140.88), 2, Parameter::new(0.0, 0.0, 0.05)),
+        ]);
+
+        // 同じ変換器で、調査日ごとに適用するパラメータが変わる
+        let before = transformer
+            .transform_on(38.2601, 140.8824, 15.0, "01", Some(SurveyStartDate::new(2010, 5, 15)))
+            .unwrap();
+        assert!((before.latitude - (38.2601 - 0.5 / 3600.0)).abs() < 1e-12);
+        assert!((before.longitude - (140.8824 + 0.8 / 3600.0)).abs() < 1e-12);
+        assert!((before.altitude - 14.75).abs() < 1e-9);
+        assert_eq!(before.skipped_steps().count(), 0);
+
+        let after = transformer
+            .transform_on(38.2601, 140.8824, 15.0, "01", Some(SurveyStartDate::new(2015, 6, 20)))
+            .unwrap();
+        assert_eq!((after.latitude, after.longitude), (38.2601, 140.8824));
+        assert!((after.altitude - 15.05).abs() < 1e-9);
+        assert_eq!(after.skipped_steps().count(), 2);
+
+        // 調査日不明 → 地震補正なし
+        let unknown = transformer.transform_on(38.2601, 140.8824, 15.0, "01", None).unwrap();
+        assert_eq!(unknown.altitude, after.altitude);
+
+        // 逆変換も同じ調査日で往復する
+        let inv = transformer
+            .inverse_on(before.latitude, before.longitude, before.altitude, "01", before.survey_date)
+            .unwrap();
+        assert!((inv.latitude - 38.2601).abs() < 1e-10);
+        assert!((inv.altitude - 15.0).abs() < 1e-9);
+        assert!(inv.residual < 1e-3);
+
+        // 2つの地震の間の調査 → 後の地震の補正だけを適用
+        let transformer = synthetic_transformer(vec![
+            patch_file("touhokutaiheiyouoki2011.par", StepKind::PatchHorizontal, (38.26, 140.88), 2, Parameter::new(-0.5, 0.8, 0.0)),
+            patch_file("kumamoto2016.par", StepKind::PatchHorizontal, (38.26, 140.88), 2, Parameter::new(0.3, -0.3, 0.0)),
+        ]);
+        let applied = |date: SurveyStartDate| {
+            let result = transformer.transform_on(38.2601, 140.8824, 0.0, "01", Some(date)).unwrap();
+            let files: Vec<String> = result
+                .steps
+                .iter()
+                .filter(|s| s.status == StepStatus::Applied)
+                .filter_map(|s| s.file_name.clone())
+                .collect();
+            (files, result.latitude)
+        };
+        let (files, lat) = applied(SurveyStartDate::new(2010, 5, 15));
+        assert_eq!(files, ["touhokutaiheiyouoki2011.par", "kumamoto2016.par"]);
+        assert!((lat - (38.2601 - 0.2 / 3600.0)).abs() < 1e-12);
+        let (files, lat) = applied(SurveyStartDate::new(2013, 5, 15));
+        assert_eq!(files, ["kumamoto2016.par"]);
+        assert!((lat - (38.2601 + 0.3 / 3600.0)).abs() < 1e-12);
+        let (files, lat) = applied(SurveyStartDate::new(2017, 5, 15));
+        assert!(files.is_empty());
+        assert_eq!(lat, 38.2601);
+    }
+
+    #[test]
+    fn test_inverse_propagates_non_convergence() {
+        // 南北に隣り合うメッシュで補正量が大きく振れ、補正後の緯度が南北で逆転するパッチ
+        // （ニュートン法の反復がメッシュ間を行き来して収束しない）
+        let center = (35.68, 139.77);
+        let mut file = patch_file("touhokutaiheiyouoki2011.par", StepKind::PatchHorizontal, center, 3, Parameter::new(0.0, 0.0, 0.0));
+        let ParamTable::Parsed(parameters) = &mut file.parameters else {
+            unreachable!()
+        };
+        let latitude_shifts = [-60.0, 0.0, -40.0, 60.0, 0.0, 60.0, 60.0];
+        for (i, shift) in (-3..=3).zip(latitude_shifts) {
+            for j in -3..=3 {
+                let point = Point::new_unchecked(center.0 + i as f64 / 120.0, center.1 + j as f64 / 80.0, 0.0);
+                parameters.insert(point.try_to_meshcode(&MeshUnit::One).unwrap(), Parameter::new(shift, 0.0, 0.0));
+            }
+        }
+        let transformer = synthetic_transformer(vec![file]);
+
+        let survey = Some(SurveyStartDate::new(2010, 4, 1));
+        // 中心のメッシュの南北中央
+        let err = transformer.inverse_on(35.675 + 0.5 / 120.0, center.1, 0.0, "01", survey).unwrap_err();
+        assert!(matches!(err, TransformError::Transform(ref m) if m == "逆変換が収束しません"), "{err:?}");
+
+        // パッチの範囲外はそのまま (JGD2000 = JGD2011)
+        let outside = transformer.inverse_on(32.8, 130.7, 0.0, "01", survey).unwrap();
+        assert_eq!((outside.latitude, outside.longitude), (32.8, 130.7));
+    }
+
+    #[test]
+    fn test_transform_to_epoch() {
+        let mut transformer = synthetic_transformer(Vec::new());
+        let (lat, lon) = (36.10377479, 140.087855041);
+        let survey = SurveyStartDate::new(2020, 6, 1);
+        let target = Epoch::At(SurveyStartDate::new(2024, 3, 1));
+
+        // パラメータ未設定はエラー
+        assert!(transformer.transform_to_epoch(lat, lon, 0.0, "02", Some(survey), target).is_err());
+
+        let params: HashMap<u32, Parameter> = [54401005, 54401055, 54401100, 54401150]
+            .into_iter()
+            .map(|code| (code, Parameter::new(-0.0062, 0.0152, 0.1)))
+            .collect();
+        let mut semi = SemiDynamic::new();
+        semi.insert(2023, Transformer::new(ParData::new(Format::SemiDynaEXE, params)), "SemiDyna2023.par".to_string());
+        transformer.set_semi_dynamic(semi);
+
+        // JGD2000の変換結果は元期 → 2023年度 (2024/3/1) に補正
+        let result = transformer.transform_to_epoch(lat, lon, 0.0, "01", Some(survey), target).unwrap();
+        assert!((result.latitude - (lat - 0.0062 / 3600.0)).abs() < 1e-10);
+        assert!((result.altitude - 0.1).abs() < 1e-9);
+        let semi_steps: Vec<_> = result.steps.iter().filter(|s| s.kind == StepKind::SemiDynamic).collect();
+        assert_eq!(semi_steps.len(), 1);
+        assert_eq!(semi_steps[0].file_name.as_deref(), Some("SemiDyna2023.par"));
+
+        // JGD2011座標は調査日の年度(2020)のパラメータが必要
+        let err = transformer.transform_to_epoch(lat, lon, 0.0, "02", Some(survey), target).unwrap_err();
+        assert!(matches!(err, TransformError::MissingSemiDynamic(2020)));
+
+        // 調査日と目標が同じ年度なら補正しない
+        let same = transformer
+            .transform_to_epoch(lat, lon, 0.0, "02", Some(SurveyStartDate::new(2023, 5, 1)), target)
+            .unwrap();
+        assert_eq!((same.latitude, same.longitude), (lat, lon));
+    }
+
+    #[test]
+    fn test_survey_start_date_from_document() {
+        // v1.10は年・月・日が別の要素、v2.10以降は年月日
+        let expected = Some(SurveyStartDate::new(1999, 5, 1));
+        let b110: crate::boring_structs_110::Boring110 = sample("BED0110.XML");
+        let b210: crate::boring_structs_210::Boring210 = sample("BED0210.XML");
+        let b300: crate::boring_structs_300::Boring300 = sample("BED0300.XML");
+        let b400: crate::boring_structs_400::Boring400 = sample("BED0400.XML");
+        assert_eq!(b110.survey_start_date(), expected);
+        assert_eq!(b210.survey_start_date(), expected);
+        assert_eq!(b300.survey_start_date(), expected);
+        assert_eq!(b400.survey_start_date(), expected);
+
+        // 文書の調査日で地震補正を判断する（1999年の調査は2011年の地震補正を受ける）
+        let transformer = synthetic_transformer(vec![patch_file(
+            "touhokutaiheiyouoki2011.par",
+            StepKind::PatchHorizontal,
+            (38.26, 140.88),
+            2,
+            Parameter::new(-0.5, 0.8, 0.0),
+        )]);
+        let result = transformer
+            .transform_on(38.2601, 140.8824, 0.0, "01", b210.survey_start_date())
+            .unwrap();
+        assert_eq!(result.survey_date, expected);
+        assert!((result.latitude - (38.2601 - 0.5 / 3600.0)).abs() < 1e-12);
+    }
+
+    #[test]
+    fn test_earthquake_catalogue_manifest() {
+        let manifest = "# パターン,発生日,地震名\n\
+                        noto2024, 2024-01-01, 令和6年能登半島地震\n\
+                        \n\
+                        kumamoto2016,2016/04/16  # 本震の日付で上書き\n";
+        let mut catalogue = EarthquakeCatalogue::builtin();
+        catalogue.merge(EarthquakeCatalogue::parse(manifest).unwrap());
+
+        let noto = catalogue.find("noto2024.par").unwrap();
+        assert_eq!(noto.date, SurveyStartDate::new(2024, 1, 1));
+        assert_eq!(noto.name.as_deref(), Some("令和6年能登半島地震"));
+        assert_eq!(catalogue.find("noto2007_h.par").unwrap().date, SurveyStartDate::new(2007, 3, 25));
+        assert_eq!(catalogue.find("kumamoto2016.par").unwrap().date, SurveyStartDate::new(2016, 4, 16));
+        assert_eq!(catalogue.earthquakes().len(), EARTHQUAKES.len() + 1);
+        assert!(EarthquakeCatalogue::builtin().find("noto2024.par").is_none());
+
+        assert!(EarthquakeCatalogue::parse("noto2024").is_err());
+        assert!(EarthquakeCatalogue::parse("noto2024,2024-13-01").is_err());
+    }
+
+    /// テストごとの一時ディレクトリ（失敗時も含め、drop時に削除する）
+    struct TempDir(std::path::PathBuf);
+
+    impl TempDir {
+        fn new(name: &str) -> Self {
+            static COUNTER: std::sync::atomic::AtomicUsize = std::sync::atomic::AtomicUsize::new(0);
+            let n = COUNTER.fetch_add(1, std::sync::atomic::Ordering::Relaxed);
+            let nanos = std::time::SystemTime::now()
+                .duration_since(std::time::UNIX_EPOCH)
+                .map_or(0, |d| d.subsec_nanos());
+            let dir = std::env::temp_dir().join(format!("{}_{}_{}_{}", name, std::process::id(), n, nanos));
+            std::fs::create_dir_all(&dir).unwrap();
+            Self(dir)
+        }
+    }
+
+    impl Drop for TempDir {
+        fn drop(&mut self) {
+            let _ = std::fs::remove_dir_all(&self.0);
+        }
+    }
+
+    #[test]
+    fn test_unknown_patch_file_is_rejected() {
+        let temp = TempDir::new("boring_patch_unknown");
+        let dir = &temp.0;
+        let horizontal = dir.join("horizontal");
+        std::fs::create_dir_all(&horizontal).unwrap();
+        std::fs::write(horizontal.join("noto2024.par"), "").unwrap();
+
+        // カタログにない地震補正ファイル → エラー
+        let err = JgdTransformer::load_patch_files(dir, &EarthquakeCatalogue::builtin())
+            .err()
+            .unwrap();
+        assert!(matches!(err, TransformError::UnknownPatchFile(ref name) if name == "noto2024.par"));
+
+        // マニフェストで登録すると、マニフェストの発生日を持つ地震補正として読み込まれる
+        let point = Point::new_unchecked(37.3, 136.9, 0.0);
+        let cell = MeshCell::try_from_point(&point, MeshUnit::One).unwrap();
+        let mut body = "PatchJGD\n".repeat(16);
+        for code in cell_corners(&cell) {
+            body.push_str(&format!("{} {:>9} {:>9}\n", code, "1.00000", "-2.00000"));
+        }
+        std::fs::write(horizontal.join("noto2024.par"), body).unwrap();
+        std::fs::write(dir.join(EARTHQUAKE_MANIFEST), "noto2024,2024-01-01\n").unwrap();
+        let catalogue = EarthquakeCatalogue::for_patch_dir(dir).unwrap();
+        let files = JgdTransformer::load_patch_files(dir, &catalogue).unwrap();
+        assert_eq!(files.len(), 1);
+        assert_eq!(files[0].file_name, "noto2024.par");
+        assert_eq!(files[0].kind, StepKind::PatchHorizontal);
+        assert_eq!(files[0].earthquake_date, Some(SurveyStartDate::new(2024, 1, 1)));
+
+        // 地震前の調査には補正を適用し、地震後の調査には適用しない
+        let transformer = synthetic_transformer(files);
+        let before = transformer
+            .transform_on(point.latitude, point.longitude, 0.0, "01", Some(SurveyStartDate::new(2023, 12, 31)))
+            .unwrap();
+        assert!((before.latitude - (point.latitude + 1.0 / 3600.0)).abs() < 1e-12);
+        assert!((before.longitude - (point.longitude - 2.0 / 3600.0)).abs() < 1e-12);
+        assert_eq!(before.applied_steps().count(), 1);
+
+        let after = transformer
+            .transform_on(point.latitude, point.longitude, 0.0, "01", Some(SurveyStartDate::new(2024, 1, 1)))
+            .unwrap();
+        assert_eq!((after.latitude, after.longitude), (point.latitude, point.longitude));
+        assert_eq!(after.skipped_steps().count(), 1);
+    }
+
+    #[test]
+    fn test_transform_out_of_coverage() {
+        let transformer = synthetic_transformer(vec![patch_file(
+            "hyokorev2014_geoid2011_h.par",
+            StepKind::Geoid,
+            (35.68, 139.77),
+            3,
+            Parameter::new(0.0, 0.0, 0.05),
+        )]);
+
+        // JGD2000の地点がパッチ範囲外 → JGD2000のまま返し、範囲外として報告
+        let result = transformer.transform_with_provenance(43.0, 141.3, 0.0, "01").unwrap();
+        assert_eq!((result.latitude, result.longitude), (43.0, 141.3));
+        assert!(result.patch_fallback);
+        assert!(result.is_out_of_coverage());
+
+        // TKY2JGDの範囲外はエラー
+        let err = transformer.transform_with_provenance(43.0, 141.3, 0.0, "00").unwrap_err();
+        assert!(matches!(err, TransformError::OutOfCoverage(_)));
+
+        // JGD2011はステップなし
+        let result = transformer.transform_with_provenance(43.0, 141.3, 0.0, "02").unwrap();
+        assert!(result.steps.is_empty());
+        assert!(!result.is_out_of_coverage());
+    }
+
+    #[test]
+    fn test_unknown_geodetic_system_is_rejected() {
+        let transformer = synthetic_transformer(Vec::new());
+
+        let err = transformer.transform_with_provenance(35.68, 139.77, 0.0, " 03 ").unwrap_err();
+        assert!(matches!(err, TransformError::UnknownGeodeticSystem(ref code) if code == "03"), "{err:?}");
+        let err = transformer.inverse_full(35.68, 139.77, 0.0, "99").unwrap_err();
+        assert!(matches!(err, TransformError::UnknownGeodeticSystem(ref code) if code == "99"), "{err:?}");
+
+        // "2" だけが変換不要
+        let result = transformer.transform_with_provenance(35.68, 139.77, 0.0, "2").unwrap();
+        assert!(result.steps.is_empty());
+        assert_eq!(transformer.inverse_full(35.68, 139.77, 0.0, "02").unwrap().latitude, 35.68);
+    }
+
     #[test]
     fn test_survey_date_parsing() {
         // YYYYMMDD形式
@@ -689,20 +2035,29 @@ mod tests {
 
     #[test]
     fn test_earthquake_correction_should_apply() {
+        // パラメータファイルが、地震の発生日と調査日から適用を判断する
+        let param = Parameter::new(0.0, 0.0, 0.05);
+        let horizontal = patch_file("touhokutaiheiyouoki2011.par", StepKind::PatchHorizontal, (38.26, 140.88), 0, param.clone());
+        let vertical = patch_file("touhokutaiheiyouoki2011_h.par", StepKind::PatchElevation, (38.26, 140.88), 0, param.clone());
+        let geoid = patch_file("hyokorev2014_geoid2011_h.par", StepKind::Geoid, (38.26, 140.88), 0, param);
+        assert_eq!(horizontal.earthquake_date, Some(SurveyStartDate::new(2011, 3, 11)));
+
         // 地震前の調査 → 適用する
         let before = SurveyStartDate::new(2010, 1, 1);
-        assert!(should_apply_earthquake_correction("touhokutaiheiyouoki2011.par", Some(before)));
+        assert!(horizontal.applies(Some(before)));
+        assert!(vertical.applies(Some(before)));
 
         // 地震後の調査 → 適用しない
         let after = SurveyStartDate::new(2012, 1, 1);
-        assert!(!should_apply_earthquake_correction("touhokutaiheiyouoki2011.par", Some(after)));
+        assert!(!horizontal.applies(Some(after)));
+        assert!(!vertical.applies(Some(after)));
 
         // ジオイド補正は常に適用
-        assert!(should_apply_earthquake_correction("hyokorev2014_geoid2011_h.par", Some(before)));
-        assert!(should_apply_earthquake_correction("hyokorev2014_geoid2011_h.par", Some(after)));
-        assert!(should_apply_earthquake_correction("hyokorev2014_geoid2011_h.par", None));
+        assert!(geoid.applies(Some(before)));
+        assert!(geoid.applies(Some(after)));
+        assert!(geoid.applies(None));
 
         // 調査日不明の場合、地震補正は適用しない
-        assert!(!should_apply_earthquake_correction("touhokutaiheiyouoki2011.par", None));
+        assert!(!horizontal.applies(None));
     }
 }
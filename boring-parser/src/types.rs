@@ -52,3 +52,120 @@ impl<'de> Visitor<'de> for FreeInfoVisitor {
         Ok(FreeInfo(None))
     }
 }
+
+// ============================================================================
+// 数値パース
+// ============================================================================
+
+/// 数値文字列をf64に変換
+///
+/// 前後の空白を除去してからパースする。要素なし・空要素・数値でない値はNoneを返す
+pub fn parse_f64(value: Option<&str>) -> Option<f64> {
+    let trimmed = value?.trim();
+    if trimmed.is_empty() {
+        return None;
+    }
+    trimmed.parse::<f64>().ok().filter(|v| v.is_finite())
+}
+
+/// CSVの項目（カンマ・引用符・改行を含む場合は引用符で囲む。RFC 4180）
+pub(crate) fn csv_field(value: &str) -> String {
+    if value.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value.to_string()
+    }
+}
+
+// ============================================================================
+// ボーリング基本項目
+// ============================================================================
+
+/// ボーリング名・総掘進長を取得するためのトレイト
+pub trait BoringHeader {
+    /// ボーリング名
+    fn boring_name(&self) -> Option<&str>;
+
+    /// 総掘進長 (m)
+    fn total_length(&self) -> Option<f64>;
+}
+
+impl BoringHeader for crate::boring_structs_400::Boring400 {
+    fn boring_name(&self) -> Option<&str> {
+        self.title.basic_info.boring_name.as_deref()
+    }
+
+    fn total_length(&self) -> Option<f64> {
+        parse_f64(self.title.boring_basic_info.total_drilling_length.as_deref())
+    }
+}
+
+impl BoringHeader for crate::boring_structs_300::Boring300 {
+    fn boring_name(&self) -> Option<&str> {
+        self.title.basic_info.boring_name.as_deref()
+    }
+
+    fn total_length(&self) -> Option<f64> {
+        parse_f64(self.title.boring_basic_info.total_length.as_deref())
+    }
+}
+
+impl BoringHeader for crate::boring_structs_210::Boring210 {
+    fn boring_name(&self) -> Option<&str> {
+        self.title.basic_info.boring_name.as_deref()
+    }
+
+    fn total_length(&self) -> Option<f64> {
+        parse_f64(self.title.boring_basic_info.total_length.as_deref())
+    }
+}
+
+impl BoringHeader for crate::boring_structs_200::Boring200 {
+    fn boring_name(&self) -> Option<&str> {
+        self.title.basic_info.boring_name.as_deref()
+    }
+
+    fn total_length(&self) -> Option<f64> {
+        parse_f64(self.title.boring_basic_info.total_length.as_deref())
+    }
+}
+
+impl BoringHeader for crate::boring_structs_110::Boring110 {
+    fn boring_name(&self) -> Option<&str> {
+        self.title.basic_info.boring_name.as_deref()
+    }
+
+    fn total_length(&self) -> Option<f64> {
+        parse_f64(self.title.boring_basic_info.total_length.as_deref())
+    }
+}
+
+// ============================================================================
+// 素集合
+// ============================================================================
+
+/// 要素の組を連結してまとまりにする素集合（union-find）
+pub(crate) struct DisjointSets {
+    parent: Vec<usize>,
+}
+
+impl DisjointSets {
+    pub(crate) fn new(len: usize) -> Self {
+        Self { parent: (0..len).collect() }
+    }
+
+    /// 要素が属するまとまりの代表（まとまりの中で最小の番号）
+    pub(crate) fn find(&mut self, mut i: usize) -> usize {
+        while self.parent[i] != i {
+            self.parent[i] = self.parent[self.parent[i]];
+            i = self.parent[i];
+        }
+        i
+    }
+
+    /// 2つの要素のまとまりを連結
+    pub(crate) fn union(&mut self, a: usize, b: usize) {
+        let (a, b) = (self.find(a), self.find(b));
+        self.parent[a.max(b)] = a.min(b);
+    }
+}
@@ -0,0 +1,97 @@
+//! JGD2011 → WGS84 変換モジュール
+//!
+//! `proj` フィーチャー（既定で有効）ではPROJの `EPSG:6668` → `EPSG:4326` 変換を使う。
+//! 無効にすると、システムのPROJライブラリなしで組み込みの変換を使う
+//! （静的リンク・WASM・クロスビルド向け）。
+//!
+//! # 組み込みの変換
+//! JGD2011（ITRF2008準拠、GRS80楕円体）とWGS84の差は数cm程度で、PROJでも
+//! `EPSG:6668` → `EPSG:4326` は座標を変えない変換（パラメータ0）として扱われる。
+//! 組み込みの変換も同じく座標をそのまま返す。
+
+use crate::transform::TransformError;
+
+/// 組み込みのJGD2011 → WGS84 変換
+///
+/// # Arguments
+/// * `lng` - JGD2011の経度 (度)
+/// * `lat` - JGD2011の緯度 (度)
+///
+/// # Returns
+/// WGS84の (経度, 緯度)
+pub fn builtin_jgd2011_to_wgs84(lng: f64, lat: f64) -> (f64, f64) {
+    (lng, lat)
+}
+
+/// JGD2011の (経度, 緯度) をWGS84に変換
+#[cfg(feature = "proj")]
+pub(crate) fn jgd2011_to_wgs84(lng: f64, lat: f64) -> Result<(f64, f64), TransformError> {
+    with_proj(|proj| proj.convert((lng, lat)))?
+        .map_err(|e| TransformError::Transform(format!("proj: {}", e)))
+}
+
+/// JGD2011の (経度, 緯度) をWGS84に変換
+#[cfg(not(feature = "proj"))]
+pub(crate) fn jgd2011_to_wgs84(lng: f64, lat: f64) -> Result<(f64, f64), TransformError> {
+    Ok(builtin_jgd2011_to_wgs84(lng, lat))
+}
+
+/// 変換が使えることを確認する（PROJの初期化に失敗した場合はエラー）
+#[cfg(feature = "proj")]
+pub(crate) fn ensure_available() -> Result<(), TransformError> {
+    with_proj(|_| ())
+}
+
+/// 変換が使えることを確認する（組み込みの変換は常に使える）
+#[cfg(not(feature = "proj"))]
+pub(crate) fn ensure_available() -> Result<(), TransformError> {
+    Ok(())
+}
+
+#[cfg(feature = "proj")]
+thread_local! {
+    /// JGD2011 → WGS84 変換器
+    ///
+    /// projの変換器はスレッド間で共有できないため、スレッドごとに初回使用時に作成して
+    /// 使い回す。これにより `JgdTransformer` は複数スレッドから同時に使える。
+    static JGD2011_TO_WGS84: std::cell::RefCell<Option<proj::Proj>> =
+        const { std::cell::RefCell::new(None) };
+}
+
+/// このスレッドのJGD2011 → WGS84 変換器で処理する
+#[cfg(feature = "proj")]
+fn with_proj<R>(f: impl FnOnce(&proj::Proj) -> R) -> Result<R, TransformError> {
+    JGD2011_TO_WGS84.with(|cell| {
+        let mut cell = cell.borrow_mut();
+        if cell.is_none() {
+            let proj = proj::Proj::new_known_crs("EPSG:6668", "EPSG:4326", None)
+                .map_err(|e| TransformError::Transform(format!("proj init error: {}", e)))?;
+            *cell = Some(proj);
+        }
+        Ok(f(cell.as_ref().expect("initialized above")))
+    })
+}
+
+#[cfg(all(test, feature = "proj"))]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_builtin_agrees_with_proj() {
+        // 稚内・札幌・東京・福岡・那覇・南鳥島
+        let points = [
+            (141.6731, 45.4156),
+            (141.3544, 43.0621),
+            (139.7671, 35.6812),
+            (130.4017, 33.5902),
+            (127.6809, 26.2124),
+            (153.9811, 24.2867),
+        ];
+        for (lng, lat) in points {
+            let (proj_lng, proj_lat) = jgd2011_to_wgs84(lng, lat).unwrap();
+            let (lng2, lat2) = builtin_jgd2011_to_wgs84(lng, lat);
+            // 1e-8度 ≒ 1mm
+            assert!((proj_lng - lng2).abs() < 1e-8 && (proj_lat - lat2).abs() < 1e-8);
+        }
+    }
+}